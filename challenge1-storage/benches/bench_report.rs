@@ -0,0 +1,260 @@
+//! Format comparison report: formats x models x payload sizes.
+//!
+//! Prints one row per combination with encode/decode time, encoded size and
+//! heap allocations per operation, as a Markdown table (default) or CSV:
+//!
+//! ```text
+//! cargo bench -p challenge1-storage --bench bench_report
+//! cargo bench -p challenge1-storage --bench bench_report -- --csv
+//! cargo bench -p challenge1-storage --bench bench_report -- --quick
+//! ```
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use challenge1_storage::{Borsh, Json, JsonLines, Person, Serializer, StorageCompatible, Wincode};
+use serde::{Deserialize, Serialize};
+
+/// System allocator wrapper that counts allocation calls.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Numeric-heavy model: a fixed header plus a long vector of integers.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    wincode::SchemaWrite,
+    wincode::SchemaRead,
+)]
+struct Metrics {
+    id: u64,
+    samples: Vec<u32>,
+}
+
+/// Nested model: many small `Person` records.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    BorshSerialize,
+    BorshDeserialize,
+    wincode::SchemaWrite,
+    wincode::SchemaRead,
+)]
+struct Roster {
+    people: Vec<Person>,
+}
+
+/// Builds each model so its Borsh encoding is roughly `bytes` long.
+fn person(bytes: usize) -> Person {
+    Person {
+        name: "a".repeat(bytes),
+        age: 30,
+    }
+}
+
+fn metrics(bytes: usize) -> Metrics {
+    Metrics {
+        id: 7,
+        samples: (0..bytes / 4).map(|n| n as u32).collect(),
+    }
+}
+
+fn roster(bytes: usize) -> Roster {
+    // "Person 0000" + age + length prefix is about 16 bytes per entry.
+    Roster {
+        people: (0..bytes / 16)
+            .map(|n| Person {
+                name: format!("Person {n:04}"),
+                age: (n % 100) as u8,
+            })
+            .collect(),
+    }
+}
+
+struct Row {
+    format: &'static str,
+    model: &'static str,
+    size: &'static str,
+    encoded_bytes: usize,
+    encode: Duration,
+    decode: Duration,
+    encode_allocs: usize,
+    decode_allocs: usize,
+}
+
+/// Runs `op` repeatedly for at least `budget` and returns the mean duration.
+fn time_op(budget: Duration, mut op: impl FnMut()) -> Duration {
+    // Warm up caches and the allocator before measuring.
+    op();
+
+    let start = Instant::now();
+    let mut iterations = 0u32;
+    while start.elapsed() < budget || iterations < 3 {
+        op();
+        iterations += 1;
+    }
+    start.elapsed() / iterations
+}
+
+/// Counts allocations made by a single call of `op`.
+fn count_allocs(mut op: impl FnMut()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    op();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+fn measure<S, T>(
+    format: (&'static str, &S),
+    model: &'static str,
+    size: &'static str,
+    value: &T,
+    budget: Duration,
+) -> Row
+where
+    S: Serializer,
+    T: StorageCompatible,
+{
+    let (format_name, serializer) = format;
+    let bytes = serializer.to_bytes(value).expect("serialize for report");
+
+    let encode = || {
+        black_box(serializer.to_bytes(black_box(value)).expect("serialize"));
+    };
+    let decode = || {
+        black_box(
+            serializer
+                .from_bytes::<T>(black_box(&bytes))
+                .expect("deserialize"),
+        );
+    };
+
+    Row {
+        format: format_name,
+        model,
+        size,
+        encoded_bytes: bytes.len(),
+        encode_allocs: count_allocs(encode),
+        decode_allocs: count_allocs(decode),
+        encode: time_op(budget, encode),
+        decode: time_op(budget, decode),
+    }
+}
+
+/// Measures one model value against every format.
+fn measure_formats<T>(
+    rows: &mut Vec<Row>,
+    model: &'static str,
+    size: &'static str,
+    value: &T,
+    budget: Duration,
+) where
+    T: StorageCompatible,
+{
+    rows.push(measure(("borsh", &Borsh), model, size, value, budget));
+    rows.push(measure(("wincode", &Wincode), model, size, value, budget));
+    rows.push(measure(("json", &Json), model, size, value, budget));
+    rows.push(measure(("jsonl", &JsonLines), model, size, value, budget));
+}
+
+fn print_markdown(rows: &[Row]) {
+    println!(
+        "| format | model | payload | encoded bytes | encode (us) | decode (us) | encode allocs | decode allocs |"
+    );
+    println!("|---|---|---|---:|---:|---:|---:|---:|");
+    for row in rows {
+        println!(
+            "| {} | {} | {} | {} | {:.2} | {:.2} | {} | {} |",
+            row.format,
+            row.model,
+            row.size,
+            row.encoded_bytes,
+            micros(row.encode),
+            micros(row.decode),
+            row.encode_allocs,
+            row.decode_allocs
+        );
+    }
+}
+
+fn print_csv(rows: &[Row]) {
+    println!("format,model,payload,encoded_bytes,encode_us,decode_us,encode_allocs,decode_allocs");
+    for row in rows {
+        println!(
+            "{},{},{},{},{:.2},{:.2},{},{}",
+            row.format,
+            row.model,
+            row.size,
+            row.encoded_bytes,
+            micros(row.encode),
+            micros(row.decode),
+            row.encode_allocs,
+            row.decode_allocs
+        );
+    }
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1_000_000.0
+}
+
+fn main() {
+    // `cargo bench` appends `--bench`; only our own flags matter here.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let csv = args.iter().any(|arg| arg == "--csv");
+    let quick = args.iter().any(|arg| arg == "--quick");
+
+    let budget = if quick {
+        Duration::from_millis(20)
+    } else {
+        Duration::from_millis(200)
+    };
+    let payload_sizes = [
+        ("1kb", 1024usize),
+        ("64kb", 64 * 1024usize),
+        ("1mb", 1024 * 1024usize),
+    ];
+
+    let mut rows = Vec::new();
+    for (size, bytes) in payload_sizes {
+        measure_formats(&mut rows, "person", size, &person(bytes), budget);
+        measure_formats(&mut rows, "metrics", size, &metrics(bytes), budget);
+        measure_formats(&mut rows, "roster", size, &roster(bytes), budget);
+    }
+
+    if csv {
+        print_csv(&rows);
+    } else {
+        print_markdown(&rows);
+    }
+}
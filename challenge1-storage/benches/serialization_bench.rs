@@ -37,6 +37,41 @@ fn bench_serialize(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_serialize_into(c: &mut Criterion) {
+    let person = sample_person();
+    let borsh = Borsh;
+    let wincode = Wincode;
+    let json = Json;
+
+    // Same payload as `serialize_person`, but every iteration reuses one buffer.
+    let mut group = c.benchmark_group("serialize_person_into");
+    let mut buf = Vec::new();
+    group.bench_function("borsh", |b| {
+        b.iter(|| {
+            borsh
+                .to_bytes_into(black_box(&person), &mut buf)
+                .expect("borsh serialize into");
+            black_box(buf.len())
+        })
+    });
+    group.bench_function("wincode", |b| {
+        b.iter(|| {
+            wincode
+                .to_bytes_into(black_box(&person), &mut buf)
+                .expect("wincode serialize into");
+            black_box(buf.len())
+        })
+    });
+    group.bench_function("json", |b| {
+        b.iter(|| {
+            json.to_bytes_into(black_box(&person), &mut buf)
+                .expect("json serialize into");
+            black_box(buf.len())
+        })
+    });
+    group.finish();
+}
+
 fn bench_deserialize(c: &mut Criterion) {
     let person = sample_person();
     let borsh = Borsh;
@@ -77,6 +112,61 @@ fn bench_deserialize(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_encoded_len(c: &mut Criterion) {
+    let person = sample_person();
+    let borsh = Borsh;
+    let wincode = Wincode;
+    let json = Json;
+
+    // Compare the structural size calculation against serialize-and-measure.
+    let mut group = c.benchmark_group("encoded_len_person");
+    group.bench_function("borsh", |b| {
+        b.iter(|| black_box(borsh.encoded_len(black_box(&person)).expect("borsh len")))
+    });
+    group.bench_function("borsh_to_bytes_len", |b| {
+        b.iter(|| {
+            black_box(
+                borsh
+                    .to_bytes(black_box(&person))
+                    .expect("borsh serialize")
+                    .len(),
+            )
+        })
+    });
+    group.bench_function("wincode", |b| {
+        b.iter(|| {
+            black_box(
+                wincode
+                    .encoded_len(black_box(&person))
+                    .expect("wincode len"),
+            )
+        })
+    });
+    group.bench_function("wincode_to_bytes_len", |b| {
+        b.iter(|| {
+            black_box(
+                wincode
+                    .to_bytes(black_box(&person))
+                    .expect("wincode serialize")
+                    .len(),
+            )
+        })
+    });
+    group.bench_function("json", |b| {
+        b.iter(|| black_box(json.encoded_len(black_box(&person)).expect("json len")))
+    });
+    group.bench_function("json_to_bytes_len", |b| {
+        b.iter(|| {
+            black_box(
+                json.to_bytes(black_box(&person))
+                    .expect("json serialize")
+                    .len(),
+            )
+        })
+    });
+    group.finish();
+}
+
 fn bench_storage_save_load(c: &mut Criterion) {
     let person = sample_person();
 
@@ -185,7 +275,9 @@ fn bench_storage_convert(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_serialize,
+    bench_serialize_into,
     bench_deserialize,
+    bench_encoded_len,
     bench_storage_save_load,
     bench_storage_convert
 );
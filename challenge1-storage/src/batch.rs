@@ -0,0 +1,177 @@
+use crate::StorageError;
+
+/// Magic bytes at the start of every batch container.
+pub const BATCH_MAGIC: [u8; 4] = *b"SBAT";
+/// Current container layout version.
+pub const BATCH_VERSION: u8 = 1;
+
+const FLAG_INDEXED: u8 = 0b0000_0001;
+// magic (4) + version (1) + flags (1) + record count (4)
+const HEADER_LEN: usize = 10;
+const LEN_PREFIX: usize = 4;
+const INDEX_ENTRY: usize = 8;
+
+/// Whether a batch container carries an offset index for random access.
+///
+/// Layout (all integers little-endian):
+///
+/// ```text
+/// "SBAT" | version: u8 | flags: u8 | count: u32
+/// [index: count x u64 offset into the record section]   (Indexed only)
+/// record section: count x (len: u32 | payload)
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// Write an offset table so `decode_nth` can jump straight to a record.
+    Indexed,
+    /// Length prefixes only; `decode_nth` skips records by their prefixes.
+    Sequential,
+}
+
+/// Parsed container header plus the byte ranges it points at.
+pub(crate) struct BatchLayout<'a> {
+    pub(crate) count: usize,
+    index: Option<&'a [u8]>,
+    records: &'a [u8],
+}
+
+/// Returns how many records a batch container holds without decoding any of them.
+pub fn record_count(bytes: &[u8]) -> Result<usize, StorageError> {
+    BatchLayout::parse(bytes).map(|layout| layout.count)
+}
+
+/// Writes the header and, when indexed, a zeroed offset table to fill in later.
+pub(crate) fn write_header(
+    buf: &mut Vec<u8>,
+    count: usize,
+    framing: Framing,
+) -> Result<(), StorageError> {
+    let count = u32::try_from(count)
+        .map_err(|_| StorageError::Batch(format!("too many records: {count}")))?;
+    let flags = match framing {
+        Framing::Indexed => FLAG_INDEXED,
+        Framing::Sequential => 0,
+    };
+
+    buf.extend_from_slice(&BATCH_MAGIC);
+    buf.push(BATCH_VERSION);
+    buf.push(flags);
+    buf.extend_from_slice(&count.to_le_bytes());
+    if framing == Framing::Indexed {
+        buf.resize(buf.len() + count as usize * INDEX_ENTRY, 0);
+    }
+    Ok(())
+}
+
+/// Offset of the record section inside a container written by `write_header`.
+pub(crate) fn records_start(count: usize, framing: Framing) -> usize {
+    match framing {
+        Framing::Indexed => HEADER_LEN + count * INDEX_ENTRY,
+        Framing::Sequential => HEADER_LEN,
+    }
+}
+
+/// Records the start of record `position` in the index table.
+pub(crate) fn write_index_entry(buf: &mut [u8], position: usize, offset: usize) {
+    let at = HEADER_LEN + position * INDEX_ENTRY;
+    buf[at..at + INDEX_ENTRY].copy_from_slice(&(offset as u64).to_le_bytes());
+}
+
+/// Reserves a length prefix and returns where it lives so it can be patched.
+pub(crate) fn begin_record(buf: &mut Vec<u8>) -> usize {
+    let at = buf.len();
+    buf.extend_from_slice(&[0; LEN_PREFIX]);
+    at
+}
+
+/// Patches the length prefix written by `begin_record` once the payload is known.
+pub(crate) fn finish_record(buf: &mut [u8], prefix_at: usize) -> Result<(), StorageError> {
+    let payload_len = buf.len() - prefix_at - LEN_PREFIX;
+    let payload_len = u32::try_from(payload_len)
+        .map_err(|_| StorageError::Batch(format!("record too large: {payload_len} bytes")))?;
+    buf[prefix_at..prefix_at + LEN_PREFIX].copy_from_slice(&payload_len.to_le_bytes());
+    Ok(())
+}
+
+impl<'a> BatchLayout<'a> {
+    pub(crate) fn parse(bytes: &'a [u8]) -> Result<Self, StorageError> {
+        if bytes.len() < HEADER_LEN || bytes[..4] != BATCH_MAGIC {
+            return Err(StorageError::Batch(String::from("missing batch header")));
+        }
+        if bytes[4] != BATCH_VERSION {
+            return Err(StorageError::Batch(format!(
+                "unsupported batch version {}",
+                bytes[4]
+            )));
+        }
+
+        let flags = bytes[5];
+        let count = u32::from_le_bytes(read_array(bytes, 6)?) as usize;
+        let mut rest = &bytes[HEADER_LEN..];
+
+        let index = if flags & FLAG_INDEXED != 0 {
+            let index_len = count
+                .checked_mul(INDEX_ENTRY)
+                .filter(|len| *len <= rest.len())
+                .ok_or_else(|| StorageError::Batch(String::from("truncated batch index")))?;
+            let (index, records) = rest.split_at(index_len);
+            rest = records;
+            Some(index)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            count,
+            index,
+            records: rest,
+        })
+    }
+
+    /// Upper bound on records that can fit, for pre-allocating without trusting `count`.
+    pub(crate) fn capacity_hint(&self) -> usize {
+        self.count.min(self.records.len() / LEN_PREFIX)
+    }
+
+    /// Returns the payload of the record starting at `offset` and where the next one starts.
+    pub(crate) fn record_at(&self, offset: usize) -> Result<(&'a [u8], usize), StorageError> {
+        let len = u32::from_le_bytes(read_array(self.records, offset)?) as usize;
+        let start = offset + LEN_PREFIX;
+        let payload = start
+            .checked_add(len)
+            .and_then(|end| self.records.get(start..end))
+            .ok_or_else(|| StorageError::Batch(format!("truncated record at offset {offset}")))?;
+        Ok((payload, start + len))
+    }
+
+    /// Finds the payload of record `position`, via the index when there is one.
+    pub(crate) fn nth(&self, position: usize) -> Result<&'a [u8], StorageError> {
+        if position >= self.count {
+            return Err(StorageError::Batch(format!(
+                "record {position} out of range ({} records)",
+                self.count
+            )));
+        }
+
+        let offset = match self.index {
+            Some(index) => u64::from_le_bytes(read_array(index, position * INDEX_ENTRY)?) as usize,
+            None => {
+                // No index: hop over earlier records using only their length prefixes.
+                let mut offset = 0;
+                for _ in 0..position {
+                    offset = self.record_at(offset)?.1;
+                }
+                offset
+            }
+        };
+
+        self.record_at(offset).map(|(payload, _)| payload)
+    }
+}
+
+fn read_array<const N: usize>(bytes: &[u8], at: usize) -> Result<[u8; N], StorageError> {
+    at.checked_add(N)
+        .and_then(|end| bytes.get(at..end))
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or_else(|| StorageError::Batch(format!("truncated batch at offset {at}")))
+}
@@ -12,6 +12,12 @@ pub enum StorageError {
     Wincode(String),
     /// Serialization or deserialization failure from JSON.
     Json(String),
+    /// Malformed batch container or out-of-range record position.
+    Batch(String),
+    /// A JSON Lines record failed to parse; `line` is 1-based.
+    JsonLine { line: usize, message: String },
+    /// Reading from or writing to an underlying stream failed.
+    Io(String),
 }
 
 impl Display for StorageError {
@@ -21,6 +27,9 @@ impl Display for StorageError {
             Self::Borsh(err) => write!(f, "borsh error: {err}"),
             Self::Wincode(err) => write!(f, "wincode error: {err}"),
             Self::Json(err) => write!(f, "json error: {err}"),
+            Self::Batch(err) => write!(f, "batch error: {err}"),
+            Self::JsonLine { line, message } => write!(f, "json error on line {line}: {message}"),
+            Self::Io(err) => write!(f, "io error: {err}"),
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::io::{self, Write};
+
 use crate::{Serializer, StorageCompatible, StorageError};
 
 /// Borsh format adapter.
@@ -12,6 +14,15 @@ impl Serializer for Borsh {
         borsh::to_vec(value).map_err(|err| StorageError::Borsh(err.to_string()))
     }
 
+    fn to_bytes_into<T>(&self, value: &T, buf: &mut Vec<u8>) -> Result<(), StorageError>
+    where
+        T: StorageCompatible,
+    {
+        // Vec<u8> is an io::Write, so Borsh appends straight into the caller's buffer.
+        buf.clear();
+        borsh::to_writer(&mut *buf, value).map_err(|err| StorageError::Borsh(err.to_string()))
+    }
+
     fn from_bytes<T>(&self, bytes: &[u8]) -> Result<T, StorageError>
     where
         T: StorageCompatible,
@@ -19,6 +30,14 @@ impl Serializer for Borsh {
         // try_from_slice decodes bytes into T using Borsh derive rules.
         T::try_from_slice(bytes).map_err(|err| StorageError::Borsh(err.to_string()))
     }
+
+    fn encoded_len<T>(&self, value: &T) -> Result<usize, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        // object_length walks the value through a counting writer, no buffer needed.
+        borsh::object_length(value).map_err(|err| StorageError::Borsh(err.to_string()))
+    }
 }
 
 /// Wincode format adapter.
@@ -33,6 +52,16 @@ impl Serializer for Wincode {
         wincode::serialize(value).map_err(|err| StorageError::Wincode(err.to_string()))
     }
 
+    fn to_bytes_into<T>(&self, value: &T, buf: &mut Vec<u8>) -> Result<(), StorageError>
+    where
+        T: StorageCompatible,
+    {
+        // Wincode's Vec writer reserves the exact size up front, then appends.
+        buf.clear();
+        wincode::serialize_into(&mut *buf, value)
+            .map_err(|err| StorageError::Wincode(err.to_string()))
+    }
+
     fn from_bytes<T>(&self, bytes: &[u8]) -> Result<T, StorageError>
     where
         T: StorageCompatible,
@@ -40,6 +69,16 @@ impl Serializer for Wincode {
         // Deserialize with the same schema-derived type information.
         wincode::deserialize(bytes).map_err(|err| StorageError::Wincode(err.to_string()))
     }
+
+    fn encoded_len<T>(&self, value: &T) -> Result<usize, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        // Wincode sums each field's schema size without writing anything.
+        let len = wincode::serialized_size(value)
+            .map_err(|err| StorageError::Wincode(err.to_string()))?;
+        usize::try_from(len).map_err(|err| StorageError::Wincode(err.to_string()))
+    }
 }
 
 /// JSON format adapter.
@@ -54,6 +93,15 @@ impl Serializer for Json {
         serde_json::to_vec(value).map_err(|err| StorageError::Json(err.to_string()))
     }
 
+    fn to_bytes_into<T>(&self, value: &T, buf: &mut Vec<u8>) -> Result<(), StorageError>
+    where
+        T: StorageCompatible,
+    {
+        // Stream JSON text into the existing buffer instead of a fresh Vec.
+        buf.clear();
+        serde_json::to_writer(&mut *buf, value).map_err(|err| StorageError::Json(err.to_string()))
+    }
+
     fn from_bytes<T>(&self, bytes: &[u8]) -> Result<T, StorageError>
     where
         T: StorageCompatible,
@@ -61,4 +109,75 @@ impl Serializer for Json {
         // Parse JSON bytes back into T.
         serde_json::from_slice(bytes).map_err(|err| StorageError::Json(err.to_string()))
     }
+
+    fn encoded_len<T>(&self, value: &T) -> Result<usize, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        // JSON length depends on escaping and number formatting, so run the
+        // encoder against a writer that only counts bytes.
+        let mut counter = ByteCounter::default();
+        serde_json::to_writer(&mut counter, value)
+            .map_err(|err| StorageError::Json(err.to_string()))?;
+        Ok(counter.len)
+    }
+}
+
+/// JSON Lines (NDJSON) format adapter: one compact JSON document per line.
+///
+/// A single value encodes as its JSON text plus a trailing `\n`. For streams of
+/// records use [`JsonLines::writer`] and [`JsonLines::reader`].
+pub struct JsonLines;
+
+impl Serializer for JsonLines {
+    fn to_bytes<T>(&self, value: &T) -> Result<Vec<u8>, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        let mut bytes = Vec::new();
+        self.to_bytes_into(value, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn to_bytes_into<T>(&self, value: &T, buf: &mut Vec<u8>) -> Result<(), StorageError>
+    where
+        T: StorageCompatible,
+    {
+        // Compact JSON escapes embedded newlines, so the record stays on one line.
+        Json.to_bytes_into(value, buf)?;
+        buf.push(b'\n');
+        Ok(())
+    }
+
+    fn from_bytes<T>(&self, bytes: &[u8]) -> Result<T, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        // serde_json ignores the trailing newline as whitespace.
+        Json.from_bytes(bytes)
+    }
+
+    fn encoded_len<T>(&self, value: &T) -> Result<usize, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        Ok(Json.encoded_len(value)? + 1)
+    }
+}
+
+/// `io::Write` sink that discards bytes and remembers how many it saw.
+#[derive(Default)]
+struct ByteCounter {
+    len: usize,
+}
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.len += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
 }
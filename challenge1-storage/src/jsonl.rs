@@ -0,0 +1,138 @@
+use std::io::{BufRead, Write};
+use std::marker::PhantomData;
+
+use crate::{JsonLines, Serializer, StorageCompatible, StorageError};
+
+/// Streaming NDJSON writer: every `write` appends one record and a newline.
+pub struct JsonLinesWriter<W: Write> {
+    inner: W,
+    // Scratch buffer reused for every record so each line is one `write_all`.
+    buf: Vec<u8>,
+    written: usize,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    /// Wraps any `io::Write` sink (file, socket, `Vec<u8>`, ...).
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buf: Vec::new(),
+            written: 0,
+        }
+    }
+
+    /// Serializes `value` as a single line.
+    pub fn write<T>(&mut self, value: &T) -> Result<(), StorageError>
+    where
+        T: StorageCompatible,
+    {
+        JsonLines.to_bytes_into(value, &mut self.buf)?;
+        self.inner
+            .write_all(&self.buf)
+            .map_err(|err| StorageError::Io(err.to_string()))?;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Number of records written so far.
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Flushes the underlying writer.
+    pub fn flush(&mut self) -> Result<(), StorageError> {
+        self.inner
+            .flush()
+            .map_err(|err| StorageError::Io(err.to_string()))
+    }
+
+    /// Returns the underlying writer without flushing it.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Lazy NDJSON reader yielding one `Result<T, StorageError>` per non-blank line.
+///
+/// A line that fails to parse yields `StorageError::JsonLine` with its 1-based
+/// line number, and the next call to `next` carries on with the following line.
+/// An I/O error from the source ends iteration after it is reported.
+pub struct JsonLinesReader<R: BufRead, T> {
+    source: R,
+    line: usize,
+    buf: Vec<u8>,
+    failed: bool,
+    marker: PhantomData<T>,
+}
+
+impl<R: BufRead, T> JsonLinesReader<R, T> {
+    /// Reads records of type `T` from any buffered source.
+    pub fn new(source: R) -> Self {
+        Self {
+            source,
+            line: 0,
+            buf: Vec::new(),
+            failed: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// 1-based number of the last line read, or 0 before the first read.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl<R: BufRead, T> Iterator for JsonLinesReader<R, T>
+where
+    T: StorageCompatible,
+{
+    type Item = Result<T, StorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        loop {
+            self.buf.clear();
+            // read_until keeps invalid UTF-8 as a per-line parse error instead of
+            // an I/O error, so one corrupt line never stops the stream.
+            match self.source.read_until(b'\n', &mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(StorageError::Io(err.to_string())));
+                }
+            }
+
+            if self.buf.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            let line = self.line;
+            return Some(
+                serde_json::from_slice(&self.buf).map_err(|err| StorageError::JsonLine {
+                    line,
+                    message: err.to_string(),
+                }),
+            );
+        }
+    }
+}
+
+impl JsonLines {
+    /// Starts an NDJSON stream on `inner`.
+    pub fn writer<W: Write>(&self, inner: W) -> JsonLinesWriter<W> {
+        JsonLinesWriter::new(inner)
+    }
+
+    /// Lazily reads `T` records from an NDJSON stream.
+    pub fn reader<R: BufRead, T>(&self, source: R) -> JsonLinesReader<R, T>
+    where
+        T: StorageCompatible,
+    {
+        JsonLinesReader::new(source)
+    }
+}
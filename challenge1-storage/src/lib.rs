@@ -3,16 +3,20 @@
 //! The code is split by responsibility so you can follow the data flow:
 //! model type -> serializer trait -> format adapter -> generic storage.
 
+pub mod batch;
 pub mod compatible;
 pub mod error;
 pub mod formats;
+pub mod jsonl;
 pub mod models;
 pub mod serializer;
 pub mod storage;
 
+pub use batch::Framing;
 pub use compatible::StorageCompatible;
 pub use error::StorageError;
-pub use formats::{Borsh, Json, Wincode};
+pub use formats::{Borsh, Json, JsonLines, Wincode};
+pub use jsonl::{JsonLinesReader, JsonLinesWriter};
 pub use models::Person;
 pub use serializer::Serializer;
 pub use storage::Storage;
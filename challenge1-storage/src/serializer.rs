@@ -1,3 +1,4 @@
+use crate::batch::{self, BatchLayout, Framing};
 use crate::{StorageCompatible, StorageError};
 
 /// Behavior every serializer format must support for this challenge.
@@ -9,10 +10,91 @@ pub trait Serializer {
     where
         T: StorageCompatible;
 
+    /// Serialize `value` into `buf`, replacing its contents but keeping its capacity.
+    ///
+    /// Reusing one buffer across calls avoids an allocation per value on hot
+    /// paths. The default implementation falls back to `to_bytes` and copies.
+    fn to_bytes_into<T>(&self, value: &T, buf: &mut Vec<u8>) -> Result<(), StorageError>
+    where
+        T: StorageCompatible,
+    {
+        let bytes = self.to_bytes(value)?;
+        buf.clear();
+        buf.extend_from_slice(&bytes);
+        Ok(())
+    }
+
     /// Convert raw bytes back into a strongly typed value.
     ///
     /// The same format used for serialization must be used for deserialization.
+    #[allow(clippy::wrong_self_convention)]
     fn from_bytes<T>(&self, bytes: &[u8]) -> Result<T, StorageError>
     where
         T: StorageCompatible;
+
+    /// Predict how many bytes `to_bytes` would produce for `value`.
+    ///
+    /// The default implementation serializes and measures the result. Formats
+    /// override it with a cheaper calculation that skips the allocation.
+    fn encoded_len<T>(&self, value: &T) -> Result<usize, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        self.to_bytes(value).map(|bytes| bytes.len())
+    }
+
+    /// Encode a slice of values into one framed batch container.
+    ///
+    /// Each record is serialized with this format and length-prefixed, so a
+    /// reader can walk or index into the batch without a single giant `Vec<T>`.
+    fn encode_many<T>(&self, values: &[T], framing: Framing) -> Result<Vec<u8>, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        let mut buf = Vec::new();
+        batch::write_header(&mut buf, values.len(), framing)?;
+        let records_start = batch::records_start(values.len(), framing);
+
+        let mut record = Vec::new();
+        for (position, value) in values.iter().enumerate() {
+            if framing == Framing::Indexed {
+                let offset = buf.len() - records_start;
+                batch::write_index_entry(&mut buf, position, offset);
+            }
+            let prefix_at = batch::begin_record(&mut buf);
+            self.to_bytes_into(value, &mut record)?;
+            buf.extend_from_slice(&record);
+            batch::finish_record(&mut buf, prefix_at)?;
+        }
+
+        Ok(buf)
+    }
+
+    /// Decode every record of a batch container produced by `encode_many`.
+    fn decode_many<T>(&self, bytes: &[u8]) -> Result<Vec<T>, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        let layout = BatchLayout::parse(bytes)?;
+        let mut values = Vec::with_capacity(layout.capacity_hint());
+        let mut offset = 0;
+        for _ in 0..layout.count {
+            let (payload, next) = layout.record_at(offset)?;
+            values.push(self.from_bytes(payload)?);
+            offset = next;
+        }
+        Ok(values)
+    }
+
+    /// Decode only record `position` of a batch container.
+    ///
+    /// Indexed batches jump straight to the record; sequential ones skip
+    /// earlier records by their length prefixes without deserializing them.
+    fn decode_nth<T>(&self, bytes: &[u8], position: usize) -> Result<T, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        let layout = BatchLayout::parse(bytes)?;
+        self.from_bytes(layout.nth(position)?)
+    }
 }
@@ -1,4 +1,5 @@
 use std::marker::PhantomData;
+use std::mem;
 
 use crate::{Serializer, StorageCompatible, StorageError};
 
@@ -12,6 +13,8 @@ where
     serializer: S,
     // Raw payload. `None` means no value has been saved yet.
     bytes: Option<Vec<u8>>,
+    // Buffer the next save serializes into; swapped with `bytes` once it succeeds.
+    spare: Vec<u8>,
     // Zero-sized marker that keeps T in the type system.
     marker: PhantomData<T>,
 }
@@ -26,13 +29,19 @@ where
         Self {
             serializer,
             bytes: None,
+            spare: Vec::new(),
             marker: PhantomData,
         }
     }
 
     /// Serializes and stores the value bytes.
+    ///
+    /// The previous payload buffer is reused, so repeated saves of similarly
+    /// sized values do not allocate.
     pub fn save(&mut self, value: &T) -> Result<(), StorageError> {
-        self.bytes = Some(self.serializer.to_bytes(value)?);
+        self.serializer.to_bytes_into(value, &mut self.spare)?;
+        let previous = self.bytes.replace(mem::take(&mut self.spare));
+        self.spare = previous.unwrap_or_default();
         Ok(())
     }
 
@@ -47,6 +56,11 @@ where
         self.bytes.is_some()
     }
 
+    /// Size in bytes of the currently stored payload, or `None` when empty.
+    pub fn stored_len(&self) -> Option<usize> {
+        self.bytes.as_ref().map(Vec::len)
+    }
+
     /// Converts the currently stored value into storage that uses another serializer.
     pub fn convert_to<S2>(&self, serializer: S2) -> Result<Storage<T, S2>, StorageError>
     where
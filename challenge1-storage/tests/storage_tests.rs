@@ -1,4 +1,7 @@
-use challenge1_storage::{Borsh, Json, Person, Storage, Wincode};
+use challenge1_storage::{
+    Borsh, Framing, Json, JsonLines, Person, Serializer, Storage, StorageCompatible, StorageError,
+    Wincode, batch,
+};
 
 #[test]
 fn saves_and_loads_with_borsh() {
@@ -104,3 +107,279 @@ fn converts_wincode_to_borsh_and_preserves_data() {
     assert!(borsh_storage.has_data());
     assert_eq!(loaded, person);
 }
+
+#[test]
+fn encoded_len_matches_serialized_length_for_every_format() {
+    let person = Person {
+        name: "Andre \"quoted\" \u{e9}".to_string(),
+        age: 30,
+    };
+
+    let borsh_len = Borsh
+        .encoded_len(&person)
+        .expect("borsh len should succeed");
+    let wincode_len = Wincode
+        .encoded_len(&person)
+        .expect("wincode len should succeed");
+    let json_len = Json.encoded_len(&person).expect("json len should succeed");
+
+    assert_eq!(
+        borsh_len,
+        Borsh.to_bytes(&person).expect("borsh bytes").len()
+    );
+    assert_eq!(
+        wincode_len,
+        Wincode.to_bytes(&person).expect("wincode bytes").len()
+    );
+    assert_eq!(json_len, Json.to_bytes(&person).expect("json bytes").len());
+}
+
+#[test]
+fn stored_len_reports_saved_payload_size() {
+    let person = Person {
+        name: "Andre".to_string(),
+        age: 30,
+    };
+    let mut storage = Storage::<Person, Json>::new(Json);
+    assert_eq!(storage.stored_len(), None);
+
+    storage.save(&person).expect("json save should succeed");
+
+    let expected = Json.encoded_len(&person).expect("json len should succeed");
+    assert_eq!(storage.stored_len(), Some(expected));
+}
+
+#[test]
+fn to_bytes_into_matches_to_bytes_and_replaces_buffer_contents() {
+    let person = Person {
+        name: "Andre".to_string(),
+        age: 30,
+    };
+    let mut buf = vec![0xAA; 256];
+
+    Borsh
+        .to_bytes_into(&person, &mut buf)
+        .expect("borsh into should succeed");
+    assert_eq!(buf, Borsh.to_bytes(&person).expect("borsh bytes"));
+
+    Wincode
+        .to_bytes_into(&person, &mut buf)
+        .expect("wincode into should succeed");
+    assert_eq!(buf, Wincode.to_bytes(&person).expect("wincode bytes"));
+
+    Json.to_bytes_into(&person, &mut buf)
+        .expect("json into should succeed");
+    assert_eq!(buf, Json.to_bytes(&person).expect("json bytes"));
+    assert!(buf.capacity() >= 256);
+}
+
+#[test]
+fn repeated_saves_overwrite_previous_value() {
+    let first = Person {
+        name: "Andre with a much longer name".to_string(),
+        age: 30,
+    };
+    let second = Person {
+        name: "Bo".to_string(),
+        age: 41,
+    };
+    let mut storage = Storage::<Person, Borsh>::new(Borsh);
+
+    storage.save(&first).expect("first save should succeed");
+    storage.save(&second).expect("second save should succeed");
+
+    assert_eq!(storage.load().expect("load should succeed"), second);
+    assert_eq!(
+        storage.stored_len(),
+        Some(Borsh.encoded_len(&second).expect("borsh len"))
+    );
+}
+
+/// Borsh that refuses values encoding to more bytes than the cap.
+struct Capped(usize);
+
+impl Serializer for Capped {
+    fn to_bytes<T>(&self, value: &T) -> Result<Vec<u8>, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        let bytes = Borsh.to_bytes(value)?;
+        if bytes.len() > self.0 {
+            return Err(StorageError::Borsh(format!(
+                "{} bytes is over the cap",
+                bytes.len()
+            )));
+        }
+        Ok(bytes)
+    }
+
+    fn from_bytes<T>(&self, bytes: &[u8]) -> Result<T, StorageError>
+    where
+        T: StorageCompatible,
+    {
+        Borsh.from_bytes(bytes)
+    }
+}
+
+#[test]
+fn failed_save_keeps_previous_value() {
+    let kept = Person {
+        name: "Andre".to_string(),
+        age: 30,
+    };
+    let too_long = Person {
+        name: "A name longer than the cap allows".to_string(),
+        age: 41,
+    };
+    let mut storage = Storage::<Person, Capped>::new(Capped(16));
+
+    storage.save(&kept).expect("first save should succeed");
+    assert!(storage.save(&too_long).is_err());
+
+    assert_eq!(storage.load().expect("load should succeed"), kept);
+    assert_eq!(
+        storage.stored_len(),
+        Some(Borsh.encoded_len(&kept).expect("borsh len"))
+    );
+}
+
+fn sample_people(count: u8) -> Vec<Person> {
+    (0..count)
+        .map(|age| Person {
+            name: format!("Person {age}"),
+            age,
+        })
+        .collect()
+}
+
+#[test]
+fn encode_many_round_trips_with_every_format_and_framing() {
+    let people = sample_people(5);
+
+    for framing in [Framing::Indexed, Framing::Sequential] {
+        let borsh_bytes = Borsh
+            .encode_many(&people, framing)
+            .expect("borsh batch encode should succeed");
+        let wincode_bytes = Wincode
+            .encode_many(&people, framing)
+            .expect("wincode batch encode should succeed");
+        let json_bytes = Json
+            .encode_many(&people, framing)
+            .expect("json batch encode should succeed");
+
+        assert_eq!(batch::record_count(&borsh_bytes).expect("count"), 5);
+        assert_eq!(
+            Borsh
+                .decode_many::<Person>(&borsh_bytes)
+                .expect("borsh decode"),
+            people
+        );
+        assert_eq!(
+            Wincode
+                .decode_many::<Person>(&wincode_bytes)
+                .expect("wincode decode"),
+            people
+        );
+        assert_eq!(
+            Json.decode_many::<Person>(&json_bytes)
+                .expect("json decode"),
+            people
+        );
+    }
+}
+
+#[test]
+fn decode_nth_reads_single_record_with_and_without_index() {
+    let people = sample_people(4);
+    let indexed = Wincode
+        .encode_many(&people, Framing::Indexed)
+        .expect("indexed encode should succeed");
+    let sequential = Wincode
+        .encode_many(&people, Framing::Sequential)
+        .expect("sequential encode should succeed");
+
+    for (position, person) in people.iter().enumerate() {
+        let from_indexed: Person = Wincode
+            .decode_nth(&indexed, position)
+            .expect("indexed decode_nth should succeed");
+        let from_sequential: Person = Wincode
+            .decode_nth(&sequential, position)
+            .expect("sequential decode_nth should succeed");
+
+        assert_eq!(&from_indexed, person);
+        assert_eq!(&from_sequential, person);
+    }
+    assert!(indexed.len() > sequential.len());
+}
+
+#[test]
+fn decode_nth_rejects_out_of_range_and_truncated_batches() {
+    let people = sample_people(2);
+    let bytes = Borsh
+        .encode_many(&people, Framing::Indexed)
+        .expect("encode should succeed");
+
+    let out_of_range = Borsh.decode_nth::<Person>(&bytes, 2);
+    assert!(matches!(out_of_range, Err(StorageError::Batch(_))));
+
+    let truncated = Borsh.decode_many::<Person>(&bytes[..bytes.len() - 1]);
+    assert!(matches!(truncated, Err(StorageError::Batch(_))));
+
+    let not_a_batch = Borsh.decode_many::<Person>(b"nope");
+    assert!(matches!(not_a_batch, Err(StorageError::Batch(_))));
+}
+
+#[test]
+fn json_lines_writer_and_reader_round_trip() {
+    let people = sample_people(3);
+    let mut writer = JsonLines.writer(Vec::new());
+    for person in &people {
+        writer.write(person).expect("jsonl write should succeed");
+    }
+    assert_eq!(writer.written(), 3);
+
+    let bytes = writer.into_inner();
+    assert_eq!(bytes.iter().filter(|byte| **byte == b'\n').count(), 3);
+
+    let loaded: Vec<Person> = JsonLines
+        .reader(bytes.as_slice())
+        .collect::<Result<_, _>>()
+        .expect("jsonl read should succeed");
+    assert_eq!(loaded, people);
+}
+
+#[test]
+fn json_lines_reader_reports_line_numbers_and_resumes() {
+    let input = b"{\"name\":\"Andre\",\"age\":30}\n\nnot json\n{\"name\":\"Bo\",\"age\":41}\n";
+    let mut reader = JsonLines.reader::<_, Person>(&input[..]);
+
+    let first = reader.next().expect("first line").expect("first parses");
+    assert_eq!(first.name, "Andre");
+
+    match reader.next().expect("bad line") {
+        Err(StorageError::JsonLine { line, .. }) => assert_eq!(line, 3),
+        other => panic!("expected JsonLine error, got {other:?}"),
+    }
+
+    let last = reader.next().expect("last line").expect("last parses");
+    assert_eq!(last.name, "Bo");
+    assert_eq!(reader.line(), 4);
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn json_lines_storage_round_trips_single_value() {
+    let person = Person {
+        name: "Andre".to_string(),
+        age: 30,
+    };
+    let mut storage = Storage::<Person, JsonLines>::new(JsonLines);
+
+    storage.save(&person).expect("jsonl save should succeed");
+
+    assert_eq!(storage.load().expect("jsonl load should succeed"), person);
+    assert_eq!(
+        storage.stored_len(),
+        Some(JsonLines.encoded_len(&person).expect("jsonl len"))
+    );
+}
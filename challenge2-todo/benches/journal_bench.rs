@@ -0,0 +1,89 @@
+use challenge2_todo::journal::{self, journal_path};
+use challenge2_todo::undo::undo_path;
+use challenge2_todo::{Priority, Todo, TodoApp, archive, store};
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use std::path::{Path, PathBuf};
+use std::{env, fs, process};
+
+const STORE_SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn sample_todo(id: u64) -> Todo {
+    Todo {
+        id,
+        description: format!("Benchmark task {id}"),
+        created_at: 1_700_000_000 + id,
+        priority: Priority::Normal,
+        due_at: None,
+        project: Some("bench".to_string()),
+        tags: vec!["load".to_string()],
+        recurrence: None,
+        subtasks: Vec::new(),
+        blocked_by: Vec::new(),
+        metadata: Vec::new(),
+        notes: None,
+        attachments: Vec::new(),
+        time_entries: Vec::new(),
+    }
+}
+
+/// Writes a store already holding `size` tasks, as if a long session had compacted it.
+fn prefilled_store(size: usize) -> PathBuf {
+    let path = env::temp_dir().join(format!("challenge2_bench_{}_{size}.bin", process::id()));
+    cleanup(&path);
+
+    let todos: Vec<Todo> = (1..=size as u64).map(sample_todo).collect();
+    let bytes = store::encode(&todos, 1).expect("encode prefilled store");
+    fs::write(&path, bytes).expect("write prefilled store");
+    journal::reset(&journal_path(&path), 1).expect("start journal");
+    path
+}
+
+fn cleanup(path: &Path) {
+    let _ = fs::remove_file(archive::archive_path(path));
+    let _ = fs::remove_file(journal_path(path));
+    let _ = fs::remove_file(undo_path(path));
+    let _ = fs::remove_file(path);
+}
+
+// Each mutation appends one record to the journal, so per-operation time stays
+// flat as the store grows; snapshots are spread over as many operations as the
+// store holds tasks.
+fn bench_add_task(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add_task");
+    for size in STORE_SIZES {
+        let path = prefilled_store(size);
+        let mut app = TodoApp::load_or_new(&path).expect("load prefilled store");
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                black_box(
+                    app.add_task(black_box("Benchmark add".to_string()))
+                        .expect("add task"),
+                )
+            })
+        });
+        cleanup(&path);
+    }
+    group.finish();
+}
+
+fn bench_edit_task(c: &mut Criterion) {
+    let mut group = c.benchmark_group("edit_task");
+    for size in STORE_SIZES {
+        let path = prefilled_store(size);
+        let mut app = TodoApp::load_or_new(&path).expect("load prefilled store");
+        let id = size as u64 / 2;
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| {
+                black_box(
+                    app.edit_description(id, black_box("Benchmark edit".to_string()))
+                        .expect("edit task"),
+                )
+            })
+        });
+        cleanup(&path);
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_add_task, bench_edit_task);
+criterion_main!(benches);
@@ -1,99 +1,1232 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
-use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf};
 
-use borsh::BorshDeserialize;
+use crate::archive::{self, ArchivedTodo, Outcome};
+use crate::dates::unix_now;
+use crate::export::TaskRecord;
+use crate::journal::{self, JournalOp};
+use crate::search::SearchIndex;
+use crate::stats::Stats;
+use crate::store;
+use crate::undo::{self, Step, UndoEntry, UndoLog};
+use crate::{DueStatus, Filter, Priority, PriorityQueue, Recurrence, Subtask, TimeEntry, Todo};
 
-use crate::{Queue, Todo};
+/// Optional attributes for a task being added; `Default` gives a plain `Normal` task.
+#[derive(Debug, Clone, Default)]
+pub struct NewTask {
+    pub priority: Priority,
+    pub due_at: Option<u64>,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+    pub recurrence: Option<Recurrence>,
+}
+
+/// How `TodoApp::import` treats pending tasks that are not in the import.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Keep them; imported tasks are added, or replace the task with the same id.
+    Merge,
+    /// Delete them into the archive, so the queue holds exactly the imported tasks.
+    Replace,
+}
+
+/// What `TodoApp::import` changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Tasks with an id the store had never seen, or with no id.
+    pub added: usize,
+    /// Pending tasks replaced by the imported task with the same id.
+    pub updated: usize,
+    /// Archived tasks brought back because the import has them pending.
+    pub restored: usize,
+    /// Finished tasks moved straight to the archive as done.
+    pub archived: usize,
+    /// Pending tasks deleted by `ImportMode::Replace`.
+    pub removed: usize,
+}
+
+/// A task found by `TodoApp::search`.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchHit<'a> {
+    pub todo: &'a Todo,
+    /// The archive entry when the task is completed or deleted; `None` while it is pending.
+    pub archived: Option<&'a ArchivedTodo>,
+    /// Higher for closer matches; only meaningful relative to other hits of the same query.
+    pub score: u32,
+}
+
+/// How `TodoApp::time_report` adds up tracked time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportBy {
+    /// One line per task, as `#3 Write spec`.
+    Task,
+    /// One line per tag, as `@urgent`; a task with several tags counts toward each.
+    Tag,
+    /// One line per project, as `+backend`.
+    Project,
+}
 
 /// Application service that wraps queue operations and disk persistence.
 pub struct TodoApp {
-    queue: Queue<Todo>,
+    queue: PriorityQueue<Todo>,
     file_path: PathBuf,
+    // Completed and deleted tasks, oldest first, persisted next to the queue file.
+    archive: Vec<ArchivedTodo>,
+    archive_path: PathBuf,
     next_id: u64,
+    // What `file_path` looked like when this process last read or wrote it.
+    disk_stamp: Option<store::FileStamp>,
+    // Mutations since the last snapshot are appended here instead of rewriting the store.
+    journal_path: PathBuf,
+    journal_stamp: Option<store::FileStamp>,
+    // Generation of the snapshot in `file_path`; only a journal with the same one is replayed.
+    generation: u64,
+    journal_ops: usize,
+    // The journal ends in a partial record, so the next commit writes a snapshot instead.
+    journal_torn: bool,
+    // Inverses of recent changes, appended to `undo_path` with every commit.
+    history: UndoLog,
+    undo_path: PathBuf,
+    // Steps appended to `undo_path` since it was last written whole.
+    undo_appended: usize,
+    // `undo_path` ends in a partial step, so the next commit rewrites it whole.
+    undo_torn: bool,
+    undo_depth: usize,
+    // Words of every pending and archived task, kept in step by `apply`.
+    index: SearchIndex,
 }
 
+/// Number of changes `TodoApp::undo` can take back unless `set_undo_depth` says otherwise.
+pub const DEFAULT_UNDO_DEPTH: usize = 100;
+
+/// Journal length, in operations, below which a snapshot is never taken.
+///
+/// Past it the journal is compacted once it holds more operations than the
+/// store holds tasks, which keeps the cost of snapshots constant per mutation.
+/// The undo history is rewritten whole by the same rule, counting its steps.
+const MIN_COMPACTION_OPS: usize = 256;
+
 impl TodoApp {
     /// Loads queue state from disk, or creates an empty queue when the file is missing.
+    ///
+    /// The last snapshot is read first, then the journal written since is
+    /// replayed on top of it. Files in an older format are upgraded in place
+    /// after their original bytes are copied to `store::backup_path`.
     pub fn load_or_new(file_path: impl Into<PathBuf>) -> io::Result<Self> {
         let file_path = file_path.into();
-        let mut queue = Queue::new();
+        adopt_legacy_siblings(&file_path)?;
+        let journal_path = journal::journal_path(&file_path);
+        let mut queue = PriorityQueue::new(Priority::ALL.len());
+        let mut generation = 0;
+        let mut legacy_bytes = None;
+
+        // Stamp before reading: a write that lands in between then shows up as a change later.
+        let disk_stamp = store::stamp(&file_path)?;
+        let journal_stamp = store::stamp(&journal_path)?;
 
         // If a previous session exists, read and restore all tasks.
-        if file_path.exists() {
+        if disk_stamp.is_some() {
             let bytes = fs::read(&file_path)?;
             if !bytes.is_empty() {
-                let todos = Vec::<Todo>::try_from_slice(&bytes)
-                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
-                for todo in todos {
-                    queue.enqueue(todo);
+                let snapshot = store::decode(&bytes)?;
+                for todo in snapshot.todos {
+                    queue.enqueue(todo.priority.level(), todo);
+                }
+                generation = snapshot.generation;
+                if snapshot.version < store::FORMAT_VERSION {
+                    legacy_bytes = Some(bytes);
                 }
             }
         }
 
+        let archive_path = archive::archive_path(&file_path);
+        let archive = archive::load(&archive_path)?;
+        let mut index = SearchIndex::default();
+        for todo in queue.iter().chain(archive.iter().map(|entry| &entry.todo)) {
+            index.insert(todo);
+        }
+        let undo_path = undo::undo_path(&file_path);
+        // Trimmed by `set_undo_depth` or the next commit, once the configured depth is known.
+        let undo = undo::load(&undo_path)?;
+
+        // Archived ids stay reserved so a restored task never collides with a new one.
         let next_id = queue
             .iter()
+            .chain(archive.iter().map(|entry| &entry.todo))
             .map(|todo| todo.id)
             .max()
             .unwrap_or(0)
             .saturating_add(1);
 
-        Ok(Self {
+        let mut app = Self {
             queue,
             file_path,
+            archive,
+            archive_path,
             next_id,
-        })
+            disk_stamp,
+            journal_path,
+            journal_stamp,
+            generation,
+            journal_ops: 0,
+            journal_torn: false,
+            history: undo.log,
+            undo_path,
+            undo_appended: undo.appended,
+            undo_torn: undo.torn,
+            undo_depth: DEFAULT_UNDO_DEPTH,
+            index,
+        };
+
+        let replay = journal::read(&app.journal_path, generation)?;
+        for op in &replay.ops {
+            app.apply(op);
+        }
+        app.journal_ops = replay.ops.len();
+        app.journal_torn = replay.torn || replay.stale;
+
+        if let Some(bytes) = legacy_bytes {
+            store::write_atomic(&store::backup_path(&app.file_path), &bytes)?;
+            app.compact()?;
+        }
+        Ok(app)
+    }
+
+    /// Re-reads the store from disk, dropping any in-memory state.
+    pub fn reload(&mut self) -> io::Result<()> {
+        let undo_depth = self.undo_depth;
+        *self = Self::load_or_new(self.file_path.clone())?;
+        self.set_undo_depth(undo_depth);
+        Ok(())
+    }
+
+    /// Keeps at most `depth` changes to undo (and as many to redo), dropping the oldest.
+    ///
+    /// A depth of 0 turns the history off. The trimmed history is saved with the next change.
+    pub fn set_undo_depth(&mut self, depth: usize) {
+        self.undo_depth = depth;
+        self.history.truncate(depth);
+    }
+
+    /// How many changes are kept to undo; `DEFAULT_UNDO_DEPTH` unless `set_undo_depth` changed it.
+    pub fn undo_depth(&self) -> usize {
+        self.undo_depth
+    }
+
+    /// Takes back the most recent change still in the undo history and persists immediately.
+    ///
+    /// Returns what the change was, such as `delete #3: Buy milk`, or `None`
+    /// when there is nothing to undo. The history is shared by every process
+    /// using the store and survives restarts.
+    pub fn undo(&mut self) -> io::Result<Option<String>> {
+        let Some(entry) = self.history.undo.last().cloned() else {
+            return Ok(None);
+        };
+        self.commit_step(entry.ops, entry.label.clone(), Step::Undo)?;
+        Ok(Some(entry.label))
+    }
+
+    /// Makes the most recently undone change again and persists immediately.
+    ///
+    /// Returns what the change was, or `None` when nothing was undone since the last change.
+    pub fn redo(&mut self) -> io::Result<Option<String>> {
+        let Some(entry) = self.history.redo.last().cloned() else {
+            return Ok(None);
+        };
+        self.commit_step(entry.ops, entry.label.clone(), Step::Redo)?;
+        Ok(Some(entry.label))
+    }
+
+    /// What `undo` would take back, most recent first.
+    pub fn undo_labels(&self) -> impl Iterator<Item = &str> {
+        self.history
+            .undo
+            .iter()
+            .rev()
+            .map(|entry| entry.label.as_str())
+    }
+
+    /// Reloads when another process has written the store since this one last read or wrote it.
+    pub fn reload_if_changed(&mut self) -> io::Result<bool> {
+        if self.is_current()? {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Folds the journal into a fresh snapshot and starts an empty journal.
+    ///
+    /// Happens on its own as the journal grows; calling it only shortens the next load.
+    pub fn compact(&mut self) -> io::Result<()> {
+        let _lock = self.lock_current()?;
+        self.write_snapshot()
     }
 
-    /// Enqueues a new task and persists immediately.
+    /// Enqueues a new task with `Normal` priority and persists immediately.
     pub fn add_task(&mut self, description: String) -> io::Result<Todo> {
+        self.add_task_with(description, NewTask::default())
+    }
+
+    /// Enqueues a new task at the given priority and persists immediately.
+    pub fn add_task_with_priority(
+        &mut self,
+        description: String,
+        priority: Priority,
+    ) -> io::Result<Todo> {
+        self.add_task_with(
+            description,
+            NewTask {
+                priority,
+                ..NewTask::default()
+            },
+        )
+    }
+
+    /// Enqueues a new task with the given attributes and persists immediately.
+    pub fn add_task_with(&mut self, description: String, options: NewTask) -> io::Result<Todo> {
         // Assign monotonic id + capture creation time.
+        let now = unix_now();
         let todo = Todo {
             id: self.next_id,
             description,
-            created_at: unix_timestamp(),
+            created_at: now,
+            priority: options.priority,
+            due_at: options.due_at,
+            project: options.project,
+            tags: options.tags,
+            recurrence: options
+                .recurrence
+                .map(|recurrence| recurrence.anchored(options.due_at.unwrap_or(now))),
+            subtasks: Vec::new(),
+            blocked_by: Vec::new(),
+            metadata: Vec::new(),
+            notes: None,
+            attachments: Vec::new(),
+            time_entries: Vec::new(),
         };
-        self.next_id = self.next_id.saturating_add(1);
-        self.queue.enqueue(todo.clone());
-        self.persist()?;
+        self.commit(vec![JournalOp::Add(todo.clone())])?;
         Ok(todo)
     }
 
-    /// Returns tasks in FIFO order.
+    /// Returns tasks in completion order: highest priority first, FIFO within a priority.
     pub fn list_tasks(&self) -> impl Iterator<Item = &Todo> {
         self.queue.iter()
     }
 
-    /// Completes the next pending task and persists immediately.
+    /// Completes the next pending task (highest priority, oldest first) and persists immediately.
+    ///
+    /// Tasks still blocked by other pending tasks are skipped, so this returns
+    /// `None` when the queue is empty or every task is blocked. The task is
+    /// moved to the archive with outcome `Done`. Completing a recurring task
+    /// enqueues its next instance.
     pub fn complete_next(&mut self) -> io::Result<Option<Todo>> {
-        let completed = self.queue.dequeue();
-        self.persist()?;
-        Ok(completed)
+        let Some(todo) = self.queue.iter().find(|todo| !self.is_blocked(todo)) else {
+            return Ok(None);
+        };
+        let todo = todo.clone();
+        self.commit(self.completion_ops(&todo))?;
+        Ok(Some(todo))
     }
 
-    /// Deletes a task at a zero-based position in FIFO order and persists immediately.
+    /// Deletes a task at a zero-based position in list order and persists immediately.
+    ///
+    /// The task is moved to the archive with outcome `Deleted`.
     pub fn delete_at(&mut self, index: usize) -> io::Result<Option<Todo>> {
-        let deleted = self.queue.remove_at(index);
-        self.persist()?;
-        Ok(deleted)
+        let Some(todo) = self.queue.iter().nth(index).cloned() else {
+            return Ok(None);
+        };
+        self.commit(vec![JournalOp::Delete {
+            id: todo.id,
+            at: unix_now(),
+        }])?;
+        Ok(Some(todo))
+    }
+
+    /// Returns pending tasks matching `filter`, in list order.
+    pub fn filter_tasks<'a>(&'a self, filter: &Filter, now: u64) -> impl Iterator<Item = &'a Todo> {
+        self.queue
+            .iter()
+            .filter(move |todo| filter.matches(todo, now))
+    }
+
+    /// Returns pending tasks whose deadline has passed, in list order.
+    pub fn overdue_tasks(&self, now: u64) -> impl Iterator<Item = &Todo> {
+        self.queue
+            .iter()
+            .filter(move |todo| todo.due_status(now) == Some(DueStatus::Overdue))
+    }
+
+    /// Returns pending tasks ordered by deadline, soonest first; tasks without one go last.
+    ///
+    /// Ties keep list order.
+    pub fn tasks_by_due(&self) -> Vec<&Todo> {
+        let mut tasks: Vec<&Todo> = self.queue.iter().collect();
+        tasks.sort_by_key(|todo| todo.due_at.unwrap_or(u64::MAX));
+        tasks
+    }
+
+    /// Returns pending tasks ordered by creation time, newest first.
+    ///
+    /// Tasks created in the same second go by id, highest first.
+    pub fn tasks_by_created(&self) -> Vec<&Todo> {
+        let mut tasks: Vec<&Todo> = self.queue.iter().collect();
+        tasks.sort_by_key(|todo| Reverse((todo.created_at, todo.id)));
+        tasks
+    }
+
+    /// Returns the pending task with the given id.
+    pub fn get(&self, id: u64) -> Option<&Todo> {
+        self.queue.iter().find(|todo| todo.id == id)
+    }
+
+    /// Zero-based position of a pending task in list order.
+    pub fn position(&self, id: u64) -> Option<usize> {
+        self.queue.iter().position(|todo| todo.id == id)
+    }
+
+    /// Completes a specific pending task, wherever it sits in the queue, and persists immediately.
+    ///
+    /// Completing a recurring task enqueues its next instance. Fails with
+    /// `InvalidInput` while the task is blocked by other pending tasks.
+    pub fn complete(&mut self, id: u64) -> io::Result<Option<Todo>> {
+        let blockers = self.pending_blockers(id);
+        if !blockers.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("task #{id} is blocked by {}", format_ids(&blockers)),
+            ));
+        }
+
+        let Some(todo) = self.get(id).cloned() else {
+            return Ok(None);
+        };
+        self.commit(self.completion_ops(&todo))?;
+        Ok(Some(todo))
+    }
+
+    /// Pending instance that was enqueued when the recurring task `completed` was finished.
+    pub fn next_in_series(&self, completed: &Todo) -> Option<&Todo> {
+        completed.recurrence?;
+        self.queue
+            .iter()
+            .filter(|todo| {
+                todo.id > completed.id
+                    && todo.recurrence == completed.recurrence
+                    && todo.description == completed.description
+            })
+            .min_by_key(|todo| todo.id)
+    }
+
+    /// Replaces a pending task's description and persists immediately.
+    pub fn edit_description(&mut self, id: u64, description: String) -> io::Result<Option<Todo>> {
+        self.update(id, |todo| todo.description = description)
+    }
+
+    /// Appends an unchecked checklist item to a pending task and persists immediately.
+    pub fn add_subtask(&mut self, id: u64, title: String) -> io::Result<Option<Todo>> {
+        self.update(id, |todo| {
+            todo.subtasks.push(Subtask { title, done: false })
+        })
+    }
+
+    /// Flips the checkbox of a task's zero-based checklist item and persists immediately.
+    pub fn toggle_subtask(&mut self, id: u64, index: usize) -> io::Result<Option<Todo>> {
+        let Some(count) = self.get(id).map(|todo| todo.subtasks.len()) else {
+            return Ok(None);
+        };
+        if index >= count {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("task #{id} has {count} subtask(s)"),
+            ));
+        }
+        self.update(id, |todo| {
+            todo.subtasks[index].done = !todo.subtasks[index].done
+        })
+    }
+
+    /// Replaces a pending task's notes and persists immediately.
+    ///
+    /// Trailing whitespace is dropped, and notes that are blank or `None` clear them.
+    pub fn set_notes(&mut self, id: u64, notes: Option<String>) -> io::Result<Option<Todo>> {
+        let notes = notes
+            .map(|notes| notes.trim_end().to_string())
+            .filter(|notes| !notes.trim_start().is_empty());
+        self.update(id, |todo| todo.notes = notes)
+    }
+
+    /// Attaches a file path or URL to a pending task and persists immediately.
+    ///
+    /// Attaching one that is already there changes nothing.
+    pub fn add_attachment(&mut self, id: u64, attachment: String) -> io::Result<Option<Todo>> {
+        self.update(id, |todo| {
+            if !todo.attachments.contains(&attachment) {
+                todo.attachments.push(attachment);
+            }
+        })
+    }
+
+    /// Removes a task's zero-based attachment and persists immediately.
+    pub fn remove_attachment(&mut self, id: u64, index: usize) -> io::Result<Option<Todo>> {
+        let Some(count) = self.get(id).map(|todo| todo.attachments.len()) else {
+            return Ok(None);
+        };
+        if index >= count {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("task #{id} has {count} attachment(s)"),
+            ));
+        }
+        self.update(id, |todo| {
+            todo.attachments.remove(index);
+        })
+    }
+
+    /// The pending task whose timer is running, if any.
+    pub fn running_timer(&self) -> Option<&Todo> {
+        self.queue
+            .iter()
+            .find(|todo| todo.running_since().is_some())
+    }
+
+    /// Starts a timer on a pending task and persists immediately.
+    ///
+    /// A timer running on another task is stopped in the same change, so one
+    /// undo takes both back and at most one timer runs at a time. Starting the
+    /// timer that is already running changes nothing.
+    pub fn start_timer(&mut self, id: u64) -> io::Result<Option<Todo>> {
+        let Some(mut todo) = self.get(id).cloned() else {
+            return Ok(None);
+        };
+        if todo.running_since().is_some() {
+            return Ok(Some(todo));
+        }
+
+        let now = unix_now();
+        todo.time_entries.push(TimeEntry {
+            started_at: now,
+            stopped_at: None,
+        });
+        let mut ops = vec![JournalOp::Edit(todo.clone())];
+        if let Some(running) = self.running_timer() {
+            let mut running = running.clone();
+            running.stop_timer(now);
+            ops.push(JournalOp::Edit(running));
+        }
+        let label = format!("start timer #{id}: {}", todo.description);
+        self.commit_step(ops, label, Step::Do)?;
+        Ok(Some(todo))
+    }
+
+    /// Stops the running timer and persists immediately; `None` when no timer is running.
+    ///
+    /// Completing or deleting a task also stops its timer.
+    pub fn stop_timer(&mut self) -> io::Result<Option<Todo>> {
+        let Some(mut todo) = self.running_timer().cloned() else {
+            return Ok(None);
+        };
+        todo.stop_timer(unix_now());
+        let label = format!("stop timer #{}: {}", todo.id, todo.description);
+        self.commit_step(vec![JournalOp::Edit(todo.clone())], label, Step::Do)?;
+        Ok(Some(todo))
+    }
+
+    /// Seconds tracked between `from` and `to` on pending and archived tasks,
+    /// grouped `by` task, tag or project, largest first.
+    ///
+    /// Labels without time are left out. Tasks without a tag or project count
+    /// toward `(no tag)` or `(no project)`; equal totals keep pending tasks
+    /// first, in queue order, then archived tasks newest first.
+    pub fn time_report(&self, from: u64, to: u64, by: ReportBy) -> Vec<(String, u64)> {
+        let tasks = self
+            .list_tasks()
+            .chain(self.history().map(|entry| &entry.todo));
+        let mut totals: Vec<(String, u64)> = Vec::new();
+        for todo in tasks {
+            let seconds = todo.time_spent(from, to);
+            if seconds == 0 {
+                continue;
+            }
+
+            let labels = match by {
+                ReportBy::Task => vec![format!("#{} {}", todo.id, todo.description)],
+                ReportBy::Tag if todo.tags.is_empty() => vec![String::from("(no tag)")],
+                ReportBy::Tag => todo.tags.iter().map(|tag| format!("@{tag}")).collect(),
+                ReportBy::Project => vec![match &todo.project {
+                    Some(project) => format!("+{project}"),
+                    None => String::from("(no project)"),
+                }],
+            };
+            for label in labels {
+                match totals.iter_mut().find(|(existing, _)| *existing == label) {
+                    Some((_, total)) => *total += seconds,
+                    None => totals.push((label, seconds)),
+                }
+            }
+        }
+        totals.sort_by_key(|(_, seconds)| Reverse(*seconds));
+        totals
+    }
+
+    /// Records that pending task `id` cannot be completed before `blocker` and persists immediately.
+    ///
+    /// Fails with `InvalidInput` when `blocker` is not pending or when the
+    /// link would close a dependency cycle.
+    pub fn add_blocker(&mut self, id: u64, blocker: u64) -> io::Result<Option<Todo>> {
+        if self.get(id).is_none() {
+            return Ok(None);
+        }
+
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+        if id == blocker {
+            return Err(invalid(format!("task #{id} cannot block itself")));
+        }
+        if self.get(blocker).is_none() {
+            return Err(invalid(format!("no pending task #{blocker}")));
+        }
+        if self.depends_on(blocker, id) {
+            return Err(invalid(format!(
+                "#{blocker} already waits on #{id}; linking them would create a cycle"
+            )));
+        }
+
+        self.update(id, |todo| {
+            if !todo.blocked_by.contains(&blocker) {
+                todo.blocked_by.push(blocker);
+            }
+        })
+    }
+
+    /// Removes a "blocked by" link from a pending task and persists immediately.
+    pub fn remove_blocker(&mut self, id: u64, blocker: u64) -> io::Result<Option<Todo>> {
+        self.update(id, |todo| todo.blocked_by.retain(|other| *other != blocker))
+    }
+
+    /// Ids of pending tasks that task `id` is still waiting on.
+    pub fn pending_blockers(&self, id: u64) -> Vec<u64> {
+        self.get(id).map_or_else(Vec::new, |todo| {
+            todo.blocked_by
+                .iter()
+                .copied()
+                .filter(|blocker| self.get(*blocker).is_some())
+                .collect()
+        })
+    }
+
+    /// Moves a pending task to a zero-based position in list order and persists immediately.
+    ///
+    /// Tasks stay within their priority level, so the position is clamped to the
+    /// range that level occupies. Returns the position the task ended up at.
+    pub fn move_to(&mut self, id: u64, position: usize) -> io::Result<Option<usize>> {
+        if self.get(id).is_none() {
+            return Ok(None);
+        }
+        self.commit(vec![JournalOp::Move {
+            id,
+            position: u64::try_from(position).unwrap_or(u64::MAX),
+        }])?;
+        Ok(self.position(id))
+    }
+
+    /// Adds tasks from an export, de-duplicated by id, and persists immediately.
+    ///
+    /// A task whose id is pending replaces that task, one whose id is archived
+    /// is restored with the imported content, and any other task is added with
+    /// its id. Records without an id get fresh ones. When an id appears more
+    /// than once, the last record wins. Finished records (with `completed_at`)
+    /// are archived as done at that time, completing the pending task with
+    /// their id if there is one; ids already archived are left alone. Fails
+    /// with `InvalidInput`, changing nothing, when a record cannot be converted.
+    pub fn import(
+        &mut self,
+        records: Vec<TaskRecord>,
+        mode: ImportMode,
+    ) -> io::Result<ImportSummary> {
+        let now = unix_now();
+        // Fresh ids start above every id in the file, so they cannot collide with later records.
+        let mut next_id = records
+            .iter()
+            .filter_map(|record| record.id)
+            .map(|id| id.saturating_add(1))
+            .fold(self.next_id, u64::max);
+
+        let mut todos: Vec<(Todo, Option<u64>)> = Vec::with_capacity(records.len());
+        let mut index_by_id = HashMap::new();
+        for (number, record) in records.into_iter().enumerate() {
+            let fresh = record.id.is_none();
+            let completed_at = record.completed_at;
+            let todo = record.into_todo(next_id, now).map_err(|err| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("task {} in import: {err}", number + 1),
+                )
+            })?;
+            if fresh {
+                next_id = next_id.saturating_add(1);
+            }
+            match index_by_id.get(&todo.id) {
+                Some(&index) => todos[index] = (todo, completed_at),
+                None => {
+                    index_by_id.insert(todo.id, todos.len());
+                    todos.push((todo, completed_at));
+                }
+            }
+        }
+
+        let mut summary = ImportSummary::default();
+        let mut ops = Vec::new();
+        if mode == ImportMode::Replace {
+            for todo in self.queue.iter() {
+                if !index_by_id.contains_key(&todo.id) {
+                    ops.push(JournalOp::Delete {
+                        id: todo.id,
+                        at: now,
+                    });
+                    summary.removed += 1;
+                }
+            }
+        }
+        for (todo, completed_at) in todos {
+            let archived = self.archive.iter().any(|entry| entry.todo.id == todo.id);
+            if let Some(at) = completed_at {
+                if archived {
+                    continue;
+                }
+                let id = todo.id;
+                if self.get(id).is_some() {
+                    ops.push(JournalOp::Edit(todo));
+                } else {
+                    ops.push(JournalOp::Add(todo));
+                }
+                ops.push(JournalOp::Complete { id, at });
+                summary.archived += 1;
+            } else if self.get(todo.id).is_some() {
+                summary.updated += 1;
+                ops.push(JournalOp::Edit(todo));
+            } else if archived {
+                summary.restored += 1;
+                ops.push(JournalOp::Restore(todo));
+            } else {
+                summary.added += 1;
+                ops.push(JournalOp::Add(todo));
+            }
+        }
+
+        if !ops.is_empty() {
+            let count = summary.added
+                + summary.updated
+                + summary.restored
+                + summary.archived
+                + summary.removed;
+            self.commit_step(ops, format!("import of {count} task(s)"), Step::Do)?;
+        }
+        Ok(summary)
+    }
+
+    /// Pending and archived tasks matching every word of `query`, best match first.
+    ///
+    /// Words match ignoring case, as a whole word, its start, any part of it,
+    /// or with a typo (see `search::MatchKind`), in any text field of a task.
+    /// Equal scores keep pending tasks first, in queue order, then archived
+    /// tasks newest first.
+    pub fn search(&self, query: &str) -> Vec<SearchHit<'_>> {
+        let scores = self.index.search(query);
+        if scores.is_empty() {
+            return Vec::new();
+        }
+
+        let pending = self.list_tasks().map(|todo| (todo, None));
+        let archived = self.history().map(|entry| (&entry.todo, Some(entry)));
+        let mut hits: Vec<SearchHit<'_>> = pending
+            .chain(archived)
+            .filter_map(|(todo, archived)| {
+                Some(SearchHit {
+                    todo,
+                    archived,
+                    score: *scores.get(&todo.id)?,
+                })
+            })
+            .collect();
+        hits.sort_by_key(|hit| Reverse(hit.score));
+        hits
+    }
+
+    /// Activity over the last `days` local days up to `now`, for the statistics view.
+    pub fn stats(&self, days: usize, now: u64) -> Stats {
+        Stats::new(self.list_tasks(), &self.archive, days, now)
+    }
+
+    /// What the store and journal looked like when last read or written.
+    ///
+    /// Every change is written to one of them, so the pair differs whenever the
+    /// tasks may have: callers can key caches of derived figures, like `stats`, on it.
+    pub fn stamps(&self) -> (Option<store::FileStamp>, Option<store::FileStamp>) {
+        (self.disk_stamp, self.journal_stamp)
+    }
+
+    /// Returns archived tasks, most recently archived first.
+    pub fn history(&self) -> impl Iterator<Item = &ArchivedTodo> {
+        self.archive.iter().rev()
+    }
+
+    /// Moves an archived task back into the pending queue, keeping its id and priority.
+    pub fn restore(&mut self, id: u64) -> io::Result<Option<Todo>> {
+        let Some(entry) = self.archive.iter().find(|entry| entry.todo.id == id) else {
+            return Ok(None);
+        };
+
+        let todo = entry.todo.clone();
+        self.commit(vec![JournalOp::Restore(todo.clone())])?;
+        Ok(Some(todo))
+    }
+
+    /// Moves the task at a zero-based position to a new priority and persists immediately.
+    ///
+    /// The task goes to the back of its new priority level.
+    pub fn set_priority_at(
+        &mut self,
+        index: usize,
+        priority: Priority,
+    ) -> io::Result<Option<Todo>> {
+        let Some(mut todo) = self.queue.iter().nth(index).cloned() else {
+            return Ok(None);
+        };
+        todo.priority = priority;
+        self.commit(vec![JournalOp::Edit(todo.clone())])?;
+        Ok(Some(todo))
     }
 
+    /// Number of pending tasks.
     pub fn len(&self) -> usize {
         self.queue.len()
     }
 
-    fn persist(&self) -> io::Result<()> {
-        // Persist as Vec<Todo> to keep on-disk format simple and stable.
+    /// Whether no tasks are pending.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    fn is_blocked(&self, todo: &Todo) -> bool {
+        todo.blocked_by
+            .iter()
+            .any(|blocker| self.get(*blocker).is_some())
+    }
+
+    /// Whether `from` waits on `target`, directly or through other tasks.
+    ///
+    /// Archived tasks are followed too: their links come back when they are restored.
+    fn depends_on(&self, from: u64, target: u64) -> bool {
+        let mut seen = HashSet::new();
+        let mut stack = vec![from];
+        while let Some(id) = stack.pop() {
+            if id == target {
+                return true;
+            }
+            if !seen.insert(id) {
+                continue;
+            }
+
+            let todo = self.get(id).or_else(|| {
+                self.archive
+                    .iter()
+                    .map(|entry| &entry.todo)
+                    .find(|todo| todo.id == id)
+            });
+            if let Some(todo) = todo {
+                stack.extend(&todo.blocked_by);
+            }
+        }
+        false
+    }
+
+    /// Applies `change` to a pending task and persists; `None` when the id is not pending.
+    fn update(&mut self, id: u64, change: impl FnOnce(&mut Todo)) -> io::Result<Option<Todo>> {
+        let Some(mut todo) = self.get(id).cloned() else {
+            return Ok(None);
+        };
+        change(&mut todo);
+        self.commit(vec![JournalOp::Edit(todo.clone())])?;
+        Ok(Some(todo))
+    }
+
+    /// Operations that complete `todo`, plus adding its next instance when it recurs.
+    fn completion_ops(&self, todo: &Todo) -> Vec<JournalOp> {
+        let now = unix_now();
+        let mut ops = vec![JournalOp::Complete {
+            id: todo.id,
+            at: now,
+        }];
+        let Some(recurrence) = todo.recurrence else {
+            return ops;
+        };
+
+        // The next instance is a new task: fresh id, fresh creation time, next due date.
+        let mut next = Todo {
+            id: self.next_id,
+            created_at: now,
+            due_at: recurrence.next_due(todo.due_at, now),
+            ..todo.clone()
+        };
+        // Each instance starts with a fresh checklist and no tracked time.
+        for subtask in &mut next.subtasks {
+            subtask.done = false;
+        }
+        next.time_entries.clear();
+        ops.push(JournalOp::Add(next));
+        ops
+    }
+
+    /// Applies one journaled mutation to the in-memory state.
+    ///
+    /// Replay may run over a snapshot that already contains the effect (a crash
+    /// between writing the archive and the queue during compaction), so every
+    /// operation leaves state that already reflects it unchanged. Returns
+    /// whether the task `op` acts on was in the state it expects, i.e. pending,
+    /// or not yet pending for an add or restore.
+    fn apply(&mut self, op: &JournalOp) -> bool {
+        let applied = self.apply_to_tasks(op);
+        let id = match op {
+            JournalOp::Add(todo) | JournalOp::Restore(todo) | JournalOp::Edit(todo) => todo.id,
+            JournalOp::Complete { id, .. }
+            | JournalOp::Delete { id, .. }
+            | JournalOp::Remove { id } => *id,
+            JournalOp::Move { .. } => return applied,
+        };
+        self.reindex(id);
+        applied
+    }
+
+    fn apply_to_tasks(&mut self, op: &JournalOp) -> bool {
+        match op {
+            JournalOp::Add(todo) | JournalOp::Restore(todo) => {
+                if matches!(op, JournalOp::Restore(_)) {
+                    self.archive.retain(|entry| entry.todo.id != todo.id);
+                }
+                self.next_id = self.next_id.max(todo.id.saturating_add(1));
+                if self.get(todo.id).is_some() {
+                    return false;
+                }
+                self.queue.enqueue(todo.priority.level(), todo.clone());
+            }
+            JournalOp::Complete { id, at } => {
+                return self.archive_pending(*id, *at, Outcome::Done);
+            }
+            JournalOp::Delete { id, at } => {
+                return self.archive_pending(*id, *at, Outcome::Deleted);
+            }
+            JournalOp::Edit(todo) => {
+                let Some(current) = self.queue.iter_mut().find(|current| current.id == todo.id)
+                else {
+                    return false;
+                };
+                if current.priority == todo.priority {
+                    *current = todo.clone();
+                } else if let Some(index) = self.position(todo.id) {
+                    // A new priority sends the task to the back of its new level.
+                    self.queue.remove_at(index);
+                    self.queue.enqueue(todo.priority.level(), todo.clone());
+                }
+            }
+            JournalOp::Remove { id } => {
+                let Some(index) = self.position(*id) else {
+                    return false;
+                };
+                self.queue.remove_at(index);
+            }
+            JournalOp::Move { id, position } => {
+                let Some(todo) = self
+                    .position(*id)
+                    .and_then(|index| self.queue.remove_at(index))
+                else {
+                    return false;
+                };
+
+                let level = todo.priority.level();
+                let range = self.queue.level_range(level);
+                let position = usize::try_from(*position).unwrap_or(usize::MAX);
+                let target = position.clamp(range.start, range.end);
+                self.queue.insert_at(level, target - range.start, todo);
+            }
+        }
+        true
+    }
+
+    /// Re-indexes the pending or archived task with this id, or drops it from the index.
+    fn reindex(&mut self, id: u64) {
+        let todo = self
+            .get(id)
+            .or_else(|| {
+                self.archive
+                    .iter()
+                    .map(|entry| &entry.todo)
+                    .find(|todo| todo.id == id)
+            })
+            .cloned();
+        match todo {
+            Some(todo) => self.index.insert(&todo),
+            None => self.index.remove(id),
+        }
+    }
+
+    /// Moves the pending task `id` to the archive; `false` when it is not pending.
+    fn archive_pending(&mut self, id: u64, at: u64, outcome: Outcome) -> bool {
+        let Some(mut todo) = self
+            .position(id)
+            .and_then(|index| self.queue.remove_at(index))
+        else {
+            return false;
+        };
+
+        let archived = self
+            .archive
+            .iter()
+            .any(|entry| entry.todo.id == id && entry.completed_at == at);
+        if !archived {
+            // A timer still running on the task stops when it leaves the queue.
+            todo.stop_timer(at);
+            self.archive.push(ArchivedTodo {
+                todo,
+                completed_at: at,
+                outcome,
+            });
+        }
+        true
+    }
+
+    /// Applies `ops` and makes them durable under the store lock.
+    ///
+    /// Usually a single append to the journal; once the journal has grown past
+    /// the size of the store, a full snapshot is written instead. Refuses to
+    /// write when another process changed the store after this one loaded it;
+    /// the caller should `reload` and retry.
+    fn commit(&mut self, ops: Vec<JournalOp>) -> io::Result<()> {
+        let label = self.describe(&ops);
+        self.commit_step(ops, label, Step::Do)
+    }
+
+    /// `commit`, recording the inverse of `ops` under `label` on the stack `step` calls for.
+    ///
+    /// An undo or redo whose operations no longer find their task as recorded,
+    /// say undoing an add after the task was completed, is refused and stays
+    /// on its stack. `ops` are applied before the store is written and taken
+    /// back again when the write fails, so memory never runs ahead of disk.
+    /// The history file is written after the store, so a crash in between can
+    /// only lose the newest step, never leave one that was not made.
+    fn commit_step(&mut self, ops: Vec<JournalOp>, label: String, step: Step) -> io::Result<()> {
+        let _lock = self.lock_current()?;
+        let limit = MIN_COMPACTION_OPS.max(self.queue.len() + self.archive.len());
+        let snapshot = self.journal_torn
+            || self.journal_stamp.is_none()
+            || self.journal_ops + ops.len() > limit;
+
+        // Each inverse is worked out against the state the operation is applied to.
+        let next_id = self.next_id;
+        let mut inverses = Vec::with_capacity(ops.len());
+        let mut all_applied = true;
+        for op in &ops {
+            inverses.push(self.inverse(op));
+            all_applied &= self.apply(op);
+        }
+        let inverse_ops: Vec<JournalOp> = inverses.into_iter().rev().flatten().collect();
+
+        let written = match step {
+            Step::Undo | Step::Redo if !all_applied => Err(io::Error::other(format!(
+                "cannot {} {label}: the task has changed since",
+                if step == Step::Undo { "undo" } else { "redo" }
+            ))),
+            _ if snapshot => self.write_snapshot(),
+            _ => journal::append(&self.journal_path, &ops),
+        };
+        if let Err(err) = written {
+            for op in &inverse_ops {
+                self.apply(op);
+            }
+            self.next_id = next_id;
+            return Err(err);
+        }
+        if !snapshot {
+            self.journal_ops += ops.len();
+            self.journal_stamp = store::stamp(&self.journal_path)?;
+        }
+
+        // Appending keeps a step's cost independent of the history before it; the
+        // file is rewritten whole once it has taken as many steps as the history holds.
+        let entry = UndoEntry {
+            label,
+            ops: inverse_ops,
+        };
+        let steps = self.history.undo.len() + self.history.redo.len();
+        let rewrite = self.undo_torn
+            || self.undo_depth == 0
+            || self.undo_appended >= MIN_COMPACTION_OPS.max(steps);
+        let appended =
+            (!rewrite).then(|| undo::append(&self.undo_path, step, &entry, self.undo_depth));
+        self.history.record(step, entry, self.undo_depth);
+        match appended {
+            Some(Ok(())) => {
+                self.undo_appended += 1;
+                Ok(())
+            }
+            // A missing file has no header to append to, so it is written whole.
+            Some(Err(err)) if err.kind() != io::ErrorKind::NotFound => {
+                self.undo_torn = true;
+                Err(err)
+            }
+            _ => {
+                undo::save(&self.undo_path, &self.history)?;
+                self.undo_appended = 0;
+                self.undo_torn = false;
+                Ok(())
+            }
+        }
+    }
+
+    /// Operations that take back `op` when applied right after it.
+    fn inverse(&self, op: &JournalOp) -> Vec<JournalOp> {
+        // Puts a task back where it is now, since re-adding it sends it to the back of its level.
+        let return_to = |id: u64| {
+            let position = self.position(id).unwrap_or(usize::MAX);
+            JournalOp::Move {
+                id,
+                position: u64::try_from(position).unwrap_or(u64::MAX),
+            }
+        };
+
+        match op {
+            JournalOp::Add(todo) if self.get(todo.id).is_none() => {
+                vec![JournalOp::Remove { id: todo.id }]
+            }
+            JournalOp::Complete { id, .. }
+            | JournalOp::Delete { id, .. }
+            | JournalOp::Remove { id } => match self.get(*id) {
+                Some(todo) if matches!(op, JournalOp::Remove { .. }) => {
+                    vec![JournalOp::Add(todo.clone()), return_to(*id)]
+                }
+                Some(todo) => vec![JournalOp::Restore(todo.clone()), return_to(*id)],
+                None => Vec::new(),
+            },
+            JournalOp::Edit(todo) => match self.get(todo.id) {
+                Some(current) => vec![JournalOp::Edit(current.clone()), return_to(todo.id)],
+                None => Vec::new(),
+            },
+            JournalOp::Move { id, .. } if self.get(*id).is_some() => vec![return_to(*id)],
+            JournalOp::Restore(todo) => {
+                let entry = self.archive.iter().find(|entry| entry.todo.id == todo.id);
+                match (self.get(todo.id), entry) {
+                    (Some(_), _) => Vec::new(),
+                    // Archive the task again with its old content, time and outcome.
+                    (None, Some(entry)) => {
+                        let (id, at) = (todo.id, entry.completed_at);
+                        let archive = match entry.outcome {
+                            Outcome::Done => JournalOp::Complete { id, at },
+                            Outcome::Deleted => JournalOp::Delete { id, at },
+                        };
+                        vec![JournalOp::Edit(entry.todo.clone()), archive]
+                    }
+                    (None, None) => vec![JournalOp::Remove { id: todo.id }],
+                }
+            }
+            JournalOp::Add(_) | JournalOp::Move { .. } => Vec::new(),
+        }
+    }
+
+    /// Short name of a change for undo messages, such as `delete #3: Buy milk`.
+    fn describe(&self, ops: &[JournalOp]) -> String {
+        let (action, id, todo) = match ops.first() {
+            Some(JournalOp::Add(todo)) => ("add", todo.id, Some(todo)),
+            Some(JournalOp::Complete { id, .. }) => ("complete", *id, None),
+            Some(JournalOp::Delete { id, .. }) => ("delete", *id, None),
+            Some(JournalOp::Edit(todo)) => ("edit", todo.id, Some(todo)),
+            Some(JournalOp::Move { id, .. }) => ("move", *id, None),
+            Some(JournalOp::Restore(todo)) => ("restore", todo.id, Some(todo)),
+            Some(JournalOp::Remove { id }) => ("remove", *id, None),
+            None => return String::from("nothing"),
+        };
+        match todo.or_else(|| self.get(id)) {
+            Some(todo) => format!("{action} #{id}: {}", todo.description),
+            None => format!("{action} #{id}"),
+        }
+    }
+
+    /// Takes the store lock, failing when the files changed since this process last saw them.
+    fn lock_current(&self) -> io::Result<store::StoreLock> {
+        let lock = store::StoreLock::acquire(&self.file_path)?;
+        if !self.is_current()? {
+            return Err(io::Error::other(format!(
+                "{} was changed by another process; reload and try again",
+                self.file_path.display()
+            )));
+        }
+        Ok(lock)
+    }
+
+    fn is_current(&self) -> io::Result<bool> {
+        Ok(store::stamp(&self.file_path)? == self.disk_stamp
+            && store::stamp(&self.journal_path)? == self.journal_stamp)
+    }
+
+    /// Writes the archive, the queue, then an empty journal for the next generation.
+    ///
+    /// Must run under the store lock. A crash before the queue is replaced
+    /// leaves the old snapshot and journal, whose replay is idempotent; a crash
+    /// after it leaves a journal from the previous generation, which is ignored.
+    fn write_snapshot(&mut self) -> io::Result<()> {
+        let generation = self.generation.wrapping_add(1);
+        archive::save(&self.archive_path, &self.archive)?;
+
+        // Persist as a versioned Vec<Todo> so future fields can be migrated.
         let todos: Vec<Todo> = self.queue.iter().cloned().collect();
-        let bytes = borsh::to_vec(&todos)
-            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
-        fs::write(&self.file_path, bytes)
+        store::write_atomic(&self.file_path, &store::encode(&todos, generation)?)?;
+        journal::reset(&self.journal_path, generation)?;
+
+        self.generation = generation;
+        self.journal_ops = 0;
+        self.journal_torn = false;
+        self.disk_stamp = store::stamp(&self.file_path)?;
+        self.journal_stamp = store::stamp(&self.journal_path)?;
+        Ok(())
     }
 }
 
-fn unix_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_or(0, |duration| duration.as_secs())
+/// `#1, #2` list used in blocker messages.
+pub fn format_ids(ids: &[u64]) -> String {
+    ids.iter()
+        .map(|id| format!("#{id}"))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Moves the journal, archive and undo history of the store at `path` from the
+/// names older builds gave them (`todos.journal`, `todos.archive.bin`,
+/// `todos.undo`) to the current ones, unless a file already has the new name.
+///
+/// Older builds only named stores `.bin`, so a store with any other extension
+/// has no old names to adopt. The old names dropped the extension, so stores
+/// differing only by extension shared them; whichever loads first takes them over.
+fn adopt_legacy_siblings(path: &Path) -> io::Result<()> {
+    if path.extension().is_none_or(|extension| extension != "bin") {
+        return Ok(());
+    }
+    let moves = [
+        (path.with_extension("journal"), journal::journal_path(path)),
+        (
+            path.with_extension("archive.bin"),
+            archive::archive_path(path),
+        ),
+        (path.with_extension("undo"), undo::undo_path(path)),
+    ];
+    for (legacy, current) in moves {
+        if current.exists() {
+            continue;
+        }
+        match fs::rename(&legacy, &current) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+    }
+    Ok(())
 }
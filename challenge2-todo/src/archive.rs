@@ -0,0 +1,163 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::Todo;
+use crate::store::{self, FORMAT_VERSION, TodoV3, TodoV4, TodoV5};
+
+/// First bytes of a versioned archive file; it shares `store::FORMAT_VERSION`.
+pub const ARCHIVE_MAGIC: [u8; 4] = *b"TARC";
+
+/// How a task left the pending queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum Outcome {
+    Done,
+    Deleted,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Self::Done => "done",
+            Self::Deleted => "deleted",
+        })
+    }
+}
+
+/// A completed or deleted task kept for history and restore.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct ArchivedTodo {
+    pub todo: Todo,
+    pub completed_at: u64,
+    pub outcome: Outcome,
+}
+
+/// Archive entry layout of format version 3.
+#[derive(BorshDeserialize)]
+struct ArchivedTodoV3 {
+    todo: TodoV3,
+    completed_at: u64,
+    outcome: Outcome,
+}
+
+impl From<ArchivedTodoV3> for ArchivedTodo {
+    fn from(old: ArchivedTodoV3) -> Self {
+        Self {
+            todo: old.todo.into(),
+            completed_at: old.completed_at,
+            outcome: old.outcome,
+        }
+    }
+}
+
+/// Archive entry layout of format version 4.
+#[derive(BorshDeserialize)]
+struct ArchivedTodoV4 {
+    todo: TodoV4,
+    completed_at: u64,
+    outcome: Outcome,
+}
+
+impl From<ArchivedTodoV4> for ArchivedTodo {
+    fn from(old: ArchivedTodoV4) -> Self {
+        Self {
+            todo: old.todo.into(),
+            completed_at: old.completed_at,
+            outcome: old.outcome,
+        }
+    }
+}
+
+/// Archive entry layout of format version 5.
+#[derive(BorshDeserialize)]
+struct ArchivedTodoV5 {
+    todo: TodoV5,
+    completed_at: u64,
+    outcome: Outcome,
+}
+
+impl From<ArchivedTodoV5> for ArchivedTodo {
+    fn from(old: ArchivedTodoV5) -> Self {
+        Self {
+            todo: old.todo.into(),
+            completed_at: old.completed_at,
+            outcome: old.outcome,
+        }
+    }
+}
+
+/// Archive file that sits next to the queue file (`todos.bin` -> `todos.bin.archive`).
+pub fn archive_path(queue_path: &Path) -> PathBuf {
+    store::with_suffix(queue_path, ".archive")
+}
+
+/// Reads archived tasks in the order they were archived; a missing file is empty.
+///
+/// Header-less files from before the archive was versioned are read with
+/// whichever legacy task layout fits, and version 3 to 5 files with their own;
+/// all are rewritten in the current format on the next snapshot.
+pub fn load(path: &Path) -> io::Result<Vec<ArchivedTodo>> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let bytes = fs::read(path)?;
+    if bytes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Like a legacy queue file, a header-less archive starts with a u32 entry count.
+    let Some(body) = bytes.strip_prefix(&ARCHIVE_MAGIC) else {
+        let entries = store::decode_legacy::<(u64, Outcome)>(&bytes)
+            .ok_or_else(|| invalid(String::from("corrupt archive: no known layout fits")))?;
+        return Ok(entries
+            .into_iter()
+            .map(|(todo, (completed_at, outcome))| ArchivedTodo {
+                todo,
+                completed_at,
+                outcome,
+            })
+            .collect());
+    };
+    match body.split_first_chunk::<2>() {
+        Some((version, body)) if u16::from_le_bytes(*version) == FORMAT_VERSION => {
+            Vec::<ArchivedTodo>::try_from_slice(body)
+                .map_err(|err| invalid(format!("corrupt archive: {err}")))
+        }
+        Some((version, body)) if u16::from_le_bytes(*version) == 3 => {
+            Vec::<ArchivedTodoV3>::try_from_slice(body)
+                .map(|entries| entries.into_iter().map(ArchivedTodo::from).collect())
+                .map_err(|err| invalid(format!("corrupt archive: {err}")))
+        }
+        Some((version, body)) if u16::from_le_bytes(*version) == 4 => {
+            Vec::<ArchivedTodoV4>::try_from_slice(body)
+                .map(|entries| entries.into_iter().map(ArchivedTodo::from).collect())
+                .map_err(|err| invalid(format!("corrupt archive: {err}")))
+        }
+        Some((version, body)) if u16::from_le_bytes(*version) == 5 => {
+            Vec::<ArchivedTodoV5>::try_from_slice(body)
+                .map(|entries| entries.into_iter().map(ArchivedTodo::from).collect())
+                .map_err(|err| invalid(format!("corrupt archive: {err}")))
+        }
+        Some((version, _)) => Err(invalid(format!(
+            "archive format version {} is not supported (this build reads 3 to {FORMAT_VERSION})",
+            u16::from_le_bytes(*version)
+        ))),
+        None => Err(invalid(String::from("archive header is truncated"))),
+    }
+}
+
+/// Writes the full archive as `ARCHIVE_MAGIC`, a little-endian `u16` version and a
+/// Borsh `Vec<ArchivedTodo>`, replacing the file atomically.
+pub fn save(path: &Path, entries: &[ArchivedTodo]) -> io::Result<()> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&ARCHIVE_MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    borsh::to_writer(&mut bytes, entries)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+    store::write_atomic(path, &bytes)
+}
@@ -0,0 +1,375 @@
+use std::path::{Path, PathBuf};
+
+use challenge2_todo::dates::{Moment, parse_due, unix_now};
+use challenge2_todo::export;
+use challenge2_todo::{Priority, Recurrence, ReportBy};
+use clap::{Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+
+const EXAMPLES: &str = "\
+Examples:
+  todo add \"Fix bug +backend @urgent\" --priority high --due tomorrow
+  todo add --every weekday \"Standup prep\"
+  todo list \"project:backend and tag:urgent and not overdue\"
+  todo list --sort created \"created>3d ago\"
+  todo --list work done 12
+  todo note 12 && todo attach 12 ~/specs/login.pdf
+  todo start 12 && todo stop && todo report --week --by project
+  todo search invoice
+  todo undo
+  todo list --format json | jq '.[].description'
+  todo export > backup.json && todo --list copy import backup.json
+  todo import ~/todo.txt && todo export --format markdown > TODO.md
+  todo completions zsh > ~/.zfunc/_todo
+
+Without --file or --list, the store is $TODO_FILE, then the `list` set in
+$XDG_CONFIG_HOME/todo/config.toml, then the list named `default` in
+$XDG_DATA_HOME/todo.";
+
+/// Persistent priority todo queue with a terminal UI.
+///
+/// Runs the interactive interface when no command is given.
+#[derive(Debug, Parser)]
+#[command(name = "todo", version, after_long_help = EXAMPLES)]
+pub struct Cli {
+    /// Use this store file instead of a named list.
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "list",
+        help_heading = "Store"
+    )]
+    pub file: Option<PathBuf>,
+
+    /// Use the named list from the data directory.
+    #[arg(long, global = true, value_name = "NAME", help_heading = "Store")]
+    pub list: Option<String>,
+
+    /// Show times in UTC instead of local time.
+    #[arg(long, global = true, help_heading = "Display")]
+    pub utc: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Open the interactive terminal interface (the default).
+    Interactive,
+    /// Add a task; `+project` and `@tag` words become labels.
+    Add {
+        /// Priority level.
+        #[arg(short, long, value_parser = parse_priority)]
+        priority: Option<Priority>,
+        /// Deadline: YYYY-MM-DD, today, tomorrow, +3d, +2w or +4h.
+        #[arg(short, long, value_name = "WHEN", value_parser = parse_due_arg)]
+        due: Option<u64>,
+        /// Repeat rule: daily, weekday, weekly[:mon,thu], monthly[:31] or Nd.
+        #[arg(short, long, value_name = "RULE", value_parser = parse_recurrence)]
+        every: Option<Recurrence>,
+        /// Print the added task in this format.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Plain)]
+        format: OutputFormat,
+        /// Task description; quote it or pass it as several words.
+        #[arg(required = true, value_name = "DESCRIPTION")]
+        words: Vec<String>,
+    },
+    /// List pending tasks, optionally narrowed by a filter expression.
+    List {
+        /// Order of the rows.
+        #[arg(long, value_enum, default_value_t = Sort::Queue)]
+        sort: Sort,
+        /// Print the tasks in this format.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Plain)]
+        format: OutputFormat,
+        /// Filter such as `project:backend and not overdue`.
+        #[arg(value_name = "FILTER")]
+        filter: Vec<String>,
+    },
+    /// Find pending and archived tasks by words in their text, best match first.
+    ///
+    /// Words match ignoring case, anywhere in a word, and with a typo or two in
+    /// longer words. Every word has to match.
+    Search {
+        #[arg(required = true, value_name = "QUERY")]
+        words: Vec<String>,
+    },
+    /// List tasks whose deadline has passed.
+    Overdue,
+    /// Print overdue tasks and exit 1 when there are any; quiet otherwise.
+    Remind,
+    /// Complete the next task, or the task with the given id.
+    Done {
+        #[arg(value_parser = parse_id)]
+        id: Option<u64>,
+        /// Print the completed task in this format.
+        #[arg(long, value_enum, default_value_t = OutputFormat::Plain)]
+        format: OutputFormat,
+    },
+    /// Replace a task's description.
+    Edit {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+        #[arg(required = true, value_name = "DESCRIPTION")]
+        words: Vec<String>,
+    },
+    /// Move a task to a zero-based position within its priority level.
+    Mv {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+        position: usize,
+    },
+    /// Show a task with its subtasks, blockers, attachments and notes.
+    Show {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+    },
+    /// Add a checklist item to a task.
+    Sub {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+        #[arg(required = true, value_name = "TITLE")]
+        words: Vec<String>,
+    },
+    /// Tick or untick a task's subtask, numbered from 1 as in `show`.
+    Check {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+        #[arg(value_parser = parse_item_number)]
+        number: usize,
+    },
+    /// Write a task's notes in $VISUAL or $EDITOR; saving an empty file removes them.
+    Note {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+        /// Remove the notes without opening the editor.
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Attach a file or URL to a task; files are kept as absolute paths.
+    Attach {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+        #[arg(value_name = "PATH_OR_URL")]
+        target: String,
+    },
+    /// Remove a task's attachment, numbered from 1 as in `show`.
+    Detach {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+        #[arg(value_parser = parse_item_number)]
+        number: usize,
+    },
+    /// Start a timer on a task, stopping the one already running.
+    Start {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+    },
+    /// Stop the running timer.
+    Stop,
+    /// Add up tracked time per task, tag or project, largest first.
+    Report {
+        /// Only time tracked since Monday.
+        #[arg(long, conflicts_with = "since")]
+        week: bool,
+        /// Only time tracked since this time: YYYY-MM-DD [HH:MM], today,
+        /// yesterday or an age like 3h or 2d.
+        #[arg(long, value_name = "WHEN", value_parser = parse_moment)]
+        since: Option<Moment>,
+        /// What each line adds up.
+        #[arg(long, value_enum, default_value_t = Group::Task)]
+        by: Group,
+    },
+    /// Make a task wait for another one.
+    Block {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+        #[arg(value_parser = parse_id)]
+        blocker: u64,
+    },
+    /// Remove a "blocked by" link.
+    Unblock {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+        #[arg(value_parser = parse_id)]
+        blocker: u64,
+    },
+    /// List completed and deleted tasks, newest first.
+    History {
+        /// Only tasks archived on or after this time: YYYY-MM-DD [HH:MM], today,
+        /// yesterday or an age like 3h or 2d.
+        #[arg(long, value_name = "WHEN", value_parser = parse_moment)]
+        since: Option<Moment>,
+    },
+    /// Take back the last change, such as an add, completion, delete or edit.
+    ///
+    /// The history is kept with the list, so changes made in the TUI or an
+    /// earlier run can be undone too.
+    Undo {
+        /// Print the changes that can be undone, most recent first, instead.
+        #[arg(long)]
+        show: bool,
+    },
+    /// Make the last undone change again.
+    Redo,
+    /// Move an archived task back into the queue.
+    Restore {
+        #[arg(value_parser = parse_id)]
+        id: u64,
+    },
+    /// Print every pending task, in queue order, for backups and scripts.
+    ///
+    /// todo.txt and Markdown output also lists finished tasks from the history.
+    Export {
+        #[arg(long, value_enum, default_value_t = DataFormat::Json)]
+        format: DataFormat,
+    },
+    /// Merge tasks from a file into the queue, matching them by id.
+    ///
+    /// todo.txt and Markdown tasks have no ids, so they are always added;
+    /// finished ones go straight to the history.
+    Import {
+        /// File written by `export` or another tool, or `-` for stdin.
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+        /// Format of the file; guessed from a `.txt` or `.md` extension, otherwise JSON.
+        #[arg(long, value_enum)]
+        format: Option<ImportFormat>,
+        /// Delete pending tasks that are not in the file.
+        #[arg(long)]
+        replace: bool,
+    },
+    /// List the named lists; `*` marks the current one.
+    Lists,
+    /// Move a `todos.bin` that an older version kept in the current directory,
+    /// with its journal, archive and undo history, into the current list.
+    Migrate,
+    /// Print a shell completion script.
+    Completions { shell: Shell },
+    /// Print the man page in roff format.
+    Man,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Sort {
+    /// Completion order: priority, then FIFO.
+    Queue,
+    /// Soonest deadline first.
+    Due,
+    /// Newest first.
+    Created,
+}
+
+/// Lines of `report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Group {
+    Task,
+    /// A task with several tags counts toward each.
+    Tag,
+    Project,
+}
+
+impl Group {
+    pub fn report_by(self) -> ReportBy {
+        match self {
+            Self::Task => ReportBy::Task,
+            Self::Tag => ReportBy::Tag,
+            Self::Project => ReportBy::Project,
+        }
+    }
+}
+
+/// Output of commands that print tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    Plain,
+    /// JSON with stable field names.
+    Json,
+    /// Comma-separated values with a header row.
+    Csv,
+    /// Tab-separated values with a header row.
+    Tsv,
+}
+
+impl OutputFormat {
+    /// The machine-readable format, or `None` for plain text.
+    pub fn data(self) -> Option<export::Format> {
+        match self {
+            Self::Plain => None,
+            Self::Json => Some(export::Format::Json),
+            Self::Csv => Some(export::Format::Csv),
+            Self::Tsv => Some(export::Format::Tsv),
+        }
+    }
+}
+
+/// Output of `export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DataFormat {
+    Json,
+    Csv,
+    Tsv,
+    /// One todo.txt line per task.
+    #[value(alias = "todo.txt")]
+    Todotxt,
+    /// A `- [ ]` checklist.
+    #[value(alias = "md")]
+    Markdown,
+}
+
+/// Input of `import`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    Json,
+    #[value(alias = "todo.txt")]
+    Todotxt,
+    #[value(alias = "md")]
+    Markdown,
+}
+
+impl ImportFormat {
+    /// Format implied by a file name: `.txt` is todo.txt, `.md` Markdown, anything else JSON.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("txt") => Self::Todotxt,
+            Some("md" | "markdown") => Self::Markdown,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// Task id written as `12` or `#12`.
+fn parse_id(value: &str) -> Result<u64, String> {
+    value
+        .trim_start_matches('#')
+        .parse()
+        .map_err(|_| format!("'{value}' is not a task id"))
+}
+
+/// Subtask or attachment number, counted from 1 as `show` lists them.
+fn parse_item_number(value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(0) => Err(String::from("items are numbered from 1, as in `show`")),
+        Ok(number) => Ok(number),
+        Err(_) => Err(format!("'{value}' is not an item number")),
+    }
+}
+
+fn parse_priority(value: &str) -> Result<Priority, String> {
+    value.parse()
+}
+
+fn parse_recurrence(value: &str) -> Result<Recurrence, String> {
+    value.parse()
+}
+
+fn parse_moment(value: &str) -> Result<Moment, String> {
+    value.parse()
+}
+
+fn parse_due_arg(value: &str) -> Result<u64, String> {
+    parse_due(value, unix_now())
+}
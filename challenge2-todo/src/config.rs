@@ -0,0 +1,330 @@
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, de};
+
+use crate::dates::TimeFormat;
+use crate::{Priority, archive, journal, store, undo};
+
+/// List used when nothing else picks one.
+pub const DEFAULT_LIST: &str = "default";
+
+/// Environment variable naming a store file, like `--file`.
+pub const FILE_VAR: &str = "TODO_FILE";
+
+/// Store that versions before named lists kept in the working directory.
+pub const LEGACY_FILE: &str = "todos.bin";
+
+/// User settings from `config.toml`. Every key is optional:
+///
+/// ```toml
+/// data-dir = "/home/me/Sync/todo"  # where named lists are kept
+/// list = "work"                    # list used without --list or --file
+/// priority = "high"                # priority for tasks added without --priority
+/// time-format = "%d %b %H:%M"      # how times are shown (chrono strftime syntax)
+/// utc = true                       # show times in UTC, like --utc
+/// undo-depth = 50                  # changes `todo undo` can take back (0 turns it off)
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub data_dir: Option<PathBuf>,
+    pub list: Option<String>,
+    #[serde(deserialize_with = "parse_optional")]
+    pub priority: Option<Priority>,
+    #[serde(deserialize_with = "parse_optional")]
+    pub time_format: Option<TimeFormat>,
+    pub utc: bool,
+    pub undo_depth: Option<usize>,
+}
+
+impl Config {
+    /// Reads the config file at `path`; a missing file gives the defaults.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err),
+        };
+        text.parse().map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {err}", path.display()),
+            )
+        })
+    }
+
+    /// Directory holding named lists: `data-dir` when set, otherwise the XDG default.
+    pub fn data_dir(&self, dirs: &Dirs) -> PathBuf {
+        self.data_dir
+            .clone()
+            .unwrap_or_else(|| dirs.data_dir.clone())
+    }
+
+    /// How times are shown: `time-format` or `DEFAULT_TIME_FORMAT`, in UTC when
+    /// the config's `utc` or the `utc` argument (`--utc`) is set.
+    pub fn time_format(&self, utc: bool) -> TimeFormat {
+        self.time_format
+            .clone()
+            .unwrap_or_default()
+            .in_utc(utc || self.utc)
+    }
+}
+
+impl FromStr for Config {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let config: Self = toml::from_str(text).map_err(|err| err.message().to_string())?;
+        if let Some(list) = &config.list {
+            validate_list_name(list)?;
+        }
+        Ok(config)
+    }
+}
+
+/// Reads a string value through the type's `FromStr`, keeping its error message.
+fn parse_optional<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(de::Error::custom))
+        .transpose()
+}
+
+/// Default locations from the XDG base directory spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dirs {
+    /// `$XDG_DATA_HOME/todo`, falling back to `~/.local/share/todo`.
+    pub data_dir: PathBuf,
+    /// `$XDG_CONFIG_HOME/todo/config.toml`, falling back to `~/.config/todo/config.toml`.
+    pub config_file: PathBuf,
+}
+
+impl Dirs {
+    /// Locations for the current user's environment.
+    pub fn from_env() -> io::Result<Self> {
+        Self::from_vars(|key| std::env::var_os(key))
+    }
+
+    /// Locations computed from environment lookups, so they can be resolved without touching
+    /// the process environment.
+    ///
+    /// Relative `XDG_*` values are ignored, as the spec requires.
+    pub fn from_vars(var: impl Fn(&str) -> Option<OsString>) -> io::Result<Self> {
+        let base = |xdg_var: &str, fallback: &str| {
+            var(xdg_var)
+                .map(PathBuf::from)
+                .filter(|path| path.is_absolute())
+                .or_else(|| {
+                    var("HOME")
+                        .filter(|home| !home.is_empty())
+                        .map(|home| PathBuf::from(home).join(fallback))
+                })
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("neither {xdg_var} nor HOME is set"),
+                    )
+                })
+        };
+
+        Ok(Self {
+            data_dir: base("XDG_DATA_HOME", ".local/share")?.join("todo"),
+            config_file: base("XDG_CONFIG_HOME", ".config")?.join("todo/config.toml"),
+        })
+    }
+}
+
+/// Checks that `name` can be used as a list name: letters, digits, `-` and `_` only.
+pub fn validate_list_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid list name '{name}' (use letters, digits, '-' and '_')"
+        ))
+    }
+}
+
+/// Queue file of a named list (`work` -> `<data_dir>/work.bin`).
+pub fn list_path(data_dir: &Path, name: &str) -> PathBuf {
+    data_dir.join(format!("{name}.bin"))
+}
+
+/// Names of the lists stored in `data_dir`, sorted; a missing directory has none.
+///
+/// Archive, backup and temp files that sit next to each list are not lists themselves.
+pub fn list_names(data_dir: &Path) -> io::Result<Vec<String>> {
+    let entries = match fs::read_dir(data_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut names = Vec::new();
+    for entry in entries {
+        let file_name = entry?.file_name();
+        let name = file_name
+            .to_str()
+            .and_then(|file_name| file_name.strip_suffix(".bin"));
+        if let Some(name) = name
+            && validate_list_name(name).is_ok()
+        {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Which store a command works on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// An explicit file from `--file` or `TODO_FILE`.
+    File(PathBuf),
+    /// A named list in the data directory.
+    List { data_dir: PathBuf, name: String },
+}
+
+impl Location {
+    /// Picks the store from, in order: `--file`, `--list`, `TODO_FILE`, the config's
+    /// `list`, then `DEFAULT_LIST`.
+    ///
+    /// Named lists live in `Config::data_dir`.
+    pub fn resolve(
+        file: Option<PathBuf>,
+        list: Option<String>,
+        env_file: Option<PathBuf>,
+        config: &Config,
+        dirs: &Dirs,
+    ) -> Result<Self, String> {
+        if let Some(file) = file {
+            if list.is_some() {
+                return Err(String::from("--file and --list cannot be used together"));
+            }
+            return Ok(Self::File(file));
+        }
+
+        let name = match list {
+            Some(name) => name,
+            None => {
+                if let Some(file) = env_file.filter(|file| !file.as_os_str().is_empty()) {
+                    return Ok(Self::File(file));
+                }
+                config
+                    .list
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_LIST.to_string())
+            }
+        };
+        validate_list_name(&name)?;
+        Ok(Self::List {
+            data_dir: config.data_dir(dirs),
+            name,
+        })
+    }
+
+    /// Queue file to load.
+    pub fn path(&self) -> PathBuf {
+        match self {
+            Self::File(path) => path.clone(),
+            Self::List { data_dir, name } => list_path(data_dir, name),
+        }
+    }
+
+    /// The list name, or `None` for an explicit file.
+    pub fn list_name(&self) -> Option<&str> {
+        match self {
+            Self::File(_) => None,
+            Self::List { name, .. } => Some(name),
+        }
+    }
+}
+
+/// `LEGACY_FILE` in `working_dir` when `location` is the default list and that
+/// list does not exist yet, i.e. tasks an older version left there that
+/// `migrate_legacy_store` can move over. Nothing is moved by this check.
+pub fn legacy_store(location: &Location, working_dir: &Path) -> Option<PathBuf> {
+    let legacy = working_dir.join(LEGACY_FILE);
+    (location.list_name() == Some(DEFAULT_LIST) && !location.path().exists() && legacy.is_file())
+        .then_some(legacy)
+}
+
+/// Moves `LEGACY_FILE` out of `working_dir` into `location`'s store, which must
+/// not exist yet, and returns the file that was moved.
+///
+/// The journal, archive and undo history move with it, under either the names
+/// older builds gave them (`todos.journal`) or the current ones (`todos.bin.journal`).
+pub fn migrate_legacy_store(location: &Location, working_dir: &Path) -> io::Result<PathBuf> {
+    let legacy = working_dir.join(LEGACY_FILE);
+    let target = location.path();
+    if !legacy.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no {LEGACY_FILE} in {}", working_dir.display()),
+        ));
+    }
+    if target.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists; open {} with --file instead",
+                target.display(),
+                legacy.display()
+            ),
+        ));
+    }
+
+    let siblings = [
+        (
+            legacy.with_extension("journal"),
+            journal::journal_path(&target),
+        ),
+        (
+            journal::journal_path(&legacy),
+            journal::journal_path(&target),
+        ),
+        (
+            legacy.with_extension("archive.bin"),
+            archive::archive_path(&target),
+        ),
+        (
+            archive::archive_path(&legacy),
+            archive::archive_path(&target),
+        ),
+        (legacy.with_extension("undo"), undo::undo_path(&target)),
+        (undo::undo_path(&legacy), undo::undo_path(&target)),
+    ];
+    for (from, to) in siblings {
+        if from.exists() && !to.exists() {
+            move_file(&from, &to)?;
+        }
+    }
+    // The store goes last: if a move fails, running the migration again finishes it.
+    move_file(&legacy, &target)?;
+    Ok(legacy)
+}
+
+/// Renames `from` to `to`, falling back to a copy and removal when they are on
+/// different filesystems.
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+            store::write_atomic(to, &fs::read(from)?)?;
+            fs::remove_file(from)
+        }
+        result => result,
+    }
+}
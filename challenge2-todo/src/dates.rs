@@ -0,0 +1,288 @@
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{
+    DateTime, Datelike, Days, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+};
+
+/// Pattern `TimeFormat` uses unless configured otherwise: `2026-10-18 14:02`.
+pub const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Current time as Unix seconds, the unit every timestamp field uses.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Parses a user-supplied date into Unix seconds, interpreted in local time.
+///
+/// Accepts `YYYY-MM-DD` (start of that day) and `YYYY-MM-DD HH:MM`.
+pub fn parse_date(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    let naive = if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        date.and_time(NaiveTime::MIN)
+    } else {
+        NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+            .map_err(|_| format!("invalid date '{input}' (expected YYYY-MM-DD [HH:MM])"))?
+    };
+
+    local_to_unix(naive).ok_or_else(|| format!("date '{input}' does not exist in local time"))
+}
+
+/// Parses a due date relative to `now` (Unix seconds).
+///
+/// Accepts everything `parse_date` does plus `today`, `tomorrow`, and offsets
+/// like `+3d`, `+2w` or `+4h`. Day-granular inputs mean "by the end of that
+/// day", so they resolve to the last second of the day in local time.
+pub fn parse_due(input: &str, now: u64) -> Result<u64, String> {
+    let input = input.trim();
+    let lower = input.to_ascii_lowercase();
+
+    match lower.as_str() {
+        "today" => return end_of_local_day(now, 0),
+        "tomorrow" => return end_of_local_day(now, 1),
+        _ => {}
+    }
+
+    if let Some(offset) = lower.strip_prefix('+') {
+        let invalid = || format!("invalid offset '{input}' (expected e.g. +3d, +2w, +4h)");
+        let unit = offset.chars().last().ok_or_else(invalid)?;
+        let amount: u64 = offset[..offset.len() - unit.len_utf8()]
+            .parse()
+            .map_err(|_| invalid())?;
+        let out_of_range = || String::from("date is out of range");
+        return match unit {
+            'h' => amount
+                .checked_mul(3600)
+                .and_then(|seconds| now.checked_add(seconds))
+                .filter(|due_at| to_local(*due_at).is_some())
+                .ok_or_else(out_of_range),
+            'd' => end_of_local_day(now, amount),
+            'w' => end_of_local_day(now, amount.checked_mul(7).ok_or_else(out_of_range)?),
+            _ => Err(invalid()),
+        };
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return date
+            .and_hms_opt(23, 59, 59)
+            .and_then(local_to_unix)
+            .ok_or_else(|| format!("date '{input}' does not exist in local time"));
+    }
+    parse_date(input)
+}
+
+/// Start of the local day containing `timestamp`, shifted by `days`.
+pub fn start_of_local_day(timestamp: u64, days: u64) -> Option<u64> {
+    let date = to_local(timestamp)?
+        .date_naive()
+        .checked_add_days(Days::new(days))?;
+    local_to_unix(date.and_time(NaiveTime::MIN))
+}
+
+/// Start of the local week (Monday) containing `timestamp`.
+pub fn start_of_local_week(timestamp: u64) -> Option<u64> {
+    let date = to_local(timestamp)?.date_naive();
+    let monday =
+        date.checked_sub_days(Days::new(u64::from(date.weekday().num_days_from_monday())))?;
+    local_to_unix(monday.and_time(NaiveTime::MIN))
+}
+
+/// Formats a due timestamp, dropping the time when it is the end-of-day default.
+pub fn format_due(timestamp: u64) -> String {
+    match to_local(timestamp) {
+        Some(local) if (local.hour(), local.minute(), local.second()) == (23, 59, 59) => {
+            local.format("%Y-%m-%d").to_string()
+        }
+        Some(local) => local.format("%Y-%m-%d %H:%M").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+/// Local calendar date of a timestamp as `YYYY-MM-DD`.
+pub fn format_date(timestamp: u64) -> String {
+    match to_local(timestamp) {
+        Some(local) => local.format("%Y-%m-%d").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+/// How creation and archive times are shown: a chrono `strftime` pattern,
+/// rendered in local time or in UTC.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeFormat {
+    pattern: String,
+    utc: bool,
+}
+
+impl TimeFormat {
+    /// The same pattern, rendered in UTC when `utc` is true.
+    pub fn in_utc(self, utc: bool) -> Self {
+        Self { utc, ..self }
+    }
+
+    /// Renders `timestamp` with the pattern.
+    pub fn format(&self, timestamp: u64) -> String {
+        let formatted = if self.utc {
+            i64::try_from(timestamp)
+                .ok()
+                .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single())
+                .map(|utc| utc.format(&self.pattern).to_string())
+        } else {
+            to_local(timestamp).map(|local| local.format(&self.pattern).to_string())
+        };
+        formatted.unwrap_or_else(|| timestamp.to_string())
+    }
+
+    /// `format` followed by the age at `now`, as in `2026-10-18 14:02 (3h ago)`.
+    pub fn with_age(&self, timestamp: u64, now: u64) -> String {
+        format!(
+            "{} ({})",
+            self.format(timestamp),
+            format_age(timestamp, now)
+        )
+    }
+}
+
+impl Default for TimeFormat {
+    fn default() -> Self {
+        Self {
+            pattern: DEFAULT_TIME_FORMAT.to_string(),
+            utc: false,
+        }
+    }
+}
+
+impl FromStr for TimeFormat {
+    type Err = String;
+
+    /// Checks the pattern up front, since chrono only reports bad specifiers while formatting.
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        if pattern.is_empty() || StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error))
+        {
+            return Err(format!("invalid time format '{pattern}'"));
+        }
+        Ok(Self {
+            pattern: pattern.to_string(),
+            utc: false,
+        })
+    }
+}
+
+/// How long before `now` a timestamp was, in its largest whole unit: `just now`,
+/// `5m ago`, `3h ago`, `2d ago`, `6w ago`, `4mo ago` or `1y ago`. Later
+/// timestamps read `in 3h`.
+pub fn format_age(timestamp: u64, now: u64) -> String {
+    let seconds = timestamp.abs_diff(now);
+    let (amount, unit) = match seconds {
+        0..60 => return String::from("just now"),
+        60..3_600 => (seconds / 60, "m"),
+        3_600..86_400 => (seconds / 3_600, "h"),
+        86_400..604_800 => (seconds / 86_400, "d"),
+        604_800..5_184_000 => (seconds / 604_800, "w"),
+        5_184_000..31_536_000 => (seconds / 2_592_000, "mo"),
+        _ => (seconds / 31_536_000, "y"),
+    };
+    if timestamp > now {
+        format!("in {amount}{unit}")
+    } else {
+        format!("{amount}{unit} ago")
+    }
+}
+
+/// A length of time in its two largest units: `45s`, `25m`, `3h 05m` or `2d 04h`.
+pub fn format_duration(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3_600 => format!("{}m", seconds / 60),
+        3_600..86_400 => format!("{}h {:02}m", seconds / 3_600, seconds % 3_600 / 60),
+        _ => format!("{}d {:02}h", seconds / 86_400, seconds % 86_400 / 3_600),
+    }
+}
+
+/// A point in time typed by a user, resolved against the current time when used.
+///
+/// Accepts what times are shown as: `YYYY-MM-DD` (start of that day),
+/// `YYYY-MM-DD HH:MM` (also with `T` instead of the space), `today`,
+/// `yesterday`, and ages such as `90m`, `3h ago`, `2d` or `1w`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Moment {
+    /// A fixed timestamp.
+    At(u64),
+    /// This many seconds before now.
+    Ago(u64),
+    /// Start of the local day this many days before today.
+    DaysAgo(u64),
+}
+
+impl Moment {
+    /// Unix seconds of the moment, with `now` as the current time.
+    pub fn resolve(self, now: u64) -> u64 {
+        match self {
+            Self::At(timestamp) => timestamp,
+            Self::Ago(seconds) => now.saturating_sub(seconds),
+            Self::DaysAgo(days) => to_local(now)
+                .and_then(|local| local.date_naive().checked_sub_days(Days::new(days)))
+                .and_then(|date| local_to_unix(date.and_time(NaiveTime::MIN)))
+                .unwrap_or_else(|| now.saturating_sub(days * 86_400)),
+        }
+    }
+}
+
+impl FromStr for Moment {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        let lower = input.to_ascii_lowercase();
+        match lower.as_str() {
+            "today" => return Ok(Self::DaysAgo(0)),
+            "yesterday" => return Ok(Self::DaysAgo(1)),
+            _ => {}
+        }
+
+        let age = lower.strip_suffix("ago").unwrap_or(&lower).trim_end();
+        if let Some(unit) = age.chars().last()
+            && let Ok(amount) = age[..age.len() - unit.len_utf8()].parse::<u64>()
+        {
+            let seconds = match unit {
+                'm' => 60,
+                'h' => 3_600,
+                'd' => 86_400,
+                'w' => 604_800,
+                _ => {
+                    return Err(format!(
+                        "invalid age '{input}' (expected e.g. 90m, 3h, 2d, 1w)"
+                    ));
+                }
+            };
+            return Ok(Self::Ago(amount.saturating_mul(seconds)));
+        }
+
+        parse_date(&input.replacen('T', " ", 1))
+            .map(Self::At)
+            .map_err(|_| {
+                format!("invalid time '{input}' (expected YYYY-MM-DD [HH:MM], today, yesterday or an age like 3h)")
+            })
+    }
+}
+
+fn end_of_local_day(now: u64, days: u64) -> Result<u64, String> {
+    days.checked_add(1)
+        .and_then(|days| start_of_local_day(now, days))
+        .map(|start| start - 1)
+        .ok_or_else(|| String::from("date is out of range"))
+}
+
+pub(crate) fn to_local(timestamp: u64) -> Option<DateTime<Local>> {
+    let seconds = i64::try_from(timestamp).ok()?;
+    Local.timestamp_opt(seconds, 0).single()
+}
+
+pub(crate) fn local_to_unix(naive: NaiveDateTime) -> Option<u64> {
+    // `earliest` resolves DST overlaps; gaps (skipped hours) have no mapping.
+    let local = Local.from_local_datetime(&naive).earliest()?;
+    u64::try_from(local.timestamp()).ok()
+}
@@ -0,0 +1,65 @@
+use std::env;
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+
+/// Opens `text` in the user's editor and returns the saved text.
+///
+/// The editor is `$VISUAL`, then `$EDITOR`, then `vi`. The file is a fresh
+/// temp file named after `name` and is removed afterwards. Text that does not
+/// end in a newline gets one, so the editor appends on a line of its own.
+pub fn edit(text: &str, name: &str) -> io::Result<String> {
+    let path = env::temp_dir().join(format!("todo-{}-{name}.md", std::process::id()));
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    file.write_all(text.as_bytes())?;
+    if !text.is_empty() && !text.ends_with('\n') {
+        file.write_all(b"\n")?;
+    }
+    drop(file);
+
+    let result = run_editor(&path).and_then(|()| fs::read_to_string(&path));
+    let _ = fs::remove_file(&path);
+    result
+}
+
+fn editor() -> OsString {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(env::var_os)
+        .find(|editor| !editor.is_empty())
+        .unwrap_or_else(|| OsString::from("vi"))
+}
+
+fn run_editor(path: &Path) -> io::Result<()> {
+    let editor = editor();
+
+    // Through the shell, so an editor with arguments such as `code --wait` works.
+    #[cfg(unix)]
+    let status = {
+        let mut script = editor.clone();
+        script.push(r#" "$@""#);
+        Command::new("sh")
+            .arg("-c")
+            .arg(script)
+            .arg(&editor)
+            .arg(path)
+            .status()
+    };
+    #[cfg(not(unix))]
+    let status = Command::new(&editor).arg(path).status();
+
+    let editor = editor.to_string_lossy();
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(io::Error::other(format!("{editor} exited with {status}"))),
+        Err(err) => Err(io::Error::new(
+            err.kind(),
+            format!("could not run {editor}: {err}"),
+        )),
+    }
+}
@@ -0,0 +1,320 @@
+use std::io::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ArchivedTodo, Priority, Recurrence, Subtask, TimeEntry, Todo};
+
+/// Machine-readable formats for `--format` and `todo export`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A JSON array of `TaskRecord` objects.
+    Json,
+    /// RFC 4180 CSV with a header row of `COLUMNS`.
+    Csv,
+    /// Tab-separated `COLUMNS` with a header row; tabs, newlines and
+    /// backslashes inside fields are written as `\t`, `\n` and `\\`.
+    Tsv,
+}
+
+/// Column order of CSV and TSV output. Names match the `TaskRecord` fields;
+/// list-valued columns hold space-separated values.
+pub const COLUMNS: [&str; 11] = [
+    "id",
+    "priority",
+    "description",
+    "created_at",
+    "due_at",
+    "project",
+    "tags",
+    "recurrence",
+    "subtasks_done",
+    "subtasks_total",
+    "blocked_by",
+];
+
+/// A task as exported and imported. Field names are part of the output format and stay stable.
+///
+/// On import only `description` is required: a missing `id` gets a fresh one,
+/// a missing `priority` is `normal`, and a missing `created_at` is the import time.
+/// A record with `completed_at` is a finished task and goes to the archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskRecord {
+    #[serde(default)]
+    pub id: Option<u64>,
+    pub description: String,
+    #[serde(default = "default_priority")]
+    pub priority: String,
+    #[serde(default)]
+    pub created_at: Option<u64>,
+    #[serde(default)]
+    pub due_at: Option<u64>,
+    #[serde(default)]
+    pub project: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Repeat rule in the `--every` syntax, e.g. `weekly:mon,thu`.
+    #[serde(default)]
+    pub recurrence: Option<String>,
+    #[serde(default)]
+    pub subtasks: Vec<SubtaskRecord>,
+    #[serde(default)]
+    pub blocked_by: Vec<u64>,
+    /// Extra `key`/`value` pairs kept from imported files, as `[key, value]` arrays.
+    #[serde(default)]
+    pub metadata: Vec<(String, String)>,
+    /// Long-form notes; left out when there are none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Attached file paths and URLs; left out when there are none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<String>,
+    /// Tracked time, oldest first; left out when there is none.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub time_entries: Vec<TimeEntryRecord>,
+    /// When a finished task was completed; left out for pending tasks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubtaskRecord {
+    pub title: String,
+    #[serde(default)]
+    pub done: bool,
+}
+
+/// A stretch of tracked time; `stopped_at` is `null` while the timer runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeEntryRecord {
+    pub started_at: u64,
+    #[serde(default)]
+    pub stopped_at: Option<u64>,
+}
+
+fn default_priority() -> String {
+    Priority::default().to_string()
+}
+
+impl From<&Todo> for TaskRecord {
+    fn from(todo: &Todo) -> Self {
+        Self {
+            id: Some(todo.id),
+            description: todo.description.clone(),
+            priority: todo.priority.to_string(),
+            created_at: Some(todo.created_at),
+            due_at: todo.due_at,
+            project: todo.project.clone(),
+            tags: todo.tags.clone(),
+            recurrence: todo.recurrence.map(|recurrence| recurrence.to_string()),
+            subtasks: todo
+                .subtasks
+                .iter()
+                .map(|subtask| SubtaskRecord {
+                    title: subtask.title.clone(),
+                    done: subtask.done,
+                })
+                .collect(),
+            blocked_by: todo.blocked_by.clone(),
+            metadata: todo.metadata.clone(),
+            notes: todo.notes.clone(),
+            attachments: todo.attachments.clone(),
+            time_entries: todo
+                .time_entries
+                .iter()
+                .map(|entry| TimeEntryRecord {
+                    started_at: entry.started_at,
+                    stopped_at: entry.stopped_at,
+                })
+                .collect(),
+            completed_at: None,
+        }
+    }
+}
+
+impl From<&ArchivedTodo> for TaskRecord {
+    fn from(entry: &ArchivedTodo) -> Self {
+        Self {
+            completed_at: Some(entry.completed_at),
+            ..Self::from(&entry.todo)
+        }
+    }
+}
+
+impl TaskRecord {
+    /// A pending record with only a description; other fields take their import defaults.
+    pub fn new(description: String) -> Self {
+        Self {
+            id: None,
+            description,
+            priority: default_priority(),
+            created_at: None,
+            due_at: None,
+            project: None,
+            tags: Vec::new(),
+            recurrence: None,
+            subtasks: Vec::new(),
+            blocked_by: Vec::new(),
+            metadata: Vec::new(),
+            notes: None,
+            attachments: Vec::new(),
+            time_entries: Vec::new(),
+            completed_at: None,
+        }
+    }
+
+    /// Converts an imported record into a task, filling gaps with `id` and `now`.
+    pub fn into_todo(self, id: u64, now: u64) -> Result<Todo, String> {
+        if self.description.trim().is_empty() {
+            return Err(String::from("task description cannot be empty"));
+        }
+        let entries = self.time_entries.len();
+        for (index, entry) in self.time_entries.iter().enumerate() {
+            match entry.stopped_at {
+                Some(stopped_at) if stopped_at < entry.started_at => {
+                    return Err(String::from("time entry stops before it starts"));
+                }
+                None if index + 1 < entries => {
+                    return Err(String::from("only the last time entry may be running"));
+                }
+                _ => {}
+            }
+        }
+        let created_at = self.created_at.unwrap_or(now);
+        Ok(Todo {
+            id: self.id.unwrap_or(id),
+            description: self.description,
+            created_at,
+            priority: self.priority.parse()?,
+            due_at: self.due_at,
+            project: self.project.filter(|project| !project.is_empty()),
+            tags: self.tags,
+            recurrence: self
+                .recurrence
+                .map(|recurrence| recurrence.parse::<Recurrence>())
+                .transpose()?
+                .map(|recurrence| recurrence.anchored(self.due_at.unwrap_or(created_at))),
+            subtasks: self
+                .subtasks
+                .into_iter()
+                .map(|subtask| Subtask {
+                    title: subtask.title,
+                    done: subtask.done,
+                })
+                .collect(),
+            blocked_by: self.blocked_by,
+            metadata: self.metadata,
+            notes: self.notes.filter(|notes| !notes.trim().is_empty()),
+            attachments: self.attachments,
+            time_entries: self
+                .time_entries
+                .into_iter()
+                .map(|entry| TimeEntry {
+                    started_at: entry.started_at,
+                    stopped_at: entry.stopped_at,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Writes `todos` in `format`, one row or array element per task.
+pub fn write_tasks<'a>(
+    format: Format,
+    todos: impl IntoIterator<Item = &'a Todo>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let records: Vec<TaskRecord> = todos.into_iter().map(TaskRecord::from).collect();
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, &records)?;
+            writeln!(out)
+        }
+        Format::Csv | Format::Tsv => {
+            write_row(format, COLUMNS.map(String::from), out)?;
+            for record in &records {
+                write_row(format, columns(record), out)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Writes a single task: a JSON object, or a header and one row.
+pub fn write_task(format: Format, todo: &Todo, out: &mut impl Write) -> io::Result<()> {
+    match format {
+        Format::Json => {
+            serde_json::to_writer_pretty(&mut *out, &TaskRecord::from(todo))?;
+            writeln!(out)
+        }
+        Format::Csv | Format::Tsv => write_tasks(format, [todo], out),
+    }
+}
+
+/// Reads records written by `write_tasks` with `Format::Json`, or by another tool.
+pub fn parse_json(text: &str) -> io::Result<Vec<TaskRecord>> {
+    serde_json::from_str(text).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid task JSON: {err}"),
+        )
+    })
+}
+
+fn columns(record: &TaskRecord) -> [String; 11] {
+    let optional = |value: Option<u64>| value.map(|value| value.to_string()).unwrap_or_default();
+    let joined = |values: &[u64]| {
+        values
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+    let done = record
+        .subtasks
+        .iter()
+        .filter(|subtask| subtask.done)
+        .count();
+
+    [
+        optional(record.id),
+        record.priority.clone(),
+        record.description.clone(),
+        optional(record.created_at),
+        optional(record.due_at),
+        record.project.clone().unwrap_or_default(),
+        record.tags.join(" "),
+        record.recurrence.clone().unwrap_or_default(),
+        done.to_string(),
+        record.subtasks.len().to_string(),
+        joined(&record.blocked_by),
+    ]
+}
+
+fn write_row(format: Format, fields: [String; 11], out: &mut impl Write) -> io::Result<()> {
+    let (separator, escape): (&str, fn(&str) -> String) = match format {
+        Format::Tsv => ("\t", escape_tsv),
+        _ => (",", escape_csv),
+    };
+    let line = fields
+        .iter()
+        .map(|field| escape(field))
+        .collect::<Vec<String>>()
+        .join(separator);
+    writeln!(out, "{line}")
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn escape_tsv(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
@@ -0,0 +1,275 @@
+use std::str::FromStr;
+
+use chrono::NaiveTime;
+
+use crate::dates::Moment;
+use crate::{DueStatus, Priority, Todo};
+
+/// Parsed filter expression used by `todo list <filter>` and the TUI `/` prompt.
+///
+/// Grammar (keywords are case-insensitive, adjacent terms are joined with `and`):
+///
+/// ```text
+/// expr  := and ("or" and)*
+/// and   := unary ("and"? unary)*
+/// unary := "not" unary | "(" expr ")" | term
+/// term  := project:NAME | +NAME | tag:NAME | @NAME | priority:LEVEL
+///        | overdue | due:today | due:overdue | due:any | due:none
+///        | created>WHEN | created<WHEN | WORD
+/// ```
+///
+/// A bare `WORD` matches descriptions containing it, ignoring case. `WHEN` is
+/// anything `Moment` reads, so `created>2026-10-18 14:02`, `created>3h ago`
+/// and `created<yesterday` work; a following `HH:MM` or `ago` word belongs to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    Project(String),
+    Tag(String),
+    Priority(Priority),
+    Due(DueFilter),
+    Created(CreatedFilter),
+    Text(String),
+    Not(Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+}
+
+/// Deadline conditions accepted by `due:`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueFilter {
+    Overdue,
+    Today,
+    Any,
+    None,
+}
+
+/// Creation time conditions: `created<WHEN` is `Before`, `created>WHEN` is `After`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreatedFilter {
+    Before(Moment),
+    After(Moment),
+}
+
+impl Filter {
+    /// Returns whether `todo` satisfies the expression at time `now` (Unix seconds).
+    pub fn matches(&self, todo: &Todo, now: u64) -> bool {
+        match self {
+            Self::Project(project) => todo
+                .project
+                .as_ref()
+                .is_some_and(|value| value.eq_ignore_ascii_case(project)),
+            Self::Tag(tag) => todo
+                .tags
+                .iter()
+                .any(|value| value.eq_ignore_ascii_case(tag)),
+            Self::Priority(priority) => todo.priority == *priority,
+            Self::Due(due) => match due {
+                DueFilter::Overdue => todo.due_status(now) == Some(DueStatus::Overdue),
+                DueFilter::Today => todo.due_status(now) == Some(DueStatus::Today),
+                DueFilter::Any => todo.due_at.is_some(),
+                DueFilter::None => todo.due_at.is_none(),
+            },
+            Self::Created(created) => match created {
+                CreatedFilter::Before(moment) => todo.created_at < moment.resolve(now),
+                CreatedFilter::After(moment) => todo.created_at >= moment.resolve(now),
+            },
+            Self::Text(text) => todo.description.to_lowercase().contains(text),
+            Self::Not(inner) => !inner.matches(todo, now),
+            Self::And(left, right) => left.matches(todo, now) && right.matches(todo, now),
+            Self::Or(left, right) => left.matches(todo, now) || right.matches(todo, now),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(input);
+        if tokens.is_empty() {
+            return Err(String::from("empty filter"));
+        }
+
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let filter = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(filter),
+            Some(token) => Err(format!("unexpected '{token}' in filter")),
+        }
+    }
+}
+
+/// Splits on whitespace and pulls parentheses out into their own tokens.
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in input.split_whitespace() {
+        let mut word = word;
+        while let Some(rest) = word.strip_prefix('(') {
+            tokens.push(String::from("("));
+            word = rest;
+        }
+
+        let mut closing = 0;
+        while let Some(rest) = word.strip_suffix(')') {
+            closing += 1;
+            word = rest;
+        }
+
+        if !word.is_empty() {
+            tokens.push(word.to_string());
+        }
+        tokens.extend(std::iter::repeat_n(String::from(")"), closing));
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek()
+            .is_some_and(|token| token.eq_ignore_ascii_case(keyword))
+    }
+
+    fn next(&mut self) -> Option<&str> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn parse_or(&mut self) -> Result<Filter, String> {
+        let mut filter = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.position += 1;
+            let right = self.parse_and()?;
+            filter = Filter::Or(Box::new(filter), Box::new(right));
+        }
+        Ok(filter)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, String> {
+        let mut filter = self.parse_unary()?;
+        loop {
+            if self.peek_keyword("and") {
+                self.position += 1;
+            } else if self
+                .peek()
+                .is_none_or(|token| token == ")" || token.eq_ignore_ascii_case("or"))
+            {
+                return Ok(filter);
+            }
+
+            let right = self.parse_unary()?;
+            filter = Filter::And(Box::new(filter), Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Filter, String> {
+        if self.peek_keyword("not") {
+            self.position += 1;
+            return Ok(Filter::Not(Box::new(self.parse_unary()?)));
+        }
+
+        match self.next() {
+            None => Err(String::from("filter ends unexpectedly")),
+            Some("(") => {
+                let filter = self.parse_or()?;
+                match self.next() {
+                    Some(")") => Ok(filter),
+                    _ => Err(String::from("missing ')' in filter")),
+                }
+            }
+            Some(")") => Err(String::from("unexpected ')' in filter")),
+            Some(token) => {
+                let mut term = token.to_string();
+                // `created>2026-10-18 14:02` and `created>3h ago` continue into the next word.
+                if created_value(&term).is_some()
+                    && let Some(next) = self.peek()
+                    && (next.eq_ignore_ascii_case("ago")
+                        || NaiveTime::parse_from_str(next, "%H:%M").is_ok())
+                {
+                    term = format!("{term} {next}");
+                    self.position += 1;
+                }
+                parse_term(&term)
+            }
+        }
+    }
+}
+
+/// Splits `created>WHEN` or `created<WHEN` into its comparison and `WHEN`.
+fn created_value(token: &str) -> Option<(char, &str)> {
+    let prefix = token.get(.."created".len())?;
+    if !prefix.eq_ignore_ascii_case("created") {
+        return None;
+    }
+    let rest = &token[prefix.len()..];
+    let comparison = rest.chars().next().filter(|ch| matches!(ch, '<' | '>'))?;
+    Some((comparison, &rest[1..]))
+}
+
+fn parse_term(token: &str) -> Result<Filter, String> {
+    let lower = token.to_lowercase();
+    if lower == "and" || lower == "or" {
+        return Err(format!("'{token}' needs a term on both sides"));
+    }
+    if lower == "overdue" {
+        return Ok(Filter::Due(DueFilter::Overdue));
+    }
+    if let Some((comparison, value)) = created_value(token) {
+        if value.is_empty() {
+            return Err(format!("missing time after 'created{comparison}'"));
+        }
+        let moment = value.parse()?;
+        return Ok(Filter::Created(if comparison == '<' {
+            CreatedFilter::Before(moment)
+        } else {
+            CreatedFilter::After(moment)
+        }));
+    }
+
+    if let Some((key, value)) = token.split_once(':') {
+        if value.is_empty() {
+            return Err(format!("missing value after '{key}:'"));
+        }
+        return match key.to_lowercase().as_str() {
+            "project" => Ok(Filter::Project(value.to_string())),
+            "tag" => Ok(Filter::Tag(value.to_string())),
+            "priority" => value.parse().map(Filter::Priority),
+            "due" => match value.to_lowercase().as_str() {
+                "overdue" => Ok(Filter::Due(DueFilter::Overdue)),
+                "today" => Ok(Filter::Due(DueFilter::Today)),
+                "any" => Ok(Filter::Due(DueFilter::Any)),
+                "none" => Ok(Filter::Due(DueFilter::None)),
+                _ => Err(format!(
+                    "unknown due filter '{value}' (expected overdue, today, any or none)"
+                )),
+            },
+            "text" => Ok(Filter::Text(value.to_lowercase())),
+            "created" => Err(format!(
+                "use created>{value} or created<{value} to filter by creation time"
+            )),
+            _ => Err(format!(
+                "unknown filter key '{key}' (expected project, tag, priority, due or text)"
+            )),
+        };
+    }
+
+    if let Some(project) = token.strip_prefix('+').filter(|name| !name.is_empty()) {
+        return Ok(Filter::Project(project.to_string()));
+    }
+    if let Some(tag) = token.strip_prefix('@').filter(|name| !name.is_empty()) {
+        return Ok(Filter::Tag(tag.to_string()));
+    }
+    Ok(Filter::Text(lower))
+}
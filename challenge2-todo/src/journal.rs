@@ -0,0 +1,247 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::Todo;
+use crate::store::{self, TodoV2, TodoV3, TodoV4, TodoV5};
+
+/// First bytes of a journal file.
+pub const JOURNAL_MAGIC: [u8; 4] = *b"TJNL";
+
+// Magic, `u16` format version, `u64` generation.
+const HEADER_LEN: usize = JOURNAL_MAGIC.len() + 2 + 8;
+
+/// One mutation of the store, appended to the journal and replayed on load.
+///
+/// Replaying an operation whose effect is already in the snapshot is a no-op,
+/// so a crash part-way through compaction never applies anything twice.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub enum JournalOp {
+    /// Enqueue a new task at the back of its priority level.
+    Add(Todo),
+    /// Move a pending task to the archive as done.
+    Complete { id: u64, at: u64 },
+    /// Move a pending task to the archive as deleted.
+    Delete { id: u64, at: u64 },
+    /// Replace a pending task. A changed priority sends it to the back of its new level.
+    Edit(Todo),
+    /// Move a pending task to a position in list order, within its priority level.
+    Move { id: u64, position: u64 },
+    /// Take a task out of the archive and enqueue it again.
+    Restore(Todo),
+    /// Drop a pending task without archiving it; this is how an `Add` is undone.
+    Remove { id: u64 },
+}
+
+/// `JournalOp` as written by format version 2, whose tasks had no `metadata`.
+#[derive(BorshDeserialize)]
+enum JournalOpV2 {
+    Add(TodoV2),
+    Complete { id: u64, at: u64 },
+    Delete { id: u64, at: u64 },
+    Edit(TodoV2),
+    Move { id: u64, position: u64 },
+    Restore(TodoV2),
+}
+
+impl From<JournalOpV2> for JournalOp {
+    fn from(old: JournalOpV2) -> Self {
+        match old {
+            JournalOpV2::Add(todo) => Self::Add(todo.into()),
+            JournalOpV2::Complete { id, at } => Self::Complete { id, at },
+            JournalOpV2::Delete { id, at } => Self::Delete { id, at },
+            JournalOpV2::Edit(todo) => Self::Edit(todo.into()),
+            JournalOpV2::Move { id, position } => Self::Move { id, position },
+            JournalOpV2::Restore(todo) => Self::Restore(todo.into()),
+        }
+    }
+}
+
+/// `JournalOp` as written by format version 3, whose tasks had no `notes` or `attachments`.
+#[derive(BorshDeserialize)]
+enum JournalOpV3 {
+    Add(TodoV3),
+    Complete { id: u64, at: u64 },
+    Delete { id: u64, at: u64 },
+    Edit(TodoV3),
+    Move { id: u64, position: u64 },
+    Restore(TodoV3),
+    Remove { id: u64 },
+}
+
+impl From<JournalOpV3> for JournalOp {
+    fn from(old: JournalOpV3) -> Self {
+        match old {
+            JournalOpV3::Add(todo) => Self::Add(todo.into()),
+            JournalOpV3::Complete { id, at } => Self::Complete { id, at },
+            JournalOpV3::Delete { id, at } => Self::Delete { id, at },
+            JournalOpV3::Edit(todo) => Self::Edit(todo.into()),
+            JournalOpV3::Move { id, position } => Self::Move { id, position },
+            JournalOpV3::Restore(todo) => Self::Restore(todo.into()),
+            JournalOpV3::Remove { id } => Self::Remove { id },
+        }
+    }
+}
+
+/// `JournalOp` as written by format version 4, whose tasks had no `time_entries`.
+#[derive(BorshDeserialize)]
+enum JournalOpV4 {
+    Add(TodoV4),
+    Complete { id: u64, at: u64 },
+    Delete { id: u64, at: u64 },
+    Edit(TodoV4),
+    Move { id: u64, position: u64 },
+    Restore(TodoV4),
+    Remove { id: u64 },
+}
+
+impl From<JournalOpV4> for JournalOp {
+    fn from(old: JournalOpV4) -> Self {
+        match old {
+            JournalOpV4::Add(todo) => Self::Add(todo.into()),
+            JournalOpV4::Complete { id, at } => Self::Complete { id, at },
+            JournalOpV4::Delete { id, at } => Self::Delete { id, at },
+            JournalOpV4::Edit(todo) => Self::Edit(todo.into()),
+            JournalOpV4::Move { id, position } => Self::Move { id, position },
+            JournalOpV4::Restore(todo) => Self::Restore(todo.into()),
+            JournalOpV4::Remove { id } => Self::Remove { id },
+        }
+    }
+}
+
+/// `JournalOp` as written by format version 5, whose monthly rules had no day.
+#[derive(BorshDeserialize)]
+enum JournalOpV5 {
+    Add(TodoV5),
+    Complete { id: u64, at: u64 },
+    Delete { id: u64, at: u64 },
+    Edit(TodoV5),
+    Move { id: u64, position: u64 },
+    Restore(TodoV5),
+    Remove { id: u64 },
+}
+
+impl From<JournalOpV5> for JournalOp {
+    fn from(old: JournalOpV5) -> Self {
+        match old {
+            JournalOpV5::Add(todo) => Self::Add(todo.into()),
+            JournalOpV5::Complete { id, at } => Self::Complete { id, at },
+            JournalOpV5::Delete { id, at } => Self::Delete { id, at },
+            JournalOpV5::Edit(todo) => Self::Edit(todo.into()),
+            JournalOpV5::Move { id, position } => Self::Move { id, position },
+            JournalOpV5::Restore(todo) => Self::Restore(todo.into()),
+            JournalOpV5::Remove { id } => Self::Remove { id },
+        }
+    }
+}
+
+/// Journal that sits next to the queue file (`todos.bin` -> `todos.bin.journal`).
+pub fn journal_path(queue_path: &Path) -> PathBuf {
+    store::with_suffix(queue_path, ".journal")
+}
+
+/// Operations read back from a journal file.
+#[derive(Debug, Default)]
+pub struct Replay {
+    pub ops: Vec<JournalOp>,
+    /// The last record was cut short, e.g. by a crash mid-append. Everything
+    /// before it is intact; the journal must be rewritten before appending again.
+    pub torn: bool,
+    /// The journal belongs to another snapshot generation or an older format
+    /// version, so it must also be rewritten before appending again.
+    pub stale: bool,
+}
+
+/// Reads the operations recorded on top of the snapshot with `generation`.
+///
+/// A missing journal, or one left over from an older generation whose
+/// operations are already in the snapshot, replays nothing. Version 2 to 5
+/// records are upgraded to the current `Todo` layout.
+pub fn read(path: &Path, generation: u64) -> io::Result<Replay> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Replay::default()),
+        Err(err) => return Err(err),
+    };
+
+    let Some(header) = bytes.first_chunk::<HEADER_LEN>() else {
+        return Err(invalid(format!("journal {} is truncated", path.display())));
+    };
+    if header[..4] != JOURNAL_MAGIC {
+        return Err(invalid(format!("{} is not a todo journal", path.display())));
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if !matches!(version, 2..=5) && version != store::FORMAT_VERSION {
+        return Err(invalid(format!(
+            "journal format version {version} is not supported (this build reads 2 to {})",
+            store::FORMAT_VERSION
+        )));
+    }
+    let mut journal_generation = [0; 8];
+    journal_generation.copy_from_slice(&header[6..]);
+    let mut replay = Replay {
+        stale: version != store::FORMAT_VERSION,
+        ..Replay::default()
+    };
+    if u64::from_le_bytes(journal_generation) != generation {
+        replay.stale = true;
+        return Ok(replay);
+    }
+
+    let mut rest = &bytes[HEADER_LEN..];
+    while !rest.is_empty() {
+        let Some((len, body)) = rest.split_first_chunk::<4>() else {
+            replay.torn = true;
+            break;
+        };
+        let len = u32::from_le_bytes(*len) as usize;
+        if body.len() < len {
+            replay.torn = true;
+            break;
+        }
+
+        let record = &body[..len];
+        let op = match version {
+            2 => JournalOpV2::try_from_slice(record).map(JournalOp::from),
+            3 => JournalOpV3::try_from_slice(record).map(JournalOp::from),
+            4 => JournalOpV4::try_from_slice(record).map(JournalOp::from),
+            5 => JournalOpV5::try_from_slice(record).map(JournalOp::from),
+            _ => JournalOp::try_from_slice(record),
+        }
+        .map_err(|err| invalid(format!("corrupt journal record: {err}")))?;
+        replay.ops.push(op);
+        rest = &body[len..];
+    }
+    Ok(replay)
+}
+
+/// Replaces the journal with an empty one for `generation`.
+pub fn reset(path: &Path, generation: u64) -> io::Result<()> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(&JOURNAL_MAGIC);
+    header.extend_from_slice(&store::FORMAT_VERSION.to_le_bytes());
+    header.extend_from_slice(&generation.to_le_bytes());
+    store::write_atomic(path, &header)
+}
+
+/// Appends `ops` as `u32` length-prefixed Borsh records in one write, then syncs.
+///
+/// Costs the same however many tasks the store holds.
+pub fn append(path: &Path, ops: &[JournalOp]) -> io::Result<()> {
+    let mut records = Vec::new();
+    for op in ops {
+        let body =
+            borsh::to_vec(op).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let len = u32::try_from(body.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "journal record too large"))?;
+        records.extend_from_slice(&len.to_le_bytes());
+        records.extend_from_slice(&body);
+    }
+
+    let mut file = OpenOptions::new().append(true).open(path)?;
+    file.write_all(&records)?;
+    file.sync_data()
+}
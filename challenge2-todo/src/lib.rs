@@ -2,13 +2,42 @@
 //!
 //! Modules are split into:
 //! - `todo`: persisted data model
-//! - `queue`: custom generic FIFO queue
+//! - `queue`: custom generic FIFO queue and per-level priority queue
+//! - `archive`: completed/deleted task history
+//! - `store`: versioned `todos.bin` snapshots and legacy migration
+//! - `journal`: append-only log of mutations replayed on top of the snapshot
+//! - `undo`: persisted undo/redo history of those mutations
+//! - `dates`: timestamp helpers and date parsing
+//! - `config`: `config.toml`, XDG locations and named lists
+//! - `export`: JSON/CSV/TSV task records for scripting, export and import
+//! - `todotxt`: todo.txt files and the task-text grammar shared with `markdown`
+//! - `markdown`: Markdown `- [ ]` checklists
+//! - `search`: inverted word index for full-text search
+//! - `stats`: daily activity, backlog and completion-time figures
+//! - `filter`: filter expressions over projects, tags, priority and due dates
+//! - `recurrence`: repeat rules for tasks that come back after completion
 //! - `app`: application logic + disk persistence
 
 pub mod app;
+pub mod archive;
+pub mod config;
+pub mod dates;
+pub mod export;
+pub mod filter;
+pub mod journal;
+pub mod markdown;
 pub mod queue;
+pub mod recurrence;
+pub mod search;
+pub mod stats;
+pub mod store;
 pub mod todo;
+pub mod todotxt;
+pub mod undo;
 
-pub use app::TodoApp;
-pub use queue::Queue;
-pub use todo::Todo;
+pub use app::{ImportMode, ImportSummary, NewTask, ReportBy, SearchHit, TodoApp};
+pub use archive::{ArchivedTodo, Outcome};
+pub use filter::Filter;
+pub use queue::{PriorityQueue, Queue};
+pub use recurrence::Recurrence;
+pub use todo::{DueStatus, InlineTags, Priority, Subtask, TimeEntry, Todo};
@@ -1,79 +1,797 @@
 use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
-use challenge2_todo::TodoApp;
+use challenge2_todo::app::format_ids;
+use challenge2_todo::config::{self, Config, Dirs, Location};
+use challenge2_todo::dates::{
+    TimeFormat, format_due, format_duration, start_of_local_week, unix_now,
+};
+use challenge2_todo::export::{self, Format};
+use challenge2_todo::{
+    ArchivedTodo, Filter, ImportMode, InlineTags, NewTask, ReportBy, Todo, TodoApp, markdown,
+    todotxt,
+};
+use clap::{CommandFactory, Parser};
 
+use cli::{Cli, Command, DataFormat, ImportFormat, Sort};
+
+mod cli;
+mod editor;
 mod tui;
 
 /// CLI entry point for the persistent todo queue app.
 fn main() {
-    // Parse command after binary name.
-    let mut args = env::args().skip(1);
-    let command = args.next();
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Interactive);
+
+    // Generated from the CLI definition; needs no store.
+    let mut generated = Vec::new();
+    match command {
+        Command::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "todo", &mut generated);
+        }
+        Command::Man => {
+            if let Err(err) = clap_mangen::Man::new(Cli::command()).render(&mut generated) {
+                eprintln!("Failed to render man page: {err}");
+                std::process::exit(1);
+            }
+        }
+        _ => {}
+    }
+    if !generated.is_empty() {
+        write_stdout(|out| out.write_all(&generated));
+        return;
+    }
+
+    let (config, dirs) = match load_config() {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("Failed to load config: {err}");
+            std::process::exit(1);
+        }
+    };
+    let time_format = config.time_format(cli.utc);
+    let env_file = env::var_os(config::FILE_VAR).map(PathBuf::from);
+    let location = match Location::resolve(cli.file, cli.list, env_file, &config, &dirs) {
+        Ok(location) => location,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    };
+    let data_dir = config.data_dir(&dirs);
+    if location.list_name().is_some()
+        && let Err(err) = fs::create_dir_all(&data_dir)
+    {
+        eprintln!("Failed to create {}: {err}", data_dir.display());
+        std::process::exit(1);
+    }
+    // An empty path still resolves `todos.bin` against the working directory.
+    let working_dir = env::current_dir().unwrap_or_default();
+    if let Command::Migrate = command {
+        match config::migrate_legacy_store(&location, &working_dir) {
+            Ok(legacy) => {
+                println!(
+                    "Moved {} to {}.",
+                    legacy.display(),
+                    location.path().display()
+                );
+                return;
+            }
+            Err(err) => {
+                eprintln!("Failed to migrate: {err}");
+                std::process::exit(1);
+            }
+        }
+    }
+    // Older versions kept the store in the working directory; point at it, never move it unasked.
+    if let Some(legacy) = config::legacy_store(&location, &working_dir) {
+        eprintln!(
+            "Found {} from an older version; run `todo migrate` to move it into {}, or open it with --file.",
+            legacy.display(),
+            data_dir.display()
+        );
+    }
 
     // Always load persisted state first so all modes see the same queue.
-    let mut app = match TodoApp::load_or_new("todos.bin") {
+    let mut app = match TodoApp::load_or_new(location.path()) {
         Ok(app) => app,
         Err(err) => {
             eprintln!("Failed to load todo queue: {err}");
             std::process::exit(1);
         }
     };
+    if let Some(depth) = config.undo_depth {
+        app.set_undo_depth(depth);
+    }
 
-    match command.as_deref() {
+    match command {
         // Default mode: launch full-screen ratatui app.
-        None | Some("interactive") => {
-            if let Err(err) = tui::run(&mut app) {
+        Command::Interactive => {
+            let priority = config.priority.unwrap_or_default();
+            if let Err(err) = tui::run(
+                &mut app,
+                &data_dir,
+                location.list_name(),
+                time_format,
+                priority,
+            ) {
                 eprintln!("TUI exited with error: {err}");
                 std::process::exit(1);
             }
         }
         // Direct one-shot commands are still available for scripting.
-        Some("add") => {
-            let description = args.collect::<Vec<String>>().join(" ");
-            if description.is_empty() {
-                eprintln!("Usage: todo add \"task description\"");
+        Command::Add {
+            priority,
+            due,
+            every,
+            format,
+            words,
+        } => {
+            let mut options = NewTask {
+                priority: priority.or(config.priority).unwrap_or_default(),
+                due_at: due,
+                recurrence: every,
+                ..NewTask::default()
+            };
+
+            // `+project` and `@tag` words become metadata instead of description text.
+            let inline = InlineTags::parse(&words.join(" "));
+            if inline.description.is_empty() {
+                eprintln!("A task needs a description besides its +project and @tag labels.");
                 std::process::exit(1);
             }
+            options.project = inline.project;
+            options.tags = inline.tags;
 
-            match app.add_task(description) {
-                Ok(todo) => println!("Added task #{}: {}", todo.id, todo.description),
+            match app.add_task_with(inline.description, options) {
+                Ok(todo) => match (format.data(), todo.due_at) {
+                    (Some(data), _) => write_stdout(|out| export::write_task(data, &todo, out)),
+                    (None, Some(due_at)) => println!(
+                        "Added task #{} ({}, due {}): {}",
+                        todo.id,
+                        todo.priority,
+                        format_due(due_at),
+                        todo.description
+                    ),
+                    (None, None) => println!(
+                        "Added task #{} ({}): {}",
+                        todo.id, todo.priority, todo.description
+                    ),
+                },
                 Err(err) => {
                     eprintln!("Failed to add task: {err}");
                     std::process::exit(1);
                 }
             }
         }
-        Some("list") => {
-            if app.len() == 0 {
-                println!("No pending tasks.");
+        Command::List {
+            sort,
+            format,
+            filter: words,
+        } => {
+            // Everything that isn't a flag is one filter expression, quoted or not.
+            let filter = if words.is_empty() {
+                None
+            } else {
+                match words.join(" ").parse::<Filter>() {
+                    Ok(filter) => Some(filter),
+                    Err(err) => {
+                        eprintln!("Invalid filter: {err}");
+                        std::process::exit(1);
+                    }
+                }
+            };
+
+            let now = unix_now();
+            let tasks: Vec<&Todo> = match sort {
+                Sort::Queue => app.list_tasks().collect(),
+                Sort::Due => app.tasks_by_due(),
+                Sort::Created => app.tasks_by_created(),
+            };
+            let tasks: Vec<&Todo> = tasks
+                .into_iter()
+                .filter(|todo| {
+                    filter
+                        .as_ref()
+                        .is_none_or(|filter| filter.matches(todo, now))
+                })
+                .collect();
+
+            if let Some(data) = format.data() {
+                write_stdout(|out| export::write_tasks(data, tasks, out));
+                return;
+            }
+            if tasks.is_empty() {
+                match filter {
+                    Some(_) => println!("No matching tasks."),
+                    None => println!("No pending tasks."),
+                }
+                return;
+            }
+
+            for todo in tasks {
+                print_task(&app, todo, &time_format);
+            }
+        }
+        Command::Search { words } => {
+            let hits = app.search(&words.join(" "));
+            if hits.is_empty() {
+                println!("No matching tasks.");
                 return;
             }
 
-            for todo in app.list_tasks() {
-                println!("#{} [{}] {}", todo.id, todo.created_at, todo.description);
+            for hit in hits {
+                match hit.archived {
+                    None => print_task(&app, hit.todo, &time_format),
+                    Some(entry) => print_archived(entry, &time_format),
+                }
+            }
+        }
+        Command::Overdue => {
+            let mut overdue = app.overdue_tasks(unix_now()).peekable();
+            if overdue.peek().is_none() {
+                println!("No overdue tasks.");
+                return;
+            }
+
+            for todo in overdue {
+                print_task(&app, todo, &time_format);
+            }
+        }
+        Command::Remind => {
+            // Quiet unless something is overdue, so it can run from cron or a shell prompt.
+            let overdue: Vec<&Todo> = app.overdue_tasks(unix_now()).collect();
+            if overdue.is_empty() {
+                return;
+            }
+
+            println!("{} overdue task(s):", overdue.len());
+            for todo in overdue {
+                print_task(&app, todo, &time_format);
+            }
+            std::process::exit(1);
+        }
+        Command::Done { id, format } => {
+            // `todo done` completes the queue head, `todo done 12` a specific task.
+            let result = match id {
+                None => app.complete_next(),
+                Some(id) => match app.complete(id) {
+                    Ok(None) => {
+                        eprintln!("No pending task #{id}.");
+                        std::process::exit(1);
+                    }
+                    result => result,
+                },
+            };
+
+            match (result, format.data()) {
+                // Scripts get the completed task, or `null` / a bare header when there was none.
+                (Ok(todo), Some(data)) => write_stdout(|out| match (&todo, data) {
+                    (Some(todo), _) => export::write_task(data, todo, out),
+                    (None, Format::Json) => writeln!(out, "null"),
+                    (None, _) => export::write_tasks(data, [], out),
+                }),
+                (Ok(Some(todo)), None) => {
+                    println!("Completed task #{}: {}", todo.id, todo.description);
+                    if let Some(next) = app.next_in_series(&todo) {
+                        let due = next.due_at.map(format_due).unwrap_or_default();
+                        println!("Next occurrence: #{} due {due}", next.id);
+                    }
+                }
+                (Ok(None), None) if app.is_empty() => println!("No tasks to complete."),
+                (Ok(None), None) => println!("All pending tasks are blocked by other tasks."),
+                (Err(err), _) => {
+                    eprintln!("Failed to complete task: {err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Edit { id, words } => {
+            let description = words.join(" ");
+
+            match app.edit_description(id, description) {
+                Ok(Some(todo)) => println!("Edited task #{}: {}", todo.id, todo.description),
+                Ok(None) => {
+                    eprintln!("No pending task #{id}.");
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    eprintln!("Failed to edit task: {err}");
+                    std::process::exit(1);
+                }
             }
         }
-        Some("done") => match app.complete_next() {
-            Ok(Some(todo)) => println!("Completed task #{}: {}", todo.id, todo.description),
-            Ok(None) => println!("No tasks to complete."),
+        Command::Mv { id, position } => match app.move_to(id, position) {
+            Ok(Some(moved)) if moved == position => println!("Moved task #{id} to {moved}"),
+            Ok(Some(moved)) => {
+                println!("Moved task #{id} to {moved} (kept within its priority level)")
+            }
+            Ok(None) => {
+                eprintln!("No pending task #{id}.");
+                std::process::exit(1);
+            }
             Err(err) => {
-                eprintln!("Failed to complete task: {err}");
+                eprintln!("Failed to move task: {err}");
                 std::process::exit(1);
             }
         },
-        Some(command) => {
-            eprintln!("Unknown command: {command}");
-            print_usage();
-            std::process::exit(1);
+        Command::Show { id } => {
+            let Some(todo) = app.get(id) else {
+                eprintln!("No pending task #{id}.");
+                std::process::exit(1);
+            };
+
+            print_task(&app, todo, &time_format);
+            println!(
+                "  created: {}",
+                time_format.with_age(todo.created_at, unix_now())
+            );
+            for (number, subtask) in todo.subtasks.iter().enumerate() {
+                let mark = if subtask.done { "x" } else { " " };
+                println!("  {}. [{mark}] {}", number + 1, subtask.title);
+            }
+            if !todo.blocked_by.is_empty() {
+                println!("  blocked by: {}", format_ids(&todo.blocked_by));
+            }
+            for (key, value) in &todo.metadata {
+                println!("  {key}: {value}");
+            }
+            if !todo.time_entries.is_empty() {
+                let now = unix_now();
+                println!("  time: {}", format_duration(todo.time_spent(0, now)));
+            }
+            if !todo.attachments.is_empty() {
+                println!("  attachments:");
+                for (number, attachment) in todo.attachments.iter().enumerate() {
+                    println!("    {}. {attachment}", number + 1);
+                }
+            }
+            if let Some(notes) = &todo.notes {
+                println!("  notes:");
+                for line in notes.lines() {
+                    println!("    {line}");
+                }
+            }
+        }
+        Command::Note { id, clear } => {
+            let Some(todo) = app.get(id) else {
+                eprintln!("No pending task #{id}.");
+                std::process::exit(1);
+            };
+            let current = todo.notes.clone().unwrap_or_default();
+
+            let notes = if clear {
+                None
+            } else {
+                match editor::edit(&current, &format!("note-{id}")) {
+                    Ok(text) if text.trim_end() == current => {
+                        println!("Notes of task #{id} unchanged.");
+                        return;
+                    }
+                    Ok(text) => Some(text),
+                    Err(err) => {
+                        eprintln!("Failed to edit notes: {err}");
+                        std::process::exit(1);
+                    }
+                }
+            };
+            match app.set_notes(id, notes) {
+                Ok(Some(todo)) if todo.notes.is_some() => println!("Saved notes of task #{id}."),
+                Ok(_) => println!("Removed notes of task #{id}."),
+                Err(err) => {
+                    eprintln!("Failed to save notes: {err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Attach { id, target } => {
+            let attachment = match resolve_attachment(&target) {
+                Ok(attachment) => attachment,
+                Err(err) => {
+                    eprintln!("Cannot attach {target}: {err}");
+                    std::process::exit(1);
+                }
+            };
+
+            match app.add_attachment(id, attachment.clone()) {
+                Ok(Some(todo)) => println!(
+                    "Attached {attachment} to task #{} ({} attachment(s))",
+                    todo.id,
+                    todo.attachments.len()
+                ),
+                Ok(None) => {
+                    eprintln!("No pending task #{id}.");
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    eprintln!("Failed to attach: {err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Detach { id, number } => {
+            let removed = app
+                .get(id)
+                .and_then(|todo| todo.attachments.get(number - 1).cloned());
+
+            match app.remove_attachment(id, number - 1) {
+                Ok(Some(todo)) => println!(
+                    "Removed {} from task #{}",
+                    removed.unwrap_or_default(),
+                    todo.id
+                ),
+                Ok(None) => {
+                    eprintln!("No pending task #{id}.");
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    eprintln!("Failed to remove attachment: {err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Sub { id, words } => {
+            let title = words.join(" ");
+
+            match app.add_subtask(id, title) {
+                Ok(Some(todo)) => {
+                    println!("Added subtask {} to task #{}", todo.subtasks.len(), todo.id)
+                }
+                Ok(None) => {
+                    eprintln!("No pending task #{id}.");
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    eprintln!("Failed to add subtask: {err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Check { id, number } => {
+            // Subtasks are numbered from 1, matching `todo show`.
+            match app.toggle_subtask(id, number - 1) {
+                Ok(Some(todo)) => {
+                    let subtask = &todo.subtasks[number - 1];
+                    let mark = if subtask.done { "x" } else { " " };
+                    println!("Task #{}: [{mark}] {}", todo.id, subtask.title);
+                }
+                Ok(None) => {
+                    eprintln!("No pending task #{id}.");
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    eprintln!("Failed to update subtask: {err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Block { id, blocker } | Command::Unblock { id, blocker } => {
+            let blocking = matches!(command, Command::Block { .. });
+            let result = if blocking {
+                app.add_blocker(id, blocker)
+            } else {
+                app.remove_blocker(id, blocker)
+            };
+            match result {
+                Ok(Some(todo)) if todo.blocked_by.is_empty() => {
+                    println!("Task #{} is not blocked by anything.", todo.id)
+                }
+                Ok(Some(todo)) => println!(
+                    "Task #{} is blocked by {}",
+                    todo.id,
+                    format_ids(&todo.blocked_by)
+                ),
+                Ok(None) => {
+                    eprintln!("No pending task #{id}.");
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    let action = if blocking { "block" } else { "unblock" };
+                    eprintln!("Failed to {action} task: {err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Start { id } => {
+            let Some(todo) = app.get(id) else {
+                eprintln!("No pending task #{id}.");
+                std::process::exit(1);
+            };
+            let now = unix_now();
+            if let Some(started_at) = todo.running_since() {
+                let running = format_duration(now.saturating_sub(started_at));
+                println!("Timer on task #{id} is already running ({running}).");
+                return;
+            }
+            let stopped = app.running_timer().cloned();
+
+            match app.start_timer(id) {
+                Ok(Some(todo)) => {
+                    if let Some(stopped) = stopped {
+                        print_stopped(&stopped, now);
+                    }
+                    println!("Started timer on task #{id}: {}", todo.description);
+                }
+                Ok(None) => {
+                    eprintln!("No pending task #{id}.");
+                    std::process::exit(1);
+                }
+                Err(err) => {
+                    eprintln!("Failed to start timer: {err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Stop => {
+            let now = unix_now();
+            match app.stop_timer() {
+                Ok(Some(todo)) => print_stopped(&todo, now),
+                Ok(None) => println!("No timer is running."),
+                Err(err) => {
+                    eprintln!("Failed to stop timer: {err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Report { week, since, by } => {
+            let now = unix_now();
+            let (from, period) = match (week, since) {
+                (true, _) => (start_of_local_week(now).unwrap_or(0), "this week"),
+                (false, Some(since)) => (since.resolve(now), "in this period"),
+                (false, None) => (0, "yet"),
+            };
+
+            let lines = app.time_report(from, now, by.report_by());
+            if lines.is_empty() {
+                println!("No time tracked {period}.");
+                return;
+            }
+            // With tags, a task's time can count more than once, so the sum is not a total.
+            let total: u64 = app
+                .time_report(from, now, ReportBy::Task)
+                .iter()
+                .map(|(_, seconds)| seconds)
+                .sum();
+            for (label, seconds) in &lines {
+                println!("{:>8}  {label}", format_duration(*seconds));
+            }
+            println!("{:>8}  total", format_duration(total));
+        }
+        Command::History { since } => {
+            let since = since.map_or(0, |since| since.resolve(unix_now()));
+
+            let mut entries = app
+                .history()
+                .filter(|entry| entry.completed_at >= since)
+                .peekable();
+            if entries.peek().is_none() {
+                println!("No archived tasks.");
+                return;
+            }
+
+            for entry in entries {
+                print_archived(entry, &time_format);
+            }
+        }
+        Command::Undo { show: true } => {
+            let mut labels = app.undo_labels().peekable();
+            if labels.peek().is_none() {
+                println!("Nothing to undo.");
+                return;
+            }
+
+            for (number, label) in labels.enumerate() {
+                println!("{}. {label}", number + 1);
+            }
+        }
+        Command::Undo { show: false } => match app.undo() {
+            Ok(Some(label)) => println!("Undid {label}"),
+            Ok(None) => println!("Nothing to undo."),
+            Err(err) => {
+                eprintln!("Failed to undo: {err}");
+                std::process::exit(1);
+            }
+        },
+        Command::Redo => match app.redo() {
+            Ok(Some(label)) => println!("Redid {label}"),
+            Ok(None) => println!("Nothing to redo."),
+            Err(err) => {
+                eprintln!("Failed to redo: {err}");
+                std::process::exit(1);
+            }
+        },
+        Command::Restore { id } => match app.restore(id) {
+            Ok(Some(todo)) => println!("Restored task #{}: {}", todo.id, todo.description),
+            Ok(None) => {
+                eprintln!("No archived task #{id}.");
+                std::process::exit(1);
+            }
+            Err(err) => {
+                eprintln!("Failed to restore task: {err}");
+                std::process::exit(1);
+            }
+        },
+        Command::Export { format } => {
+            // Finished tasks, oldest first, for the formats that can mark tasks done.
+            let mut finished: Vec<&ArchivedTodo> = app.history().collect();
+            finished.reverse();
+
+            write_stdout(|out| match format {
+                DataFormat::Json => export::write_tasks(Format::Json, app.list_tasks(), out),
+                DataFormat::Csv => export::write_tasks(Format::Csv, app.list_tasks(), out),
+                DataFormat::Tsv => export::write_tasks(Format::Tsv, app.list_tasks(), out),
+                DataFormat::Todotxt => todotxt::write(app.list_tasks(), finished, out),
+                DataFormat::Markdown => markdown::write(app.list_tasks(), finished, out),
+            });
+        }
+        Command::Import {
+            path,
+            format,
+            replace,
+        } => {
+            let format = format.unwrap_or_else(|| ImportFormat::for_path(&path));
+            let parsed = read_input(&path).and_then(|text| match format {
+                ImportFormat::Json => export::parse_json(&text),
+                ImportFormat::Todotxt => Ok(todotxt::parse(&text, unix_now())),
+                ImportFormat::Markdown => Ok(markdown::parse(&text, unix_now())),
+            });
+            let records = match parsed {
+                Ok(records) => records,
+                Err(err) => {
+                    eprintln!("Failed to read {}: {err}", path.display());
+                    std::process::exit(1);
+                }
+            };
+            let mode = if replace {
+                ImportMode::Replace
+            } else {
+                ImportMode::Merge
+            };
+
+            match app.import(records, mode) {
+                Ok(summary) => {
+                    println!(
+                        "Imported tasks: {} added, {} updated, {} restored, {} archived, {} removed.",
+                        summary.added,
+                        summary.updated,
+                        summary.restored,
+                        summary.archived,
+                        summary.removed
+                    );
+                }
+                Err(err) => {
+                    eprintln!("Failed to import tasks: {err}");
+                    std::process::exit(1);
+                }
+            }
         }
+        Command::Lists => {
+            let mut names = match config::list_names(&data_dir) {
+                Ok(names) => names,
+                Err(err) => {
+                    eprintln!("Failed to read {}: {err}", data_dir.display());
+                    std::process::exit(1);
+                }
+            };
+            // The current list shows up even before its first task is saved.
+            if let Some(current) = location.list_name()
+                && !names.iter().any(|name| name == current)
+            {
+                names.push(current.to_string());
+                names.sort();
+            }
+
+            for name in names {
+                let marker = if location.list_name() == Some(name.as_str()) {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{marker} {name}");
+            }
+            if let Location::File(path) = &location {
+                println!("* {} (file)", path.display());
+            }
+        }
+        Command::Completions { .. } | Command::Man | Command::Migrate => {
+            unreachable!("handled before loading")
+        }
+    }
+}
+
+/// Reads `config.toml` from its XDG location.
+fn load_config() -> io::Result<(Config, Dirs)> {
+    let dirs = Dirs::from_env()?;
+    let config = Config::load(&dirs.config_file)?;
+    Ok((config, dirs))
+}
+
+/// Reads a whole file, or stdin for `-`.
+fn read_input(path: &Path) -> io::Result<String> {
+    if path == Path::new("-") {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// What `attach` stores: a URL as given, or an existing file's absolute path.
+fn resolve_attachment(target: &str) -> io::Result<String> {
+    if target.contains("://") || target.starts_with("mailto:") {
+        return Ok(target.to_string());
     }
+    let path = fs::canonicalize(target)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Writes output for scripts to stdout; a closed pipe (`todo man | head`) is not worth reporting.
+fn write_stdout(write: impl FnOnce(&mut io::StdoutLock<'static>) -> io::Result<()>) {
+    let mut out = io::stdout().lock();
+    if let Err(err) = write(&mut out).and_then(|()| out.flush())
+        && err.kind() != io::ErrorKind::BrokenPipe
+    {
+        eprintln!("Failed to write output: {err}");
+        std::process::exit(1);
+    }
+}
+
+/// Prints one pending task as a list row, with its deadline, checklist progress and blockers.
+fn print_task(app: &TodoApp, todo: &Todo, time_format: &TimeFormat) {
+    let mut line = format!(
+        "#{} [{}] {:<6} {}",
+        todo.id,
+        time_format.with_age(todo.created_at, unix_now()),
+        todo.priority,
+        todo.description
+    );
+    let labels = todo.labels();
+    if !labels.is_empty() {
+        line.push(' ');
+        line.push_str(&labels);
+    }
+    if let Some(due_at) = todo.due_at {
+        line.push_str(&format!(" (due {})", format_due(due_at)));
+    }
+    if let Some(recurrence) = todo.recurrence {
+        line.push_str(&format!(" (repeats {recurrence})"));
+    }
+    if let Some(started_at) = todo.running_since() {
+        let running = format_duration(unix_now().saturating_sub(started_at));
+        line.push_str(&format!(" (timer running {running})"));
+    }
+    if !todo.subtasks.is_empty() {
+        let done = todo.subtasks.iter().filter(|subtask| subtask.done).count();
+        line.push_str(&format!(" [{done}/{}]", todo.subtasks.len()));
+    }
+    let blockers = app.pending_blockers(todo.id);
+    if !blockers.is_empty() {
+        line.push_str(&format!(" (blocked by {})", format_ids(&blockers)));
+    }
+    println!("{line}");
+}
+
+/// Prints how long the timer on `todo` ran before it was stopped at about `now`.
+fn print_stopped(todo: &Todo, now: u64) {
+    let entry = todo.time_entries.last().copied();
+    let ran = entry.map_or(0, |entry| entry.seconds_between(0, now));
+    println!(
+        "Stopped timer on task #{} after {}: {}",
+        todo.id,
+        format_duration(ran),
+        todo.description
+    );
 }
 
-fn print_usage() {
-    println!("Todo CLI");
-    println!("  todo                # ratatui interactive mode");
-    println!("  todo interactive");
-    println!("  todo add \"Buy groceries\"");
-    println!("  todo list");
-    println!("  todo done");
+/// Prints one archived task as a history row, with when and how it was archived.
+fn print_archived(entry: &ArchivedTodo, time_format: &TimeFormat) {
+    println!(
+        "#{} [{}] {:<7} {}",
+        entry.todo.id,
+        time_format.format(entry.completed_at),
+        entry.outcome,
+        entry.todo.description
+    );
 }
@@ -0,0 +1,187 @@
+use std::io::{self, Write};
+
+use crate::dates::{format_date, parse_date};
+use crate::export::{SubtaskRecord, TaskRecord};
+use crate::todotxt::{self, PRIORITY_KEY};
+use crate::{ArchivedTodo, Outcome, Todo};
+
+/// Metadata key holding the heading line (`## Work`) a task was listed under.
+pub const SECTION_KEY: &str = "section";
+
+/// Key of the date a finished item was completed (`- [x] Ship it done:2026-10-19`).
+pub const DONE_KEY: &str = "done";
+
+/// Reads the checklist items of a Markdown file.
+///
+/// `- [ ]` and `- [x]` items (also with `*` or `+` bullets) are tasks, and
+/// items indented below a task are its subtasks. A task's text follows the
+/// todo.txt rules: `(A)`, `+project`, `@context`, `due:` and other `key:value`
+/// words. Finished tasks come back with `completed_at`, from their `done:` date
+/// or `now`. The nearest heading above a task is kept under `SECTION_KEY`;
+/// other lines are ignored.
+pub fn parse(text: &str, now: u64) -> Vec<TaskRecord> {
+    let mut records: Vec<TaskRecord> = Vec::new();
+    let mut section: Option<&str> = None;
+    // Indent of the task that following, deeper items belong to.
+    let mut task_indent = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if is_heading(trimmed) {
+            section = Some(trimmed);
+            task_indent = None;
+            continue;
+        }
+        let Some((indent, done, text)) = checklist_item(line) else {
+            continue;
+        };
+
+        if let Some(parent) = task_indent
+            && indent > parent
+            && let Some(task) = records.last_mut()
+        {
+            task.subtasks.push(SubtaskRecord {
+                title: text.to_string(),
+                done,
+            });
+            continue;
+        }
+
+        let mut record = todotxt::parse_text(text, now);
+        if done {
+            let date = take_metadata(&mut record, DONE_KEY, |value| parse_date(value).ok());
+            record.completed_at = Some(date.unwrap_or(now));
+        }
+        if let Some(section) = section {
+            record
+                .metadata
+                .push((SECTION_KEY.to_string(), section.to_string()));
+        }
+        records.push(record);
+        task_indent = Some(indent);
+    }
+    records
+}
+
+/// Writes pending tasks in queue order as `- [ ]` items, then the finished tasks
+/// in `archived` as `- [x]` items with a `done:` date; subtasks are indented below.
+///
+/// Tasks without a section come first, then each section under its heading in
+/// the order it first appears. Deleted tasks and blockers are not written.
+pub fn write<'a>(
+    pending: impl IntoIterator<Item = &'a Todo>,
+    archived: impl IntoIterator<Item = &'a ArchivedTodo>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let items: Vec<(&Todo, Option<u64>)> = pending
+        .into_iter()
+        .map(|todo| (todo, None))
+        .chain(
+            archived
+                .into_iter()
+                .filter(|entry| entry.outcome == Outcome::Done)
+                .map(|entry| (&entry.todo, Some(entry.completed_at))),
+        )
+        .collect();
+
+    let mut sections: Vec<Option<&str>> = Vec::new();
+    for (todo, _) in &items {
+        let section = section_of(todo);
+        if !sections.contains(&section) {
+            sections.push(section);
+        }
+    }
+    // Items before the first heading must come first to stay outside every section.
+    sections.sort_by_key(|section| section.is_some());
+
+    let mut first = true;
+    for section in sections {
+        if let Some(heading) = section {
+            if !first {
+                writeln!(out)?;
+            }
+            writeln!(out, "{}", heading_line(heading))?;
+            writeln!(out)?;
+        }
+        first = false;
+
+        for &(todo, completed_at) in items.iter().filter(|(todo, _)| section_of(todo) == section) {
+            write_item(todo, completed_at, out)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_item(todo: &Todo, completed_at: Option<u64>, out: &mut impl Write) -> io::Result<()> {
+    let mut text = todotxt::format_words(todo, &[PRIORITY_KEY, SECTION_KEY]);
+    if let Some(letter) = todotxt::priority_letter(todo) {
+        text = format!("({letter}) {text}");
+    }
+    if let Some(completed_at) = completed_at {
+        text.push_str(&format!(" {DONE_KEY}:{}", format_date(completed_at)));
+    }
+    writeln!(out, "- [{}] {text}", checkbox(completed_at.is_some()))?;
+
+    for subtask in &todo.subtasks {
+        writeln!(out, "  - [{}] {}", checkbox(subtask.done), subtask.title)?;
+    }
+    Ok(())
+}
+
+fn checkbox(done: bool) -> char {
+    if done { 'x' } else { ' ' }
+}
+
+fn section_of(todo: &Todo) -> Option<&str> {
+    todo.metadata
+        .iter()
+        .find(|(key, _)| key == SECTION_KEY)
+        .map(|(_, value)| value.as_str())
+}
+
+/// A section as a heading line; values that are not already a heading become `##` ones.
+fn heading_line(section: &str) -> String {
+    if is_heading(section) {
+        section.to_string()
+    } else {
+        format!("## {section}")
+    }
+}
+
+/// An ATX heading: one to six `#` followed by a space.
+fn is_heading(line: &str) -> bool {
+    let level = line.chars().take_while(|&ch| ch == '#').count();
+    (1..=6).contains(&level) && line[level..].starts_with(' ')
+}
+
+/// Splits a `- [ ] text` line into its indent, whether it is checked, and its text.
+fn checklist_item(line: &str) -> Option<(usize, bool, &str)> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    let rest = trimmed.strip_prefix(['-', '*', '+'])?.strip_prefix(' ')?;
+    let (done, text) = if let Some(text) = rest.strip_prefix("[ ]") {
+        (false, text)
+    } else {
+        let text = rest
+            .strip_prefix("[x]")
+            .or_else(|| rest.strip_prefix("[X]"))?;
+        (true, text)
+    };
+
+    let text = text.trim();
+    (!text.is_empty()).then_some((indent, done, text))
+}
+
+/// Removes the first `key` entry that `parse` accepts and returns its parsed value.
+fn take_metadata<T>(
+    record: &mut TaskRecord,
+    key: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<T> {
+    let index = record
+        .metadata
+        .iter()
+        .position(|(name, value)| name == key && parse(value).is_some())?;
+    let (_, value) = record.metadata.remove(index);
+    parse(&value)
+}
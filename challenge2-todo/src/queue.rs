@@ -68,6 +68,25 @@ impl<T> Queue<T> {
         self.items[self.head..].iter().filter_map(Option::as_ref)
     }
 
+    /// Mutable iteration from oldest to newest item.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.items[self.head..]
+            .iter_mut()
+            .filter_map(Option::as_mut)
+    }
+
+    /// Inserts an item at a zero-based logical index, clamped to the queue length.
+    ///
+    /// `index = 0` makes it the next item to be dequeued.
+    pub fn insert_at(&mut self, index: usize, value: T) {
+        let index = index.min(self.len);
+        // Drop the consumed prefix first so logical and physical indexes line up.
+        self.items.drain(..self.head);
+        self.head = 0;
+        self.items.insert(index, Some(value));
+        self.len += 1;
+    }
+
     /// Removes and returns the item at a zero-based logical index from the queue front.
     ///
     /// `index = 0` removes the same item as `dequeue`.
@@ -118,3 +137,80 @@ impl<T> Queue<T> {
         }
     }
 }
+
+/// Generic priority queue: FIFO within each level, highest level served first.
+///
+/// Each level is its own `Queue<T>`, so enqueue/dequeue stay O(1) amortized
+/// and items of equal priority keep their arrival order.
+pub struct PriorityQueue<T> {
+    levels: Vec<Queue<T>>,
+}
+
+impl<T> PriorityQueue<T> {
+    /// Creates an empty queue with `levels` priority levels (`0` is lowest).
+    pub fn new(levels: usize) -> Self {
+        Self {
+            levels: (0..levels.max(1)).map(|_| Queue::new()).collect(),
+        }
+    }
+
+    /// Adds an item to the back of its level. Out-of-range levels clamp to the highest.
+    pub fn enqueue(&mut self, level: usize, value: T) {
+        let level = level.min(self.levels.len() - 1);
+        self.levels[level].enqueue(value);
+    }
+
+    /// Removes and returns the oldest item of the highest non-empty level.
+    pub fn dequeue(&mut self) -> Option<T> {
+        self.levels.iter_mut().rev().find_map(Queue::dequeue)
+    }
+
+    /// Returns the item `dequeue` would remove, without removing it.
+    pub fn peek(&self) -> Option<&T> {
+        self.levels.iter().rev().find_map(Queue::peek)
+    }
+
+    /// Number of pending items across all levels.
+    pub fn len(&self) -> usize {
+        self.levels.iter().map(Queue::len).sum()
+    }
+
+    /// True when every level is empty.
+    pub fn is_empty(&self) -> bool {
+        self.levels.iter().all(Queue::is_empty)
+    }
+
+    /// Iterates in dequeue order: highest level first, oldest first within a level.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.levels.iter().rev().flat_map(Queue::iter)
+    }
+
+    /// Mutable iteration in dequeue order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.levels.iter_mut().rev().flat_map(Queue::iter_mut)
+    }
+
+    /// Positions (in `iter` order) occupied by `level`, as a half-open range.
+    pub fn level_range(&self, level: usize) -> std::ops::Range<usize> {
+        let level = level.min(self.levels.len() - 1);
+        let start: usize = self.levels[level + 1..].iter().map(Queue::len).sum();
+        start..start + self.levels[level].len()
+    }
+
+    /// Inserts an item at a zero-based position within its level, clamped to that level.
+    pub fn insert_at(&mut self, level: usize, index: usize, value: T) {
+        let level = level.min(self.levels.len() - 1);
+        self.levels[level].insert_at(index, value);
+    }
+
+    /// Removes and returns the item at a zero-based position in `iter` order.
+    pub fn remove_at(&mut self, mut index: usize) -> Option<T> {
+        for level in self.levels.iter_mut().rev() {
+            if index < level.len() {
+                return level.remove_at(index);
+            }
+            index -= level.len();
+        }
+        None
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveTime};
+
+use crate::dates::{local_to_unix, to_local};
+
+const WEEKDAY_NAMES: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// How often a recurring task comes back after it is completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum Recurrence {
+    Daily,
+    /// Monday to Friday.
+    Weekdays,
+    /// Bit set of weekdays, bit 0 is Monday.
+    Weekly(u8),
+    /// Same day of the month, clamped to the last day of shorter months. Holds
+    /// that day (1 to 31), or 0 until `anchored` takes it from a due date.
+    Monthly(u8),
+    EveryDays(u16),
+}
+
+impl Recurrence {
+    /// Due date of the instance that follows one due at `previous_due`, completed at `now`.
+    ///
+    /// The next due date keeps the previous time of day and is always later than
+    /// `now`, so completing a task that is days overdue does not spawn another
+    /// overdue one. Without a previous due date the series is counted from
+    /// today and lands at the end of the day, like `parse_due` does.
+    pub fn next_due(self, previous_due: Option<u64>, now: u64) -> Option<u64> {
+        let today = to_local(now)?.date_naive();
+        let (base, time) = match previous_due {
+            Some(due_at) => {
+                let local = to_local(due_at)?;
+                (local.date_naive(), local.time())
+            }
+            None => (today, NaiveTime::from_hms_opt(23, 59, 59)?),
+        };
+        let at = |date: NaiveDate| local_to_unix(date.and_time(time));
+
+        match self {
+            Self::Monthly(day) => {
+                // Clamp from the anchor day each time, so the 31st comes back after February.
+                let day = if day == 0 { base.day() } else { u32::from(day) };
+                let month = base.with_day(1)?;
+                (1..)
+                    .map_while(|months| month.checked_add_months(Months::new(months)))
+                    .filter_map(|first| {
+                        let last = first.checked_add_months(Months::new(1))?.pred_opt()?;
+                        first.with_day(day.min(last.day()))
+                    })
+                    .filter_map(at)
+                    .find(|&due_at| due_at > now)
+            }
+            Self::EveryDays(days) => {
+                let step = u64::from(days.max(1));
+                // Jump close to today instead of walking a long-overdue series one step at a time.
+                let elapsed = u64::try_from((today - base).num_days()).unwrap_or(0);
+                ((elapsed / step).max(1)..)
+                    .map_while(|steps| base.checked_add_days(Days::new(steps * step)))
+                    .filter_map(at)
+                    .find(|&due_at| due_at > now)
+            }
+            Self::Daily | Self::Weekdays | Self::Weekly(_) => {
+                let start = base.succ_opt()?.max(today);
+                // Any rule with at least one day matches within a week of `start`.
+                (0..8)
+                    .filter_map(|offset| start.checked_add_days(Days::new(offset)))
+                    .filter(|date| self.includes(*date))
+                    .filter_map(at)
+                    .find(|&due_at| due_at > now)
+            }
+        }
+    }
+
+    /// Pins an unanchored `Monthly` rule to the day of the month `due_at` falls on;
+    /// every other rule is returned unchanged.
+    pub fn anchored(self, due_at: u64) -> Self {
+        match (self, to_local(due_at)) {
+            (Self::Monthly(0), Some(local)) => Self::Monthly(local.day() as u8),
+            _ => self,
+        }
+    }
+
+    fn includes(self, date: NaiveDate) -> bool {
+        let weekday = date.weekday().num_days_from_monday();
+        match self {
+            Self::Daily => true,
+            Self::Weekdays => weekday < 5,
+            Self::Weekly(days) => days & (1 << weekday) != 0,
+            Self::Monthly(_) | Self::EveryDays(_) => false,
+        }
+    }
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Daily => f.pad("daily"),
+            Self::Weekdays => f.pad("weekdays"),
+            Self::Weekly(days) => {
+                let names: Vec<&str> = WEEKDAY_NAMES
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| days & (1 << index) != 0)
+                    .map(|(_, name)| &name[..3])
+                    .collect();
+                f.pad(&format!("weekly:{}", names.join(",")))
+            }
+            Self::Monthly(0) => f.pad("monthly"),
+            Self::Monthly(day) => f.pad(&format!("monthly:{day}")),
+            Self::EveryDays(days) => f.pad(&format!("{days}d")),
+        }
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    /// Accepts `daily`, `weekday(s)`, `weekly` (every 7 days), `weekly:mon,thu`
+    /// (or just `mon,thu`), `monthly` (on the due date's day) or `monthly:31`,
+    /// and `Nd` for every N days.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "unknown recurrence '{value}' (expected daily, weekday, weekly[:mon,thu], monthly[:31] or Nd)"
+            )
+        };
+        let lower = value.trim().to_ascii_lowercase();
+
+        match lower.as_str() {
+            "daily" => return Ok(Self::Daily),
+            "weekday" | "weekdays" => return Ok(Self::Weekdays),
+            "weekly" => return Ok(Self::EveryDays(7)),
+            "monthly" => return Ok(Self::Monthly(0)),
+            _ => {}
+        }
+
+        if let Some(day) = lower.strip_prefix("monthly:") {
+            return match day.parse::<u8>() {
+                Ok(day @ 1..=31) => Ok(Self::Monthly(day)),
+                _ => Err(invalid()),
+            };
+        }
+
+        if let Some(days) = lower.strip_suffix('d')
+            && let Ok(days) = days.parse::<u16>()
+        {
+            return if days == 0 {
+                Err(invalid())
+            } else {
+                Ok(Self::EveryDays(days))
+            };
+        }
+
+        let names = lower.strip_prefix("weekly:").unwrap_or(&lower);
+        let mut days = 0_u8;
+        for name in names.split(',') {
+            let name = name.trim();
+            let index = WEEKDAY_NAMES
+                .iter()
+                .position(|day| name.len() >= 3 && day.starts_with(name))
+                .ok_or_else(invalid)?;
+            days |= 1 << index;
+        }
+        Ok(Self::Weekly(days))
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Range;
+
+use crate::Todo;
+
+/// How a query word matched a word of a task, from loosest to closest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    /// Within a typo or two of the word or its start: one for 4-7 letters, two for longer.
+    Fuzzy,
+    /// Somewhere inside the word.
+    Substring,
+    /// At the start of the word.
+    Prefix,
+    /// The whole word.
+    Exact,
+}
+
+impl MatchKind {
+    /// How the lowercase query `word` matches the lowercase task word `term`, if at all.
+    pub fn of(word: &str, term: &str) -> Option<Self> {
+        if term == word {
+            Some(Self::Exact)
+        } else if term.starts_with(word) {
+            Some(Self::Prefix)
+        } else if term.contains(word) {
+            Some(Self::Substring)
+        } else if is_typo_of(word, term) {
+            Some(Self::Fuzzy)
+        } else {
+            None
+        }
+    }
+
+    /// Points a match adds to a task's score.
+    fn score(self) -> u32 {
+        self as u32 + 1
+    }
+}
+
+/// Inverted index from words to the tasks containing them, over `Todo::search_text`.
+///
+/// A query scans the vocabulary rather than every task, so its cost grows with
+/// the number of distinct words, not with the length of the list.
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    postings: BTreeMap<String, BTreeSet<u64>>,
+    // Words indexed under each id, so a task can be taken out again.
+    words: HashMap<u64, BTreeSet<String>>,
+}
+
+impl SearchIndex {
+    /// Indexes `todo`, replacing whatever was indexed under its id.
+    pub fn insert(&mut self, todo: &Todo) {
+        self.remove(todo.id);
+        let words: BTreeSet<String> = todo.search_text().flat_map(words).collect();
+        for word in &words {
+            self.postings
+                .entry(word.clone())
+                .or_default()
+                .insert(todo.id);
+        }
+        self.words.insert(todo.id, words);
+    }
+
+    /// Drops the task with this id from the index.
+    pub fn remove(&mut self, id: u64) {
+        let Some(words) = self.words.remove(&id) else {
+            return;
+        };
+        for word in words {
+            if let Some(ids) = self.postings.get_mut(&word) {
+                ids.remove(&id);
+                if ids.is_empty() {
+                    self.postings.remove(&word);
+                }
+            }
+        }
+    }
+
+    /// Ids of the tasks matching every word of `query`, ignoring case, with
+    /// scores that grow with how closely each word matched.
+    ///
+    /// Each query word counts its best match in the task. A query without
+    /// letters or digits matches nothing.
+    pub fn search(&self, query: &str) -> HashMap<u64, u32> {
+        let mut scores: Option<HashMap<u64, u32>> = None;
+        for word in words(query) {
+            let mut best: HashMap<u64, MatchKind> = HashMap::new();
+            for (term, ids) in &self.postings {
+                let Some(kind) = MatchKind::of(&word, term) else {
+                    continue;
+                };
+                for id in ids {
+                    let current = best.entry(*id).or_insert(kind);
+                    *current = (*current).max(kind);
+                }
+            }
+
+            let matched = match scores {
+                None => best
+                    .into_iter()
+                    .map(|(id, kind)| (id, kind.score()))
+                    .collect(),
+                Some(scores) => scores
+                    .into_iter()
+                    .filter_map(|(id, score)| Some((id, score + best.get(&id)?.score())))
+                    .collect(),
+            };
+            scores = Some(matched);
+        }
+        scores.unwrap_or_default()
+    }
+}
+
+/// Lowercase words of `text`: runs of letters and digits.
+pub fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    word_spans(text).map(|(_, word)| word.to_lowercase())
+}
+
+/// Byte ranges of `text` matched by the words of `query`, sorted and merged, for highlighting.
+///
+/// Substring matches cover just the matched letters; fuzzy ones, and words
+/// whose letters change length when lowercased, cover the whole word.
+pub fn highlight(text: &str, query: &str) -> Vec<Range<usize>> {
+    let query: Vec<String> = words(query).collect();
+    let mut ranges = Vec::new();
+    for (start, word) in word_spans(text) {
+        let lower = word.to_lowercase();
+        let aligned = word
+            .chars()
+            .all(|ch| ch.to_lowercase().map(char::len_utf8).sum::<usize>() == ch.len_utf8());
+        for query_word in &query {
+            match MatchKind::of(query_word, &lower) {
+                None => {}
+                Some(MatchKind::Fuzzy) => ranges.push(start..start + word.len()),
+                Some(_) => match lower.find(query_word.as_str()).filter(|_| aligned) {
+                    Some(offset) => {
+                        ranges.push(start + offset..start + offset + query_word.len());
+                    }
+                    None => ranges.push(start..start + word.len()),
+                },
+            }
+        }
+    }
+
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Words of `text` with their byte offsets.
+fn word_spans(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = None;
+    text.char_indices()
+        .chain([(text.len(), ' ')])
+        .filter_map(move |(index, ch)| match (ch.is_alphanumeric(), start) {
+            (true, None) => {
+                start = Some(index);
+                None
+            }
+            (false, Some(from)) => {
+                start = None;
+                Some((from, &text[from..index]))
+            }
+            _ => None,
+        })
+}
+
+/// Whether `word` is a typo or two away from `term` or from a start of it,
+/// so a word still being typed finds longer ones.
+fn is_typo_of(word: &str, term: &str) -> bool {
+    let word: Vec<char> = word.chars().collect();
+    let term: Vec<char> = term.chars().collect();
+    let typos = match word.len() {
+        0..=3 => return false,
+        4..=7 => 1,
+        _ => 2,
+    };
+    prefix_distances(&word, &term)
+        .into_iter()
+        .any(|distance| distance <= typos)
+}
+
+/// Levenshtein distances from `from` to every prefix of `to`, shortest prefix first:
+/// the insertions, deletions and substitutions needed to turn one into the other.
+fn prefix_distances(from: &[char], to: &[char]) -> Vec<usize> {
+    let mut previous: Vec<usize> = (0..=to.len()).collect();
+    let mut current = vec![0; to.len() + 1];
+    for (i, from_ch) in from.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, to_ch) in to.iter().enumerate() {
+            let substitution = previous[j] + usize::from(from_ch != to_ch);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous
+}
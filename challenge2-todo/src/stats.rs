@@ -0,0 +1,117 @@
+use crate::dates::Moment;
+use crate::{ArchivedTodo, Outcome, Todo};
+
+/// Figures for the statistics view, worked out from the pending queue and the
+/// archive, which keeps every completed task with the time it was completed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stats {
+    /// Start of each local day covered, oldest first; the last one is today.
+    pub days: Vec<u64>,
+    /// Tasks created on each day, pending or archived.
+    pub added: Vec<u64>,
+    /// Tasks completed on each day; deleted tasks do not count.
+    pub completed: Vec<u64>,
+    /// Tasks pending at the end of each day, and right now for today.
+    pub backlog: Vec<u64>,
+    /// Mean seconds from `created_at` to completion over every completed task.
+    pub average_completion: Option<u64>,
+    /// Completed tasks the average is taken over.
+    pub completed_total: usize,
+    /// Id of the pending task created first.
+    pub oldest_pending: Option<u64>,
+}
+
+impl Stats {
+    /// Works out the figures for the `days` local days up to and including the one containing `now`.
+    pub fn new<'a>(
+        pending: impl IntoIterator<Item = &'a Todo>,
+        archive: &[ArchivedTodo],
+        days: usize,
+        now: u64,
+    ) -> Self {
+        let pending: Vec<&Todo> = pending.into_iter().collect();
+        let starts: Vec<u64> = (0..days as u64)
+            .rev()
+            .map(|ago| Moment::DaysAgo(ago).resolve(now))
+            .collect();
+        // Each day ends where the next starts; today takes in everything after its start.
+        let ends: Vec<u64> = starts
+            .iter()
+            .skip(1)
+            .copied()
+            .chain((days > 0).then_some(u64::MAX))
+            .collect();
+        // First day ending after `time`, or `days` when none does.
+        let day_ending_after = |time: u64| ends.partition_point(|end| *end <= time);
+        let done: Vec<&ArchivedTodo> = archive
+            .iter()
+            .filter(|entry| entry.outcome == Outcome::Done)
+            .collect();
+
+        let mut added = vec![0; days];
+        let mut completed = vec![0; days];
+        // A task was pending at a day's end when it existed by then and had not
+        // yet left the queue, so it adds one to the backlog from the day it was
+        // created up to the day it left; the running sum gives each day's count.
+        let mut backlog_delta = vec![0_i64; days + 1];
+        let mut count = |created_at: u64, left_at: u64| {
+            let first = day_ending_after(created_at);
+            if first < days && created_at >= starts[first] {
+                added[first] += 1;
+            }
+            let last = day_ending_after(left_at);
+            if first < last {
+                backlog_delta[first] += 1;
+                backlog_delta[last] -= 1;
+            }
+        };
+        for todo in &pending {
+            count(todo.created_at, u64::MAX);
+        }
+        for entry in archive {
+            count(entry.todo.created_at, entry.completed_at);
+        }
+        for entry in &done {
+            let day = day_ending_after(entry.completed_at);
+            if day < days && entry.completed_at >= starts[day] {
+                completed[day] += 1;
+            }
+        }
+        let backlog = backlog_delta[..days]
+            .iter()
+            .scan(0_i64, |pending, delta| {
+                *pending += delta;
+                Some(*pending as u64)
+            })
+            .collect();
+
+        let durations: Vec<u64> = done
+            .iter()
+            .map(|entry| entry.completed_at.saturating_sub(entry.todo.created_at))
+            .collect();
+        let average_completion =
+            (!durations.is_empty()).then(|| durations.iter().sum::<u64>() / durations.len() as u64);
+
+        Self {
+            added,
+            completed,
+            backlog,
+            days: starts,
+            average_completion,
+            completed_total: durations.len(),
+            oldest_pending: pending
+                .iter()
+                .min_by_key(|todo| (todo.created_at, todo.id))
+                .map(|todo| todo.id),
+        }
+    }
+
+    /// How much the backlog grew, or shrank when negative, from the end of the
+    /// first day covered to now.
+    pub fn backlog_change(&self) -> i64 {
+        match (self.backlog.first(), self.backlog.last()) {
+            (Some(first), Some(last)) => *last as i64 - *first as i64,
+            _ => 0,
+        }
+    }
+}
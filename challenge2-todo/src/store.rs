@@ -0,0 +1,579 @@
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use borsh::BorshDeserialize;
+
+use crate::{Priority, Recurrence, Subtask, TimeEntry, Todo};
+
+/// First bytes of a versioned queue file.
+pub const MAGIC: [u8; 4] = *b"TODO";
+
+/// Version written by this build. Bump it whenever `Todo` changes shape and
+/// add a decoder for the previous layout to `decode`.
+pub const FORMAT_VERSION: u16 = 6;
+
+/// Version reported for header-less files written before versioning existed.
+pub const LEGACY_VERSION: u16 = 0;
+
+const HEADER_LEN: usize = MAGIC.len() + 2;
+
+/// Task layouts of header-less files, oldest first. Before the header existed
+/// each release appended fields to `Todo`, so a file can hold any of these.
+#[derive(BorshDeserialize)]
+struct LegacyTodo {
+    id: u64,
+    description: String,
+    created_at: u64,
+}
+
+#[derive(BorshDeserialize)]
+struct LegacyTodoWithPriority {
+    base: LegacyTodo,
+    priority: Priority,
+}
+
+#[derive(BorshDeserialize)]
+struct LegacyTodoWithDue {
+    base: LegacyTodoWithPriority,
+    due_at: Option<u64>,
+}
+
+#[derive(BorshDeserialize)]
+struct LegacyTodoWithTags {
+    base: LegacyTodoWithDue,
+    project: Option<String>,
+    tags: Vec<String>,
+}
+
+#[derive(BorshDeserialize)]
+struct LegacyTodoWithRecurrence {
+    base: LegacyTodoWithTags,
+    recurrence: Option<RecurrenceV5>,
+}
+
+impl From<LegacyTodo> for TodoV2 {
+    fn from(legacy: LegacyTodo) -> Self {
+        Self {
+            id: legacy.id,
+            description: legacy.description,
+            created_at: legacy.created_at,
+            priority: Priority::default(),
+            due_at: None,
+            project: None,
+            tags: Vec::new(),
+            recurrence: None,
+            subtasks: Vec::new(),
+            blocked_by: Vec::new(),
+        }
+    }
+}
+
+impl From<LegacyTodoWithPriority> for TodoV2 {
+    fn from(legacy: LegacyTodoWithPriority) -> Self {
+        Self {
+            priority: legacy.priority,
+            ..legacy.base.into()
+        }
+    }
+}
+
+impl From<LegacyTodoWithDue> for TodoV2 {
+    fn from(legacy: LegacyTodoWithDue) -> Self {
+        Self {
+            due_at: legacy.due_at,
+            ..legacy.base.into()
+        }
+    }
+}
+
+impl From<LegacyTodoWithTags> for TodoV2 {
+    fn from(legacy: LegacyTodoWithTags) -> Self {
+        Self {
+            project: legacy.project,
+            tags: legacy.tags,
+            ..legacy.base.into()
+        }
+    }
+}
+
+impl From<LegacyTodoWithRecurrence> for TodoV2 {
+    fn from(legacy: LegacyTodoWithRecurrence) -> Self {
+        Self {
+            recurrence: legacy.recurrence,
+            ..legacy.base.into()
+        }
+    }
+}
+
+/// Reads a header-less Borsh `Vec` whose entries are a task followed by `E`
+/// (`()` for a queue file, the completion fields for an archive).
+///
+/// Layouts are tried newest first. Borsh must consume every byte, so a file only
+/// matches the layout it was written with; `None` means none of them fit.
+pub(crate) fn decode_legacy<E: BorshDeserialize>(bytes: &[u8]) -> Option<Vec<(Todo, E)>> {
+    fn read<T, E>(bytes: &[u8]) -> Option<Vec<(Todo, E)>>
+    where
+        T: BorshDeserialize + Into<TodoV2>,
+        E: BorshDeserialize,
+    {
+        let entries = Vec::<(T, E)>::try_from_slice(bytes).ok()?;
+        Some(
+            entries
+                .into_iter()
+                .map(|(todo, extra)| (Todo::from(todo.into()), extra))
+                .collect(),
+        )
+    }
+
+    read::<TodoV2, E>(bytes)
+        .or_else(|| read::<LegacyTodoWithRecurrence, E>(bytes))
+        .or_else(|| read::<LegacyTodoWithTags, E>(bytes))
+        .or_else(|| read::<LegacyTodoWithDue, E>(bytes))
+        .or_else(|| read::<LegacyTodoWithPriority, E>(bytes))
+        .or_else(|| read::<LegacyTodo, E>(bytes))
+}
+
+/// Task layout of versions 1 and 2, before `metadata` was added.
+///
+/// Archives and journals written by those versions embed it too, as do the last
+/// header-less files.
+#[derive(BorshDeserialize)]
+pub(crate) struct TodoV2 {
+    id: u64,
+    description: String,
+    created_at: u64,
+    priority: Priority,
+    due_at: Option<u64>,
+    project: Option<String>,
+    tags: Vec<String>,
+    recurrence: Option<RecurrenceV5>,
+    subtasks: Vec<Subtask>,
+    blocked_by: Vec<u64>,
+}
+
+impl From<TodoV2> for Todo {
+    fn from(old: TodoV2) -> Self {
+        Self {
+            id: old.id,
+            description: old.description,
+            created_at: old.created_at,
+            priority: old.priority,
+            due_at: old.due_at,
+            project: old.project,
+            tags: old.tags,
+            recurrence: upgrade_recurrence(old.recurrence, old.due_at, old.created_at),
+            subtasks: old.subtasks,
+            blocked_by: old.blocked_by,
+            metadata: Vec::new(),
+            notes: None,
+            attachments: Vec::new(),
+            time_entries: Vec::new(),
+        }
+    }
+}
+
+/// Task layout of version 3, before `notes` and `attachments` were added.
+///
+/// Archives and journals written by that version embed it too.
+#[derive(BorshDeserialize)]
+pub(crate) struct TodoV3 {
+    id: u64,
+    description: String,
+    created_at: u64,
+    priority: Priority,
+    due_at: Option<u64>,
+    project: Option<String>,
+    tags: Vec<String>,
+    recurrence: Option<RecurrenceV5>,
+    subtasks: Vec<Subtask>,
+    blocked_by: Vec<u64>,
+    metadata: Vec<(String, String)>,
+}
+
+impl From<TodoV3> for Todo {
+    fn from(old: TodoV3) -> Self {
+        Self {
+            id: old.id,
+            description: old.description,
+            created_at: old.created_at,
+            priority: old.priority,
+            due_at: old.due_at,
+            project: old.project,
+            tags: old.tags,
+            recurrence: upgrade_recurrence(old.recurrence, old.due_at, old.created_at),
+            subtasks: old.subtasks,
+            blocked_by: old.blocked_by,
+            metadata: old.metadata,
+            notes: None,
+            attachments: Vec::new(),
+            time_entries: Vec::new(),
+        }
+    }
+}
+
+/// Task layout of version 4, before `time_entries` was added.
+///
+/// Archives and journals written by that version embed it too.
+#[derive(BorshDeserialize)]
+pub(crate) struct TodoV4 {
+    id: u64,
+    description: String,
+    created_at: u64,
+    priority: Priority,
+    due_at: Option<u64>,
+    project: Option<String>,
+    tags: Vec<String>,
+    recurrence: Option<RecurrenceV5>,
+    subtasks: Vec<Subtask>,
+    blocked_by: Vec<u64>,
+    metadata: Vec<(String, String)>,
+    notes: Option<String>,
+    attachments: Vec<String>,
+}
+
+impl From<TodoV4> for Todo {
+    fn from(old: TodoV4) -> Self {
+        Self {
+            id: old.id,
+            description: old.description,
+            created_at: old.created_at,
+            priority: old.priority,
+            due_at: old.due_at,
+            project: old.project,
+            tags: old.tags,
+            recurrence: upgrade_recurrence(old.recurrence, old.due_at, old.created_at),
+            subtasks: old.subtasks,
+            blocked_by: old.blocked_by,
+            metadata: old.metadata,
+            notes: old.notes,
+            attachments: old.attachments,
+            time_entries: Vec::new(),
+        }
+    }
+}
+
+/// Task layout of version 5, before `Recurrence::Monthly` kept its day of the month.
+///
+/// Archives and journals written by that version embed it too.
+#[derive(BorshDeserialize)]
+pub(crate) struct TodoV5 {
+    id: u64,
+    description: String,
+    created_at: u64,
+    priority: Priority,
+    due_at: Option<u64>,
+    project: Option<String>,
+    tags: Vec<String>,
+    recurrence: Option<RecurrenceV5>,
+    subtasks: Vec<Subtask>,
+    blocked_by: Vec<u64>,
+    metadata: Vec<(String, String)>,
+    notes: Option<String>,
+    attachments: Vec<String>,
+    time_entries: Vec<TimeEntry>,
+}
+
+impl From<TodoV5> for Todo {
+    fn from(old: TodoV5) -> Self {
+        Self {
+            id: old.id,
+            description: old.description,
+            created_at: old.created_at,
+            priority: old.priority,
+            due_at: old.due_at,
+            project: old.project,
+            tags: old.tags,
+            recurrence: upgrade_recurrence(old.recurrence, old.due_at, old.created_at),
+            subtasks: old.subtasks,
+            blocked_by: old.blocked_by,
+            metadata: old.metadata,
+            notes: old.notes,
+            attachments: old.attachments,
+            time_entries: old.time_entries,
+        }
+    }
+}
+
+/// `Recurrence` as written up to version 5, when `Monthly` had no day.
+#[derive(Clone, Copy, BorshDeserialize)]
+pub(crate) enum RecurrenceV5 {
+    Daily,
+    Weekdays,
+    Weekly(u8),
+    Monthly,
+    EveryDays(u16),
+}
+
+/// Converts an old rule, pinning `Monthly` to the day of its due date, or of
+/// its creation when it has none, as adding the task does now.
+fn upgrade_recurrence(
+    old: Option<RecurrenceV5>,
+    due_at: Option<u64>,
+    created_at: u64,
+) -> Option<Recurrence> {
+    let recurrence = match old? {
+        RecurrenceV5::Daily => Recurrence::Daily,
+        RecurrenceV5::Weekdays => Recurrence::Weekdays,
+        RecurrenceV5::Weekly(days) => Recurrence::Weekly(days),
+        RecurrenceV5::Monthly => Recurrence::Monthly(0),
+        RecurrenceV5::EveryDays(days) => Recurrence::EveryDays(days),
+    };
+    Some(recurrence.anchored(due_at.unwrap_or(created_at)))
+}
+
+/// Decoded contents of a queue file.
+#[derive(Debug)]
+pub struct Snapshot {
+    pub todos: Vec<Todo>,
+    /// Format version the file was written with.
+    pub version: u16,
+    /// Journal generation the snapshot was taken at; only a journal with the
+    /// same generation is replayed on top of it.
+    pub generation: u64,
+}
+
+/// Serializes a snapshot as `MAGIC`, a little-endian `u16` version, then a Borsh
+/// `(generation: u64, Vec<Todo>)`.
+pub fn encode(todos: &[Todo], generation: u64) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN);
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    borsh::to_writer(&mut bytes, &(generation, todos))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+    Ok(bytes)
+}
+
+/// Reads a queue file of any known version.
+///
+/// Files without the header are read with whichever legacy layout fits and reported as
+/// `LEGACY_VERSION`. Versions newer than `FORMAT_VERSION` are rejected rather
+/// than guessed at. Files from before the journal existed report generation 0.
+pub fn decode(bytes: &[u8]) -> io::Result<Snapshot> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    // A legacy file starts with a u32 task count, which never spells out the magic.
+    let Some(body) = bytes.strip_prefix(&MAGIC) else {
+        let legacy = decode_legacy::<()>(bytes)
+            .ok_or_else(|| invalid(String::from("unrecognized todo file")))?;
+        return Ok(Snapshot {
+            todos: legacy.into_iter().map(|(todo, ())| todo).collect(),
+            version: LEGACY_VERSION,
+            generation: 0,
+        });
+    };
+
+    let Some((version, body)) = body.split_first_chunk::<2>() else {
+        return Err(invalid(String::from("todo file header is truncated")));
+    };
+    let version = u16::from_le_bytes(*version);
+
+    // Older versions get an arm here that converts their layout to the current `Todo`.
+    let decoded = match version {
+        // Version 1 had no journal, so no generation either.
+        1 => Vec::<TodoV2>::try_from_slice(body).map(|todos| (0, upgrade(todos))),
+        2 => <(u64, Vec<TodoV2>)>::try_from_slice(body)
+            .map(|(generation, todos)| (generation, upgrade(todos))),
+        3 => <(u64, Vec<TodoV3>)>::try_from_slice(body)
+            .map(|(generation, todos)| (generation, upgrade(todos))),
+        4 => <(u64, Vec<TodoV4>)>::try_from_slice(body)
+            .map(|(generation, todos)| (generation, upgrade(todos))),
+        5 => <(u64, Vec<TodoV5>)>::try_from_slice(body)
+            .map(|(generation, todos)| (generation, upgrade(todos))),
+        FORMAT_VERSION => <(u64, Vec<Todo>)>::try_from_slice(body),
+        _ => {
+            return Err(invalid(format!(
+                "todo file format version {version} is not supported (this build reads up to {FORMAT_VERSION})"
+            )));
+        }
+    };
+    let (generation, todos) =
+        decoded.map_err(|err| invalid(format!("corrupt version {version} todo file: {err}")))?;
+    Ok(Snapshot {
+        todos,
+        version,
+        generation,
+    })
+}
+
+fn upgrade<T: Into<Todo>>(todos: Vec<T>) -> Vec<Todo> {
+    todos.into_iter().map(Into::into).collect()
+}
+
+/// Where the pre-migration copy of a queue file is kept (`todos.bin` -> `todos.bin.bak`).
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+/// Advisory lock file guarding writes to a queue file (`todos.bin` -> `todos.bin.lock`).
+pub fn lock_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".lock")
+}
+
+/// Appends `suffix` to the whole file name, so `todos.bin` and `todos.txt` get
+/// different siblings.
+pub(crate) fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Replaces `path` with `bytes` so readers see either the old or the new file, never a torn one.
+///
+/// Writes a sibling temp file, syncs it, renames it over `path`, then syncs the
+/// directory so the rename itself survives a crash.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let temp_path = with_suffix(path, ".tmp");
+    let mut file = File::create(&temp_path)?;
+    file.write_all(bytes)?;
+    file.sync_all()?;
+    drop(file);
+
+    if let Err(err) = fs::rename(&temp_path, path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(err);
+    }
+
+    // Directories can only be opened for syncing on Unix; elsewhere the rename is the best we get.
+    #[cfg(unix)]
+    {
+        let parent = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Identity of a file's current contents, used to notice writes from other processes.
+///
+/// Atomic writes replace the file, so on Unix the inode changes on every save
+/// even when size and timestamp happen to match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+    inode: u64,
+}
+
+/// Stamp of the file at `path`, or `None` when it does not exist.
+pub fn stamp(path: &Path) -> io::Result<Option<FileStamp>> {
+    let metadata = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    #[cfg(unix)]
+    let inode = std::os::unix::fs::MetadataExt::ino(&metadata);
+    #[cfg(not(unix))]
+    let inode = 0;
+
+    Ok(Some(FileStamp {
+        len: metadata.len(),
+        modified: metadata.modified().ok(),
+        inode,
+    }))
+}
+
+/// Held while a process writes the store; dropping it releases the lock.
+///
+/// The lock is an advisory OS lock on the lock file: it only keeps `todo`
+/// processes from interleaving their writes, and the OS releases it when the
+/// owner exits, so a crashed process never leaves the store locked. The file
+/// holds the owner's pid for error messages.
+#[derive(Debug)]
+pub struct StoreLock {
+    path: PathBuf,
+    file: File,
+}
+
+impl StoreLock {
+    /// Takes the lock for the store at `store_path`, waiting up to a second for another writer.
+    ///
+    /// Fails with `WouldBlock` and a "store is locked by pid N" message when it stays held.
+    pub fn acquire(store_path: &Path) -> io::Result<Self> {
+        const ATTEMPTS: u32 = 20;
+        let mut attempt = 1;
+        loop {
+            match Self::try_acquire(store_path) {
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock && attempt < ATTEMPTS => {
+                    attempt += 1;
+                    thread::sleep(Duration::from_millis(50));
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Takes the lock without waiting.
+    pub fn try_acquire(store_path: &Path) -> io::Result<Self> {
+        let path = lock_path(store_path);
+        loop {
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)?;
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => return Err(locked(&path)),
+                Err(TryLockError::Error(err)) => return Err(err),
+            }
+            // The previous owner removes the file before unlocking it. If that
+            // happened after we opened it, lock whatever the path names now.
+            if !names_file(&path, &file)? {
+                continue;
+            }
+            file.set_len(0)?;
+            write!(file, "{}", std::process::id())?;
+            return Ok(Self { path, file });
+        }
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        // Remove the file before unlocking, so a waiter that opened it sees it is gone.
+        let _ = fs::remove_file(&self.path);
+        let _ = self.file.unlock();
+    }
+}
+
+fn locked(path: &Path) -> io::Error {
+    let owner = fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| contents.trim().parse::<u32>().ok());
+    let message = match owner {
+        Some(pid) => format!("store is locked by pid {pid} ({})", path.display()),
+        // The owner has locked the file but not written its pid yet.
+        None => format!("store is locked by another process ({})", path.display()),
+    };
+    io::Error::new(io::ErrorKind::WouldBlock, message)
+}
+
+/// Whether `path` still refers to the open `file`.
+fn names_file(path: &Path, file: &File) -> io::Result<bool> {
+    let current = match fs::metadata(path) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err),
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let held = file.metadata()?;
+        Ok(current.dev() == held.dev() && current.ino() == held.ino())
+    }
+    // Without inode numbers, a file that still exists is taken to be the same one.
+    #[cfg(not(unix))]
+    {
+        let _ = (current, file);
+        Ok(true)
+    }
+}
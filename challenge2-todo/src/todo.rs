@@ -1,9 +1,255 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use crate::{Recurrence, dates};
+
 /// Single todo item persisted to disk.
 #[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
 pub struct Todo {
     pub id: u64,
     pub description: String,
     pub created_at: u64,
+    pub priority: Priority,
+    /// Unix seconds the task is due by, if it has a deadline.
+    pub due_at: Option<u64>,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+    /// Set for tasks that are re-added with a new due date when completed.
+    pub recurrence: Option<Recurrence>,
+    /// Checklist items, in the order they were added.
+    pub subtasks: Vec<Subtask>,
+    /// Ids of tasks that must leave the pending queue before this one can be completed.
+    pub blocked_by: Vec<u64>,
+    /// `key`/`value` pairs from imported files that have no field of their own,
+    /// in file order, so exporting writes them back.
+    pub metadata: Vec<(String, String)>,
+    /// Long-form notes edited in `$EDITOR`, possibly several lines.
+    pub notes: Option<String>,
+    /// File paths and URLs attached to the task, in the order they were added.
+    pub attachments: Vec<String>,
+    /// Time worked on the task, oldest first; only the last entry may still be running.
+    pub time_entries: Vec<TimeEntry>,
+}
+
+/// One stretch of time spent on a task, in Unix seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct TimeEntry {
+    pub started_at: u64,
+    /// `None` while the timer is running.
+    pub stopped_at: Option<u64>,
+}
+
+impl TimeEntry {
+    /// Seconds of this entry that fall between `from` and `to`, with a running
+    /// timer counting up to `to`.
+    pub fn seconds_between(&self, from: u64, to: u64) -> u64 {
+        let end = self.stopped_at.unwrap_or(to).min(to);
+        end.saturating_sub(self.started_at.max(from))
+    }
+}
+
+/// One checklist item inside a task.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct Subtask {
+    pub title: String,
+    pub done: bool,
+}
+
+/// A task description with inline `+project` and `@tag` tokens pulled out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineTags {
+    pub description: String,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl InlineTags {
+    /// Splits `text` into a description and its `+project`/`@tag` tokens.
+    ///
+    /// A token needs a letter right after the sigil, so `+1` or `@ home` stay in
+    /// the description. When several projects are given the last one wins.
+    pub fn parse(text: &str) -> Self {
+        let mut words = Vec::new();
+        let mut project = None;
+        let mut tags: Vec<String> = Vec::new();
+
+        for word in text.split_whitespace() {
+            if let Some(name) = sigil_name(word, '+') {
+                project = Some(name.to_string());
+            } else if let Some(name) = sigil_name(word, '@') {
+                if !tags.iter().any(|tag| tag.eq_ignore_ascii_case(name)) {
+                    tags.push(name.to_string());
+                }
+            } else {
+                words.push(word);
+            }
+        }
+
+        Self {
+            description: words.join(" "),
+            project,
+            tags,
+        }
+    }
+}
+
+/// The name after `sigil` in a `+project` or `@tag` word.
+pub(crate) fn sigil_name(word: &str, sigil: char) -> Option<&str> {
+    word.strip_prefix(sigil)
+        .filter(|name| name.starts_with(|ch: char| ch.is_alphabetic()))
+}
+
+/// Where a task's deadline falls relative to the current local day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueStatus {
+    /// The deadline has passed.
+    Overdue,
+    /// Due later today.
+    Today,
+    /// Due after today.
+    Upcoming,
+}
+
+impl Todo {
+    /// Project and tags in inline form (`+backend @urgent`), empty when there are none.
+    pub fn labels(&self) -> String {
+        self.project
+            .iter()
+            .map(|project| format!("+{project}"))
+            .chain(self.tags.iter().map(|tag| format!("@{tag}")))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Every piece of text `todo search` looks at: the description, project,
+    /// tags, subtask titles, metadata values, notes and attachments. New text
+    /// fields belong here.
+    pub fn search_text(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.description.as_str())
+            .chain(self.project.as_deref())
+            .chain(self.tags.iter().map(String::as_str))
+            .chain(self.subtasks.iter().map(|subtask| subtask.title.as_str()))
+            .chain(self.metadata.iter().map(|(_, value)| value.as_str()))
+            .chain(self.notes.as_deref())
+            .chain(self.attachments.iter().map(String::as_str))
+    }
+
+    /// When the running timer on this task was started, if one is running.
+    pub fn running_since(&self) -> Option<u64> {
+        self.time_entries
+            .last()
+            .filter(|entry| entry.stopped_at.is_none())
+            .map(|entry| entry.started_at)
+    }
+
+    /// Stops the running timer at `at`, never before it started; does nothing
+    /// when no timer is running.
+    pub fn stop_timer(&mut self, at: u64) {
+        if let Some(entry) = self.time_entries.last_mut()
+            && entry.stopped_at.is_none()
+        {
+            entry.stopped_at = Some(at.max(entry.started_at));
+        }
+    }
+
+    /// Seconds spent on the task between `from` and `to`.
+    pub fn time_spent(&self, from: u64, to: u64) -> u64 {
+        self.time_entries
+            .iter()
+            .map(|entry| entry.seconds_between(from, to))
+            .sum()
+    }
+
+    /// Classifies `due_at` against `now`; `None` when the task has no deadline.
+    pub fn due_status(&self, now: u64) -> Option<DueStatus> {
+        let due_at = self.due_at?;
+        if due_at < now {
+            return Some(DueStatus::Overdue);
+        }
+
+        let tomorrow = dates::start_of_local_day(now, 1).unwrap_or(u64::MAX);
+        if due_at < tomorrow {
+            Some(DueStatus::Today)
+        } else {
+            Some(DueStatus::Upcoming)
+        }
+    }
+}
+
+/// Urgency of a task. Higher priorities are completed first.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    /// All priorities from lowest to highest.
+    pub const ALL: [Priority; 4] = [
+        Priority::Low,
+        Priority::Normal,
+        Priority::High,
+        Priority::Urgent,
+    ];
+
+    /// Zero-based level used by `PriorityQueue` (`Low = 0`).
+    pub fn level(self) -> usize {
+        self as usize
+    }
+
+    /// Next priority up, saturating at `Urgent`.
+    pub fn raised(self) -> Self {
+        Self::ALL[(self.level() + 1).min(Self::ALL.len() - 1)]
+    }
+
+    /// Next priority down, saturating at `Low`.
+    pub fn lowered(self) -> Self {
+        Self::ALL[self.level().saturating_sub(1)]
+    }
+
+    /// Lowercase name used by the CLI and list output.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+            Self::Urgent => "urgent",
+        }
+    }
+}
+
+impl Display for Priority {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.label())
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|priority| priority.label().eq_ignore_ascii_case(value))
+            .ok_or_else(|| {
+                format!("unknown priority '{value}' (expected low, normal, high or urgent)")
+            })
+    }
 }
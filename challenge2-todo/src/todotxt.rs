@@ -0,0 +1,245 @@
+use std::io::{self, Write};
+
+use crate::dates::{format_date, format_due, parse_date, parse_due};
+use crate::export::TaskRecord;
+use crate::todo::sigil_name;
+use crate::{ArchivedTodo, Outcome, Priority, Recurrence, Todo};
+
+/// Metadata key keeping a priority letter that the task's `Priority` would not
+/// write back by itself, such as `C` (normal) or `E` (low). In todo.txt files
+/// it is also the key carrying the priority of finished tasks (`pri:A`).
+pub const PRIORITY_KEY: &str = "pri";
+
+/// Reads a todo.txt file, one task per non-blank line.
+///
+/// A line looks like `x 2026-10-19 2026-10-01 (A) Call Mom +family @phone due:2026-10-20`:
+/// a leading `x` and completion date mark a finished task, which comes back
+/// with `completed_at` (`now` when the date is missing). An optional `(A)`
+/// priority and creation date follow; the rest is read by `parse_text`.
+pub fn parse(text: &str, now: u64) -> Vec<TaskRecord> {
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| parse_line(line, now))
+        .collect()
+}
+
+/// Writes pending tasks in queue order, then the finished tasks in `archived` as `x` lines.
+///
+/// Deleted tasks are skipped. Subtasks and blockers have no todo.txt form and are not written.
+pub fn write<'a>(
+    pending: impl IntoIterator<Item = &'a Todo>,
+    archived: impl IntoIterator<Item = &'a ArchivedTodo>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for todo in pending {
+        let priority = priority_letter(todo)
+            .map(|letter| format!("({letter}) "))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{priority}{} {}",
+            format_date(todo.created_at),
+            format_words(todo, &[PRIORITY_KEY])
+        )?;
+    }
+
+    for entry in archived
+        .into_iter()
+        .filter(|entry| entry.outcome == Outcome::Done)
+    {
+        let todo = &entry.todo;
+        // Finished tasks keep their priority as a key, so the line still starts with `x`.
+        let priority = priority_letter(todo)
+            .map(|letter| format!(" {PRIORITY_KEY}:{letter}"))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "x {} {} {}{priority}",
+            format_date(entry.completed_at),
+            format_date(todo.created_at),
+            format_words(todo, &[PRIORITY_KEY])
+        )?;
+    }
+    Ok(())
+}
+
+/// Reads the text of one task: an optional leading `(A)` priority, then words.
+///
+/// `(A)` is urgent, `(B)` high, `(C)` normal and `(D)` to `(Z)` low. The last
+/// `+project` becomes the project (earlier ones stay in the description), every
+/// `@context` becomes a tag, `due:` takes anything `parse_due` accepts (with
+/// `T` between date and time), and `rec:` takes a `Recurrence` rule. Any other
+/// `key:value` word, or a `due:`/`rec:` value that does not parse, is kept in
+/// the task's metadata. The remaining words are the description.
+pub(crate) fn parse_text(text: &str, now: u64) -> TaskRecord {
+    let (letter, rest) = split_priority(text.trim());
+    let mut record = parse_words(rest, now);
+    if let Some(letter) = letter {
+        set_priority_letter(&mut record, letter);
+    }
+    record
+}
+
+/// The description followed by the task's fields as todo.txt words, leaving out
+/// metadata under the keys in `skip` and values that cannot be a single word.
+pub(crate) fn format_words(todo: &Todo, skip: &[&str]) -> String {
+    let mut words = vec![todo.description.clone()];
+    if !todo.labels().is_empty() {
+        words.push(todo.labels());
+    }
+    if let Some(due_at) = todo.due_at {
+        words.push(format!("due:{}", format_due(due_at).replace(' ', "T")));
+    }
+    if let Some(recurrence) = todo.recurrence {
+        words.push(format!("rec:{recurrence}"));
+    }
+    for (key, value) in &todo.metadata {
+        let one_word = !key.contains(char::is_whitespace) && !value.contains(char::is_whitespace);
+        if one_word && !skip.contains(&key.as_str()) {
+            words.push(format!("{key}:{value}"));
+        }
+    }
+    words.join(" ")
+}
+
+/// The letter written for a task's priority: the one it was imported with when
+/// that still matches, otherwise `A`, `B` or `D`; normal tasks get none.
+pub(crate) fn priority_letter(todo: &Todo) -> Option<char> {
+    let kept = todo
+        .metadata
+        .iter()
+        .find(|(key, _)| key == PRIORITY_KEY)
+        .and_then(|(_, value)| single_letter(value))
+        .filter(|&letter| priority_for_letter(letter) == todo.priority);
+    kept.or(default_letter(todo.priority))
+}
+
+fn parse_line(line: &str, now: u64) -> TaskRecord {
+    let mut rest = line.trim();
+    let mut completed_at = None;
+    if let Some(after) = rest.strip_prefix("x ") {
+        let (date, after) = split_date(after.trim_start());
+        completed_at = Some(date.unwrap_or(now));
+        rest = after;
+    }
+    let (letter, rest) = split_priority(rest);
+    let (created_at, rest) = split_date(rest);
+
+    let mut record = parse_words(rest, now);
+    if let Some(letter) = letter {
+        set_priority_letter(&mut record, letter);
+    }
+    record.created_at = created_at;
+    record.completed_at = completed_at;
+    record
+}
+
+fn parse_words(text: &str, now: u64) -> TaskRecord {
+    let mut record = TaskRecord::new(String::new());
+    let mut words = Vec::new();
+    // Like `InlineTags`, the last project wins; earlier ones stay where they were written.
+    let project_index = text
+        .split_whitespace()
+        .enumerate()
+        .filter(|(_, word)| sigil_name(word, '+').is_some())
+        .last()
+        .map(|(index, _)| index);
+
+    for (index, word) in text.split_whitespace().enumerate() {
+        if Some(index) == project_index
+            && let Some(name) = sigil_name(word, '+')
+        {
+            record.project = Some(name.to_string());
+        } else if let Some(name) = sigil_name(word, '@') {
+            if !record.tags.iter().any(|tag| tag.eq_ignore_ascii_case(name)) {
+                record.tags.push(name.to_string());
+            }
+        } else if let Some((key, value)) = key_value(word) {
+            match (key, single_letter(value)) {
+                ("due", _) if record.due_at.is_none() => {
+                    match parse_due(&value.replace('T', " "), now) {
+                        Ok(due_at) => record.due_at = Some(due_at),
+                        Err(_) => record.metadata.push((key.to_string(), value.to_string())),
+                    }
+                }
+                ("rec", _) if record.recurrence.is_none() => match value.parse::<Recurrence>() {
+                    Ok(recurrence) => record.recurrence = Some(recurrence.to_string()),
+                    Err(_) => record.metadata.push((key.to_string(), value.to_string())),
+                },
+                (PRIORITY_KEY, Some(letter)) => set_priority_letter(&mut record, letter),
+                _ => record.metadata.push((key.to_string(), value.to_string())),
+            }
+        } else {
+            words.push(word);
+        }
+    }
+
+    record.description = words.join(" ");
+    record
+}
+
+/// Splits `key:value` where the key is a word starting with a letter. URLs
+/// (`https://…`) and times (`10:30`) stay in the description.
+fn key_value(word: &str) -> Option<(&str, &str)> {
+    let (key, value) = word.split_once(':')?;
+    let key_ok = key.starts_with(|ch: char| ch.is_ascii_alphabetic())
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_');
+    (key_ok && !value.is_empty() && !value.starts_with('/')).then_some((key, value))
+}
+
+fn split_priority(text: &str) -> (Option<char>, &str) {
+    match text.as_bytes() {
+        [b'(', letter @ b'A'..=b'Z', b')', b' ', ..] => {
+            (Some(char::from(*letter)), text[4..].trim_start())
+        }
+        _ => (None, text),
+    }
+}
+
+/// Splits off a leading `YYYY-MM-DD` word, read as the start of that local day.
+fn split_date(text: &str) -> (Option<u64>, &str) {
+    let (word, rest) = text.split_once(' ').unwrap_or((text, ""));
+    match parse_date(word) {
+        Ok(date) if word.len() == "YYYY-MM-DD".len() => (Some(date), rest.trim_start()),
+        _ => (None, text),
+    }
+}
+
+fn set_priority_letter(record: &mut TaskRecord, letter: char) {
+    let priority = priority_for_letter(letter);
+    record.priority = priority.to_string();
+    record.metadata.retain(|(key, _)| key != PRIORITY_KEY);
+    if default_letter(priority) != Some(letter) {
+        record
+            .metadata
+            .push((PRIORITY_KEY.to_string(), letter.to_string()));
+    }
+}
+
+fn single_letter(value: &str) -> Option<char> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter @ 'A'..='Z'), None) => Some(letter),
+        _ => None,
+    }
+}
+
+fn priority_for_letter(letter: char) -> Priority {
+    match letter {
+        'A' => Priority::Urgent,
+        'B' => Priority::High,
+        'C' => Priority::Normal,
+        _ => Priority::Low,
+    }
+}
+
+fn default_letter(priority: Priority) -> Option<char> {
+    match priority {
+        Priority::Urgent => Some('A'),
+        Priority::High => Some('B'),
+        Priority::Normal => None,
+        Priority::Low => Some('D'),
+    }
+}
@@ -1,48 +1,147 @@
 use std::io;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use challenge2_todo::{Todo, TodoApp};
-use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use challenge2_todo::app::format_ids;
+use challenge2_todo::config::{self, list_path};
+use challenge2_todo::dates::{
+    Moment, TimeFormat, format_date, format_due, format_duration, unix_now,
+};
+use challenge2_todo::search::highlight;
+use challenge2_todo::stats::Stats;
+use challenge2_todo::store::FileStamp;
+use challenge2_todo::{
+    ArchivedTodo, DueStatus, Filter, InlineTags, NewTask, Priority, SearchHit, Todo, TodoApp,
+};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use crossterm::execute;
 use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
 };
-use ratatui::backend::CrosstermBackend;
-use ratatui::layout::{Constraint, Direction, Layout};
-use ratatui::style::{Color, Style};
-use ratatui::text::Line;
-use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
 use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{
+    Bar, BarChart, BarGroup, Block, Borders, Clear, List, ListItem, ListState, Paragraph,
+    Sparkline, Wrap,
+};
 
+use crate::editor;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum Mode {
     // Browse list + run shortcuts.
     Normal,
     // Type into input buffer to create a new task.
     Adding,
+    // Edit the description of the task with this id in the input buffer.
+    Editing(u64),
+    // Type a filter expression; the pending list narrows as it changes.
+    Filtering,
+    // Type a search query; the search tab updates as it changes.
+    Searching,
+    // Pick another named list from a popup.
+    Switching,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tab {
+    // Pending queue.
+    Pending,
+    // Completed and deleted tasks.
+    Archive,
+    // Pending and archived tasks matching the search query, best first.
+    Search,
+    // Activity charts worked out from the queue and the archive.
+    Stats,
 }
 
+/// Days of activity the statistics tab charts.
+const STATS_DAYS: usize = 30;
+
+/// Of those, the most recent days the backlog bar chart shows.
+const BACKLOG_DAYS: usize = 14;
+
 struct UiState {
     mode: Mode,
+    tab: Tab,
     input: String,
     status: String,
     list_state: ListState,
+    archive_state: ListState,
+    // Last filter expression that parsed; the pending list only shows matches.
+    filter: Option<Filter>,
+    filter_input: String,
+    search_input: String,
+    search_state: ListState,
+    // Lines the detail pane is scrolled by, for the task `detail_task`.
+    detail_scroll: u16,
+    detail_task: Option<u64>,
+    // Task whose notes to open in the editor once the key handler returns.
+    edit_notes: Option<u64>,
+    // Named lists in `data_dir`, refreshed whenever the switcher opens.
+    data_dir: PathBuf,
+    current_list: Option<String>,
+    lists: Vec<String>,
+    lists_state: ListState,
+    // How creation and archive times are rendered.
+    time_format: TimeFormat,
+    // Priority of added tasks, from the config file.
+    priority: Priority,
+    // Figures for the statistics tab, with the store stamps and day they were worked out for.
+    stats: Option<(StatsKey, Stats)>,
 }
 
+type StatsKey = ((Option<FileStamp>, Option<FileStamp>), u64);
+
 impl UiState {
-    fn new() -> Self {
+    fn new(
+        data_dir: &Path,
+        current_list: Option<&str>,
+        time_format: TimeFormat,
+        priority: Priority,
+    ) -> Self {
         let mut list_state = ListState::default();
         list_state.select(None);
 
         Self {
             mode: Mode::Normal,
+            tab: Tab::Pending,
             input: String::new(),
             status: String::from("Ready. Select a task with arrows, then use shortcuts below."),
             list_state,
+            archive_state: ListState::default(),
+            filter: None,
+            filter_input: String::new(),
+            search_input: String::new(),
+            search_state: ListState::default(),
+            detail_scroll: 0,
+            detail_task: None,
+            edit_notes: None,
+            data_dir: data_dir.to_path_buf(),
+            current_list: current_list.map(str::to_string),
+            lists: Vec::new(),
+            lists_state: ListState::default(),
+            time_format,
+            priority,
+            stats: None,
         }
     }
 }
 
-pub fn run(app: &mut TodoApp) -> io::Result<()> {
+/// Runs the TUI on `app`; the list switcher offers the named lists in `data_dir`.
+///
+/// Tasks added in it get `priority`, the configured default.
+pub fn run(
+    app: &mut TodoApp,
+    data_dir: &Path,
+    current_list: Option<&str>,
+    time_format: TimeFormat,
+    priority: Priority,
+) -> io::Result<()> {
     // Terminal setup for full-screen TUI.
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -50,7 +149,11 @@ pub fn run(app: &mut TodoApp) -> io::Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = run_loop(&mut terminal, app);
+    let result = run_loop(
+        &mut terminal,
+        app,
+        UiState::new(data_dir, current_list, time_format, priority),
+    );
 
     // Always restore terminal state before returning.
     disable_raw_mode()?;
@@ -63,13 +166,61 @@ pub fn run(app: &mut TodoApp) -> io::Result<()> {
 fn run_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut TodoApp,
+    mut state: UiState,
 ) -> io::Result<()> {
-    let mut state = UiState::new();
-
     loop {
-        // Snapshot tasks for this frame render.
-        let tasks: Vec<Todo> = app.list_tasks().cloned().collect();
-        clamp_selection(&mut state, tasks.len());
+        // Pick up writes from other `todo` processes (e.g. `todo add` in another terminal).
+        if app.reload_if_changed()? {
+            state.status = String::from("Reloaded: the todo store changed on disk.");
+        }
+
+        // Statistics only change with the tasks or when a new day starts.
+        if state.tab == Tab::Stats {
+            let now = unix_now();
+            let key = (app.stamps(), Moment::DaysAgo(0).resolve(now));
+            if state
+                .stats
+                .as_ref()
+                .is_none_or(|(cached, _)| *cached != key)
+            {
+                state.stats = Some((key, app.stats(STATS_DAYS, now)));
+            }
+        }
+
+        let tasks = visible_tasks(&state, app);
+        let archived: Vec<&ArchivedTodo> = app.history().collect();
+        let hits = if state.tab == Tab::Search {
+            app.search(&state.search_input)
+        } else {
+            Vec::new()
+        };
+        clamp_selection(&mut state.list_state, tasks.len());
+        clamp_selection(&mut state.archive_state, archived.len());
+        clamp_selection(&mut state.search_state, hits.len());
+
+        // A newly selected task starts with its details scrolled to the top.
+        let detail_task = match state.tab {
+            Tab::Pending => state
+                .list_state
+                .selected()
+                .and_then(|index| tasks.get(index))
+                .map(|todo| todo.id),
+            Tab::Archive => state
+                .archive_state
+                .selected()
+                .and_then(|index| archived.get(index))
+                .map(|entry| entry.todo.id),
+            Tab::Search => state
+                .search_state
+                .selected()
+                .and_then(|index| hits.get(index))
+                .map(|hit| hit.todo.id),
+            Tab::Stats => None,
+        };
+        if detail_task != state.detail_task {
+            state.detail_task = detail_task;
+            state.detail_scroll = 0;
+        }
 
         terminal.draw(|frame| {
             let chunks = Layout::default()
@@ -90,66 +241,97 @@ fn run_loop(
             let mode_label = match state.mode {
                 Mode::Normal => "Normal",
                 Mode::Adding => "Adding",
+                Mode::Editing(_) => "Editing",
+                Mode::Filtering => "Filtering",
+                Mode::Searching => "Searching",
+                Mode::Switching => "Switching list",
             };
 
-            let title_text = format!(
-                "Todo Queue (Ratatui) | Mode: {mode_label} | Pending: {}",
+            let list_label = state.current_list.as_deref().unwrap_or("(file)");
+            let mut title_text = format!(
+                "Todo Queue (Ratatui) | List: {list_label} | Mode: {mode_label} | Pending: {}",
                 tasks.len()
             );
-            let title = Paragraph::new(title_text)
+            if state.filter.is_some() {
+                title_text.push_str(&format!(" of {} | Filter: {}", app.len(), state.filter_input));
+            }
+            if state.tab == Tab::Search {
+                title_text.push_str(&format!(" | Matches: {}", hits.len()));
+            }
+            if let Some(todo) = app.running_timer() {
+                let started_at = todo.running_since().unwrap_or_default();
+                let running = format_duration(unix_now().saturating_sub(started_at));
+                title_text.push_str(&format!(" | Timer: #{} {running}", todo.id));
+            }
+            let title = Paragraph::new(vec![Line::from(title_text), tab_line(state.tab)])
                 .block(Block::default().borders(Borders::ALL).title("Challenge 2"));
             frame.render_widget(title, chunks[0]);
 
-            let items = if tasks.is_empty() {
-                vec![ListItem::new("No pending tasks")]
-            } else {
-                tasks
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, todo)| {
-                        ListItem::new(format!(
-                            "{:>2}. #{} [{}] {}",
-                            idx + 1,
-                            todo.id,
-                            todo.created_at,
-                            todo.description
-                        ))
-                    })
-                    .collect()
-            };
-
-            let list = List::new(items)
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .title("Pending Tasks (j/k or arrows)"),
-                )
-                .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan))
-                .highlight_symbol("> ");
-            frame.render_stateful_widget(list, body_chunks[0], &mut state.list_state);
-
-            let detail_text = selected_detail_text(&tasks, &state);
-            let detail = Paragraph::new(detail_text).block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Selected Task"),
-            );
-            frame.render_widget(detail, body_chunks[1]);
+            match state.tab {
+                Tab::Pending => render_pending(
+                    frame,
+                    &body_chunks,
+                    &tasks,
+                    &mut state.list_state,
+                    &mut state.detail_scroll,
+                    &state.time_format,
+                    app,
+                ),
+                Tab::Archive => render_archive(
+                    frame,
+                    &body_chunks,
+                    &archived,
+                    &mut state.archive_state,
+                    &mut state.detail_scroll,
+                    &state.time_format,
+                ),
+                Tab::Search => render_search(
+                    frame,
+                    &body_chunks,
+                    &hits,
+                    &state.search_input,
+                    &mut state.search_state,
+                    &mut state.detail_scroll,
+                    &state.time_format,
+                ),
+                Tab::Stats => {
+                    if let Some((_, stats)) = &state.stats {
+                        render_stats(frame, chunks[1], stats, app, &state.time_format);
+                    }
+                }
+            }
 
             let input_title = match state.mode {
-                Mode::Normal => "Input (press 'a' to add)",
+                Mode::Normal | Mode::Switching => "Input (press 'a' to add, 'e' to edit)",
                 Mode::Adding => "Add Task (type and press Enter)",
+                Mode::Editing(_) => "Edit Task (press Enter to save, Esc to cancel)",
+                Mode::Filtering => "Filter (e.g. project:backend and not overdue; Enter keeps, Esc clears)",
+                Mode::Searching => "Search pending and archived tasks (Enter keeps, Esc clears)",
             };
             let input_text = match state.mode {
-                Mode::Normal => String::from(""),
-                Mode::Adding => state.input.clone(),
+                Mode::Normal | Mode::Switching => String::from(""),
+                Mode::Adding | Mode::Editing(_) => state.input.clone(),
+                Mode::Filtering => state.filter_input.clone(),
+                Mode::Searching => state.search_input.clone(),
             };
             let input = Paragraph::new(input_text)
                 .block(Block::default().borders(Borders::ALL).title(input_title));
             frame.render_widget(input, chunks[2]);
 
+            let shortcuts = match state.tab {
+                Tab::Pending => {
+                    "a: add  e: edit  n: notes  t: timer  Enter: complete selected  d: complete next  x/Delete: delete  +/-: priority  u/Ctrl-r: undo/redo  /: filter  s: search  L: lists  Tab: archive  q: quit"
+                }
+                Tab::Archive => {
+                    "r: restore selected  u/Ctrl-r: undo/redo  s: search  Tab: search results  q: quit"
+                }
+                Tab::Search => {
+                    "s: change search  Enter: go to selected task  u/Ctrl-r: undo/redo  Tab: stats  q: quit"
+                }
+                Tab::Stats => "u/Ctrl-r: undo/redo  s: search  Tab: pending  q: quit",
+            };
             let help = Paragraph::new(vec![
-                Line::from("a: add  d: complete next  x/Delete: delete selected  q: quit"),
+                Line::from(shortcuts),
                 Line::from(format!("Status: {}", state.status)),
             ])
             .block(
@@ -158,6 +340,10 @@ fn run_loop(
                     .title("Help / Status"),
             );
             frame.render_widget(help, chunks[3]);
+
+            if state.mode == Mode::Switching {
+                render_list_switcher(frame, &state.lists, &mut state.lists_state);
+            }
         })?;
 
         if event::poll(Duration::from_millis(200))? {
@@ -169,13 +355,48 @@ fn run_loop(
                 continue;
             }
 
-            match state.mode {
-                Mode::Normal => {
-                    if handle_normal_mode(key.code, &mut state, app)? {
-                        break;
-                    }
+            let result = match state.mode {
+                Mode::Normal
+                    if key.code == KeyCode::Char('r')
+                        && key.modifiers.contains(KeyModifiers::CONTROL) =>
+                {
+                    undo_or_redo(&mut state, app, true).map(|()| false)
+                }
+                Mode::Normal => handle_normal_mode(key.code, &mut state, app),
+                Mode::Adding => handle_add_mode(key.code, &mut state, app).map(|()| false),
+                Mode::Editing(id) => {
+                    handle_edit_mode(key.code, id, &mut state, app).map(|()| false)
+                }
+                Mode::Filtering => {
+                    handle_filter_mode(key.code, &mut state);
+                    Ok(false)
+                }
+                Mode::Searching => {
+                    handle_search_mode(key.code, &mut state, app);
+                    Ok(false)
+                }
+                Mode::Switching => {
+                    handle_switch_mode(key.code, &mut state, app);
+                    Ok(false)
+                }
+            };
+            let result = result.and_then(|quit| {
+                if let Some(id) = state.edit_notes.take() {
+                    edit_notes(terminal, id, &mut state, app)?;
+                }
+                Ok(quit)
+            });
+
+            match result {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(err) => {
+                    // A failed save (store locked, or changed by another process) is taken
+                    // back; resync with what other processes wrote and report instead of exiting.
+                    app.reload()?;
+                    state.mode = Mode::Normal;
+                    state.status = format!("Not saved: {err}");
                 }
-                Mode::Adding => handle_add_mode(key.code, &mut state, app)?,
             }
         }
     }
@@ -184,6 +405,40 @@ fn run_loop(
 }
 
 fn handle_normal_mode(key: KeyCode, state: &mut UiState, app: &mut TodoApp) -> io::Result<bool> {
+    if key == KeyCode::Tab {
+        state.tab = match state.tab {
+            Tab::Pending => Tab::Archive,
+            Tab::Archive => Tab::Search,
+            Tab::Search => Tab::Stats,
+            Tab::Stats => Tab::Pending,
+        };
+        return Ok(false);
+    }
+    if key == KeyCode::Char('u') {
+        undo_or_redo(state, app, false)?;
+        return Ok(false);
+    }
+    if matches!(key, KeyCode::Char('J') | KeyCode::PageDown) {
+        state.detail_scroll = state.detail_scroll.saturating_add(1);
+        return Ok(false);
+    }
+    if matches!(key, KeyCode::Char('K') | KeyCode::PageUp) {
+        state.detail_scroll = state.detail_scroll.saturating_sub(1);
+        return Ok(false);
+    }
+    if key == KeyCode::Char('s') {
+        state.tab = Tab::Search;
+        state.mode = Mode::Searching;
+        state.status = String::from("Searching: results update as you type.");
+        return Ok(false);
+    }
+    match state.tab {
+        Tab::Pending => {}
+        Tab::Archive => return handle_archive_keys(key, state, app),
+        Tab::Search => return Ok(handle_search_keys(key, state, app)),
+        Tab::Stats => return Ok(key == KeyCode::Char('q')),
+    }
+
     match key {
         KeyCode::Char('q') => return Ok(true),
         KeyCode::Char('a') => {
@@ -191,17 +446,89 @@ fn handle_normal_mode(key: KeyCode, state: &mut UiState, app: &mut TodoApp) -> i
             state.input.clear();
             state.status = String::from("Adding mode: type task and press Enter. Esc cancels.");
         }
-        KeyCode::Char('d') => match app.complete_next()? {
+        KeyCode::Char('L') => open_list_switcher(state),
+        KeyCode::Char('/') => {
+            state.mode = Mode::Filtering;
+            state.status = String::from("Filtering: the list updates as you type.");
+        }
+        KeyCode::Char('n') => match selected_task(state, app) {
+            Some(todo) => state.edit_notes = Some(todo.id),
+            None => {
+                state.status = String::from("No selected task to write notes for.");
+            }
+        },
+        KeyCode::Char('t') => match selected_task(state, app).cloned() {
+            Some(todo) if todo.running_since().is_some() => {
+                if let Some(todo) = app.stop_timer()? {
+                    let ran = todo
+                        .time_entries
+                        .last()
+                        .map_or(0, |entry| entry.seconds_between(0, unix_now()));
+                    state.status = format!(
+                        "Stopped timer on task #{} after {}.",
+                        todo.id,
+                        format_duration(ran)
+                    );
+                }
+            }
             Some(todo) => {
-                state.status = format!("Completed task #{}: {}", todo.id, todo.description);
+                let stopped = app.running_timer().map(|running| running.id);
+                app.start_timer(todo.id)?;
+                state.status = match stopped {
+                    Some(stopped) => {
+                        format!("Started timer on task #{} and stopped #{stopped}.", todo.id)
+                    }
+                    None => format!("Started timer on task #{}.", todo.id),
+                };
             }
             None => {
+                state.status = String::from("No selected task to time.");
+            }
+        },
+        KeyCode::Char('e') => match selected_task(state, app) {
+            Some(todo) => {
+                // Pre-fill the input so the description can be tweaked in place.
+                state.input = todo.description.clone();
+                state.mode = Mode::Editing(todo.id);
+                state.status = format!("Editing task #{}. Enter saves, Esc cancels.", todo.id);
+            }
+            None => {
+                state.status = String::from("No selected task to edit.");
+            }
+        },
+        KeyCode::Enter => {
+            let selected = selected_task(state, app).map(|todo| todo.id);
+            match selected {
+                Some(id) => {
+                    // Check first: completing a blocked task is an error that would end the TUI.
+                    let blockers = app.pending_blockers(id);
+                    if !blockers.is_empty() {
+                        state.status =
+                            format!("Task #{id} is blocked by {}.", format_ids(&blockers));
+                    } else if let Some(todo) = app.complete(id)? {
+                        state.status = completed_status(&todo, app);
+                    }
+                }
+                None => {
+                    state.status = String::from("No selected task to complete.");
+                }
+            }
+        }
+        KeyCode::Char('d') => match app.complete_next()? {
+            Some(todo) => {
+                state.status = completed_status(&todo, app);
+            }
+            None if app.is_empty() => {
                 state.status = String::from("No tasks to complete.");
             }
+            None => {
+                state.status = String::from("All pending tasks are blocked by other tasks.");
+            }
         },
         KeyCode::Delete | KeyCode::Char('x') => {
-            // Delete the highlighted item by logical queue index.
-            if let Some(index) = state.list_state.selected() {
+            // The highlight indexes the filtered view, so map it back to a queue index.
+            let index = selected_task(state, app).and_then(|todo| app.position(todo.id));
+            if let Some(index) = index {
                 match app.delete_at(index)? {
                     Some(todo) => {
                         state.status = format!("Deleted task #{}: {}", todo.id, todo.description);
@@ -214,6 +541,10 @@ fn handle_normal_mode(key: KeyCode, state: &mut UiState, app: &mut TodoApp) -> i
                 state.status = String::from("No selected task to delete.");
             }
         }
+        KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('-') => {
+            let raise = key != KeyCode::Char('-');
+            change_selected_priority(state, app, raise)?;
+        }
         KeyCode::Up | KeyCode::Char('k') => {
             let current = state.list_state.selected().unwrap_or(0);
             state.list_state.select(Some(current.saturating_sub(1)));
@@ -228,6 +559,171 @@ fn handle_normal_mode(key: KeyCode, state: &mut UiState, app: &mut TodoApp) -> i
     Ok(false)
 }
 
+fn handle_archive_keys(key: KeyCode, state: &mut UiState, app: &mut TodoApp) -> io::Result<bool> {
+    match key {
+        KeyCode::Char('q') => return Ok(true),
+        KeyCode::Char('r') => {
+            // Archive rows are listed newest first, matching `TodoApp::history`.
+            let selected = state
+                .archive_state
+                .selected()
+                .and_then(|index| app.history().nth(index))
+                .map(|entry| entry.todo.id);
+            let Some(id) = selected else {
+                state.status = String::from("No archived task selected.");
+                return Ok(false);
+            };
+
+            if let Some(todo) = app.restore(id)? {
+                state.status = format!("Restored task #{}: {}", todo.id, todo.description);
+            }
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            let current = state.archive_state.selected().unwrap_or(0);
+            state.archive_state.select(Some(current.saturating_sub(1)));
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            let current = state.archive_state.selected().unwrap_or(0);
+            state.archive_state.select(Some(current.saturating_add(1)));
+        }
+        _ => {}
+    }
+
+    Ok(false)
+}
+
+fn handle_search_keys(key: KeyCode, state: &mut UiState, app: &TodoApp) -> bool {
+    match key {
+        KeyCode::Char('q') => return true,
+        KeyCode::Enter => go_to_search_hit(state, app),
+        KeyCode::Up | KeyCode::Char('k') => {
+            let current = state.search_state.selected().unwrap_or(0);
+            state.search_state.select(Some(current.saturating_sub(1)));
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            let current = state.search_state.selected().unwrap_or(0);
+            state.search_state.select(Some(current.saturating_add(1)));
+        }
+        _ => {}
+    }
+
+    false
+}
+
+/// Switches to the tab holding the selected search result and highlights it there.
+fn go_to_search_hit(state: &mut UiState, app: &TodoApp) {
+    let hits = app.search(&state.search_input);
+    let Some(hit) = state
+        .search_state
+        .selected()
+        .and_then(|index| hits.get(index))
+    else {
+        state.status = String::from("No search result selected.");
+        return;
+    };
+    let id = hit.todo.id;
+
+    if hit.archived.is_some() {
+        state.tab = Tab::Archive;
+        state
+            .archive_state
+            .select(app.history().position(|entry| entry.todo.id == id));
+        state.status = format!("Showing archived task #{id}.");
+        return;
+    }
+
+    state.tab = Tab::Pending;
+    state.status = format!("Showing task #{id}.");
+    let mut index = visible_tasks(state, app)
+        .iter()
+        .position(|todo| todo.id == id);
+    if index.is_none() {
+        // The filter hides the task, so drop it rather than select nothing.
+        state.filter = None;
+        state.filter_input.clear();
+        index = app.position(id);
+        state.status.push_str(" Filter cleared.");
+    }
+    state.list_state.select(index);
+}
+
+/// Opens a task's notes in `$EDITOR`, handing it the terminal until it exits.
+fn edit_notes(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    id: u64,
+    state: &mut UiState,
+    app: &mut TodoApp,
+) -> io::Result<()> {
+    let current = app
+        .get(id)
+        .and_then(|todo| todo.notes.clone())
+        .unwrap_or_default();
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    let edited = editor::edit(&current, &format!("note-{id}"));
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    terminal.clear()?;
+
+    state.status = match edited {
+        Ok(text) if text.trim_end() == current => format!("Notes of task #{id} unchanged."),
+        Ok(text) => match app.set_notes(id, Some(text))? {
+            Some(todo) if todo.notes.is_some() => format!("Saved notes of task #{id}."),
+            Some(_) => format!("Removed notes of task #{id}."),
+            None => format!("Task #{id} is no longer pending."),
+        },
+        Err(err) => format!("Could not edit notes: {err}"),
+    };
+    Ok(())
+}
+
+/// Undoes the last change, or redoes the last undone one, on either tab.
+fn undo_or_redo(state: &mut UiState, app: &mut TodoApp, redo: bool) -> io::Result<()> {
+    state.status = if redo {
+        match app.redo()? {
+            Some(label) => format!("Redid {label}"),
+            None => String::from("Nothing to redo."),
+        }
+    } else {
+        match app.undo()? {
+            Some(label) => format!("Undid {label}. Ctrl-r redoes it."),
+            None => String::from("Nothing to undo."),
+        }
+    };
+    Ok(())
+}
+
+fn change_selected_priority(state: &mut UiState, app: &mut TodoApp, raise: bool) -> io::Result<()> {
+    let selected = selected_task(state, app).map(|todo| (todo.id, todo.priority));
+    let Some((index, current)) =
+        selected.and_then(|(id, priority)| Some((app.position(id)?, priority)))
+    else {
+        state.status = String::from("No selected task to reprioritize.");
+        return Ok(());
+    };
+
+    let priority = if raise {
+        current.raised()
+    } else {
+        current.lowered()
+    };
+    if priority == current {
+        state.status = format!("Task is already {current} priority.");
+        return Ok(());
+    }
+
+    if let Some(todo) = app.set_priority_at(index, priority)? {
+        // Keep the highlight on the task, which moved to its new priority level.
+        let new_index = visible_tasks(state, app)
+            .iter()
+            .position(|task| task.id == todo.id);
+        state.list_state.select(new_index);
+        state.status = format!("Task #{} priority: {current} -> {priority}", todo.id);
+    }
+    Ok(())
+}
+
 fn handle_add_mode(key: KeyCode, state: &mut UiState, app: &mut TodoApp) -> io::Result<()> {
     match key {
         KeyCode::Esc => {
@@ -241,7 +737,18 @@ fn handle_add_mode(key: KeyCode, state: &mut UiState, app: &mut TodoApp) -> io::
                 state.status = String::from("Task description cannot be empty.");
             } else {
                 // Commit typed value into queue + persistence layer.
-                let todo = app.add_task(value.to_string())?;
+                let inline = InlineTags::parse(value);
+                if inline.description.is_empty() {
+                    state.status = String::from("Task description cannot be empty.");
+                    return Ok(());
+                }
+                let options = NewTask {
+                    priority: state.priority,
+                    project: inline.project,
+                    tags: inline.tags,
+                    ..NewTask::default()
+                };
+                let todo = app.add_task_with(inline.description, options)?;
                 state.status = format!("Added task #{}: {}", todo.id, todo.description);
             }
             state.input.clear();
@@ -259,35 +766,758 @@ fn handle_add_mode(key: KeyCode, state: &mut UiState, app: &mut TodoApp) -> io::
     Ok(())
 }
 
-fn clamp_selection(state: &mut UiState, len: usize) {
+fn handle_edit_mode(
+    key: KeyCode,
+    id: u64,
+    state: &mut UiState,
+    app: &mut TodoApp,
+) -> io::Result<()> {
+    match key {
+        KeyCode::Esc => {
+            state.mode = Mode::Normal;
+            state.input.clear();
+            state.status = String::from("Edit cancelled.");
+        }
+        KeyCode::Enter => {
+            let value = state.input.trim();
+            if value.is_empty() {
+                state.status = String::from("Task description cannot be empty.");
+                return Ok(());
+            }
+
+            state.status = match app.edit_description(id, value.to_string())? {
+                Some(todo) => format!("Edited task #{}: {}", todo.id, todo.description),
+                None => format!("Task #{id} is no longer pending."),
+            };
+            state.input.clear();
+            state.mode = Mode::Normal;
+        }
+        KeyCode::Backspace => {
+            state.input.pop();
+        }
+        KeyCode::Char(ch) => {
+            state.input.push(ch);
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn handle_filter_mode(key: KeyCode, state: &mut UiState) {
+    match key {
+        KeyCode::Esc => {
+            state.mode = Mode::Normal;
+            state.filter = None;
+            state.filter_input.clear();
+            state.status = String::from("Filter cleared.");
+            return;
+        }
+        KeyCode::Enter => {
+            state.mode = Mode::Normal;
+            state.status = match &state.filter {
+                Some(_) => format!("Filter: {}", state.filter_input),
+                None => String::from("No filter."),
+            };
+            return;
+        }
+        KeyCode::Backspace => {
+            state.filter_input.pop();
+        }
+        KeyCode::Char(ch) => {
+            state.filter_input.push(ch);
+        }
+        _ => return,
+    }
+
+    // Re-parse on every keystroke; a half-typed expression keeps the last valid filter.
+    if state.filter_input.trim().is_empty() {
+        state.filter = None;
+        state.status = String::from("Filtering: the list updates as you type.");
+        return;
+    }
+    match state.filter_input.parse::<Filter>() {
+        Ok(filter) => {
+            state.filter = Some(filter);
+            state.status = String::from("Filtering: the list updates as you type.");
+        }
+        Err(err) => state.status = format!("Filter incomplete: {err}"),
+    }
+}
+
+fn handle_search_mode(key: KeyCode, state: &mut UiState, app: &TodoApp) {
+    match key {
+        KeyCode::Esc => {
+            state.mode = Mode::Normal;
+            state.search_input.clear();
+            state.status = String::from("Search cleared.");
+            return;
+        }
+        KeyCode::Enter => {
+            state.mode = Mode::Normal;
+            state.status = match app.search(&state.search_input).len() {
+                0 => String::from("No matching tasks."),
+                count => format!("{count} match(es). Enter goes to the selected task."),
+            };
+            return;
+        }
+        KeyCode::Backspace => {
+            state.search_input.pop();
+        }
+        KeyCode::Char(ch) => {
+            state.search_input.push(ch);
+        }
+        _ => return,
+    }
+
+    // A new query starts over at the best match.
+    state.search_state.select(Some(0));
+}
+
+fn open_list_switcher(state: &mut UiState) {
+    let mut lists = match config::list_names(&state.data_dir) {
+        Ok(lists) => lists,
+        Err(err) => {
+            state.status = format!("Could not read lists: {err}");
+            return;
+        }
+    };
+    // A list that has never been saved still shows up while it is open.
+    if let Some(current) = &state.current_list
+        && !lists.contains(current)
+    {
+        lists.push(current.clone());
+        lists.sort();
+    }
+    if lists.is_empty() {
+        state.status = format!("No lists in {}.", state.data_dir.display());
+        return;
+    }
+
+    let current = state
+        .current_list
+        .as_ref()
+        .and_then(|current| lists.iter().position(|name| name == current));
+    state.lists_state.select(Some(current.unwrap_or(0)));
+    state.lists = lists;
+    state.mode = Mode::Switching;
+    state.status = String::from("Pick a list: Enter switches, Esc cancels.");
+}
+
+fn handle_switch_mode(key: KeyCode, state: &mut UiState, app: &mut TodoApp) {
+    match key {
+        KeyCode::Esc => {
+            state.mode = Mode::Normal;
+            state.status = String::from("Stayed on the current list.");
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            let current = state.lists_state.selected().unwrap_or(0);
+            state.lists_state.select(Some(current.saturating_sub(1)));
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            let current = state.lists_state.selected().unwrap_or(0);
+            let last = state.lists.len().saturating_sub(1);
+            state.lists_state.select(Some((current + 1).min(last)));
+        }
+        KeyCode::Enter => {
+            let Some(name) = state
+                .lists_state
+                .selected()
+                .and_then(|index| state.lists.get(index))
+                .cloned()
+            else {
+                return;
+            };
+
+            state.mode = Mode::Normal;
+            match TodoApp::load_or_new(list_path(&state.data_dir, &name)) {
+                Ok(loaded) => {
+                    // The configured undo depth applies to every list.
+                    let undo_depth = app.undo_depth();
+                    *app = loaded;
+                    app.set_undo_depth(undo_depth);
+                    state.filter = None;
+                    state.filter_input.clear();
+                    state.list_state.select(None);
+                    state.archive_state.select(None);
+                    state.search_state.select(None);
+                    state.status = format!("Switched to list '{name}'.");
+                    state.current_list = Some(name);
+                }
+                Err(err) => state.status = format!("Could not open list '{name}': {err}"),
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Status line after completing a task, naming the next instance of a recurring one.
+fn completed_status(todo: &Todo, app: &TodoApp) -> String {
+    let mut status = format!("Completed task #{}: {}", todo.id, todo.description);
+    if let Some(next) = app.next_in_series(todo) {
+        status.push_str(&format!(" (next: #{})", next.id));
+    }
+    status
+}
+
+/// Pending tasks in list order, narrowed by the active filter.
+fn visible_tasks<'a>(state: &UiState, app: &'a TodoApp) -> Vec<&'a Todo> {
+    match &state.filter {
+        Some(filter) => app.filter_tasks(filter, unix_now()).collect(),
+        None => app.list_tasks().collect(),
+    }
+}
+
+/// Pending task under the highlight, if any.
+fn selected_task<'a>(state: &UiState, app: &'a TodoApp) -> Option<&'a Todo> {
+    let index = state.list_state.selected()?;
+    visible_tasks(state, app).get(index).copied()
+}
+
+fn clamp_selection(list_state: &mut ListState, len: usize) {
     if len == 0 {
         // Nothing to highlight when list is empty.
-        state.list_state.select(None);
+        list_state.select(None);
         return;
     }
 
-    let current = state.list_state.selected().unwrap_or(0);
+    let current = list_state.selected().unwrap_or(0);
     let max_index = len.saturating_sub(1);
-    state.list_state.select(Some(current.min(max_index)));
+    list_state.select(Some(current.min(max_index)));
 }
 
-fn selected_detail_text(tasks: &[Todo], state: &UiState) -> String {
+fn selected_detail_text(
+    tasks: &[&Todo],
+    list_state: &ListState,
+    time_format: &TimeFormat,
+    app: &TodoApp,
+) -> String {
     if tasks.is_empty() {
         return String::from("No task selected");
     }
 
     // Derive a safe index from current cursor position.
-    let index = state
-        .list_state
-        .selected()
-        .unwrap_or(0)
-        .min(tasks.len() - 1);
-    let todo = &tasks[index];
-    format!(
-        "Index: {}\nID: {}\nCreated: {}\n\n{}",
+    let index = list_state.selected().unwrap_or(0).min(tasks.len() - 1);
+    let todo = tasks[index];
+    let due = todo.due_at.map_or_else(|| String::from("-"), format_due);
+    let project = todo.project.as_deref().unwrap_or("-");
+    let repeats = todo
+        .recurrence
+        .map_or_else(|| String::from("-"), |recurrence| recurrence.to_string());
+    let tags = if todo.tags.is_empty() {
+        String::from("-")
+    } else {
+        todo.tags.join(", ")
+    };
+    let mut text = format!(
+        "Index: {}\nID: {}\nCreated: {}\nPriority: {}\nDue: {}\nProject: {}\nTags: {}\nRepeats: {}\n\n{}",
         index + 1,
         todo.id,
-        todo.created_at,
+        time_format.with_age(todo.created_at, unix_now()),
+        todo.priority,
+        due,
+        project,
+        tags,
+        repeats,
         todo.description
-    )
+    );
+
+    if !todo.time_entries.is_empty() {
+        let now = unix_now();
+        text.push_str(&format!(
+            "\n\nTracked: {}",
+            format_duration(todo.time_spent(0, now))
+        ));
+        if todo.running_since().is_some() {
+            text.push_str(" (timer running)");
+        }
+    }
+    if !todo.subtasks.is_empty() {
+        text.push_str("\n\nSubtasks:");
+        for subtask in &todo.subtasks {
+            let mark = if subtask.done { "x" } else { " " };
+            text.push_str(&format!("\n[{mark}] {}", subtask.title));
+        }
+    }
+    if !todo.blocked_by.is_empty() {
+        text.push_str("\n\nBlocked by:");
+        for blocker in &todo.blocked_by {
+            // Blockers that were completed or deleted no longer hold the task back.
+            let state = if app.get(*blocker).is_some() {
+                "pending"
+            } else {
+                "cleared"
+            };
+            text.push_str(&format!("\n#{blocker} ({state})"));
+        }
+    }
+    push_attachments_and_notes(&mut text, todo);
+    text
+}
+
+/// Appends a task's numbered attachments and its notes to a detail pane's text.
+fn push_attachments_and_notes(text: &mut String, todo: &Todo) {
+    if !todo.attachments.is_empty() {
+        text.push_str("\n\nAttachments:");
+        for (number, attachment) in todo.attachments.iter().enumerate() {
+            text.push_str(&format!("\n{}. {attachment}", number + 1));
+        }
+    }
+    if let Some(notes) = &todo.notes {
+        text.push_str("\n\nNotes:\n");
+        text.push_str(notes);
+    }
+}
+
+/// Detail pane showing `text` wrapped, scrolled down by `scroll` lines.
+///
+/// The scroll is capped at the last line of text so `K` works right away after
+/// scrolling too far.
+fn detail_pane<'a>(text: impl Into<Text<'a>>, scroll: &mut u16, title: &'a str) -> Paragraph<'a> {
+    let text = text.into();
+    let last_line = u16::try_from(text.lines.len().saturating_sub(1)).unwrap_or(u16::MAX);
+    *scroll = (*scroll).min(last_line);
+    Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .scroll((*scroll, 0))
+        .block(Block::default().borders(Borders::ALL).title(title))
+}
+
+fn render_pending(
+    frame: &mut Frame,
+    body: &[Rect],
+    tasks: &[&Todo],
+    list_state: &mut ListState,
+    scroll: &mut u16,
+    time_format: &TimeFormat,
+    app: &TodoApp,
+) {
+    let now = unix_now();
+    let items = if tasks.is_empty() {
+        vec![ListItem::new("No pending tasks")]
+    } else {
+        tasks
+            .iter()
+            .enumerate()
+            .map(|(idx, todo)| {
+                let labels = todo.labels();
+                let labels = if labels.is_empty() {
+                    labels
+                } else {
+                    format!(" {labels}")
+                };
+                let mut details = todo
+                    .due_at
+                    .map(|due_at| format!(" (due {})", format_due(due_at)))
+                    .unwrap_or_default();
+                if !app.pending_blockers(todo.id).is_empty() {
+                    details.push_str(" (blocked)");
+                }
+                // Overdue rows go red and rows due today yellow.
+                let style = match todo.due_status(now) {
+                    Some(DueStatus::Overdue) => Style::default().fg(Color::Red),
+                    Some(DueStatus::Today) => Style::default().fg(Color::Yellow),
+                    _ => Style::default(),
+                };
+                ListItem::new(format!(
+                    "{:>2}. #{} [{}] {:<6} {}{}{}",
+                    idx + 1,
+                    todo.id,
+                    time_format.with_age(todo.created_at, now),
+                    todo.priority,
+                    todo.description,
+                    labels,
+                    details
+                ))
+                .style(style)
+            })
+            .collect()
+    };
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Pending Tasks (j/k or arrows)"),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, body[0], list_state);
+
+    let detail_text = selected_detail_text(tasks, list_state, time_format, app);
+    let detail = detail_pane(detail_text, scroll, "Selected Task (J/K scroll)");
+    frame.render_widget(detail, body[1]);
+}
+
+fn render_archive(
+    frame: &mut Frame,
+    body: &[Rect],
+    entries: &[&ArchivedTodo],
+    list_state: &mut ListState,
+    scroll: &mut u16,
+    time_format: &TimeFormat,
+) {
+    let items = if entries.is_empty() {
+        vec![ListItem::new("No archived tasks")]
+    } else {
+        entries
+            .iter()
+            .map(|entry| {
+                ListItem::new(format!(
+                    "#{} [{}] {:<7} {}",
+                    entry.todo.id,
+                    time_format.format(entry.completed_at),
+                    entry.outcome,
+                    entry.todo.description
+                ))
+            })
+            .collect()
+    };
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Archive, newest first (j/k or arrows)"),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, body[0], list_state);
+
+    let detail = detail_pane(
+        selected_archive_text(entries, list_state, time_format),
+        scroll,
+        "Archived Task (J/K scroll)",
+    );
+    frame.render_widget(detail, body[1]);
+}
+
+fn render_search(
+    frame: &mut Frame,
+    body: &[Rect],
+    hits: &[SearchHit],
+    query: &str,
+    list_state: &mut ListState,
+    scroll: &mut u16,
+    time_format: &TimeFormat,
+) {
+    let items = if hits.is_empty() {
+        let empty = if query.trim().is_empty() {
+            "Press s and type to search pending and archived tasks"
+        } else {
+            "No matching tasks"
+        };
+        vec![ListItem::new(empty)]
+    } else {
+        hits.iter()
+            .map(|hit| {
+                let prefix = match hit.archived {
+                    None => format!(
+                        "#{} [{}] {:<7} ",
+                        hit.todo.id,
+                        time_format.format(hit.todo.created_at),
+                        hit.todo.priority
+                    ),
+                    Some(entry) => format!(
+                        "#{} [{}] {:<7} ",
+                        entry.todo.id,
+                        time_format.format(entry.completed_at),
+                        entry.outcome
+                    ),
+                };
+                let labels = hit.todo.labels();
+                let text = if labels.is_empty() {
+                    hit.todo.description.clone()
+                } else {
+                    format!("{} {labels}", hit.todo.description)
+                };
+
+                let mut line = highlighted(&text, query);
+                line.spans.insert(0, Span::raw(prefix));
+                // Archived results are dimmed so pending work stands out.
+                let style = if hit.archived.is_some() {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(line).style(style)
+            })
+            .collect()
+    };
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Search Results, best first (j/k or arrows)"),
+        )
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan))
+        .highlight_symbol("> ");
+    frame.render_stateful_widget(list, body[0], list_state);
+
+    let detail = detail_pane(
+        search_detail_lines(hits, query, list_state, time_format),
+        scroll,
+        "Selected Result (J/K scroll)",
+    );
+    frame.render_widget(detail, body[1]);
+}
+
+/// Status and text of the selected search result, with every field the query matched.
+fn search_detail_lines(
+    hits: &[SearchHit],
+    query: &str,
+    list_state: &ListState,
+    time_format: &TimeFormat,
+) -> Vec<Line<'static>> {
+    if hits.is_empty() {
+        return vec![Line::from("No task selected")];
+    }
+
+    let index = list_state.selected().unwrap_or(0).min(hits.len() - 1);
+    let hit = &hits[index];
+    let now = unix_now();
+    let status = match hit.archived {
+        None => String::from("pending"),
+        Some(entry) => format!(
+            "{} {}",
+            entry.outcome,
+            time_format.with_age(entry.completed_at, now)
+        ),
+    };
+    let mut lines = vec![
+        Line::from(format!("ID: {}", hit.todo.id)),
+        Line::from(format!("Status: {status}")),
+        Line::from(format!(
+            "Created: {}",
+            time_format.with_age(hit.todo.created_at, now)
+        )),
+        Line::from(format!("Priority: {}", hit.todo.priority)),
+        Line::from(""),
+    ];
+
+    let mut fields = hit.todo.search_text();
+    if let Some(description) = fields.next() {
+        lines.push(highlighted(description, query));
+    }
+    // Notes span several lines, so only the lines that matched are shown.
+    let matched: Vec<&str> = fields
+        .flat_map(str::lines)
+        .filter(|line| !highlight(line, query).is_empty())
+        .collect();
+    if !matched.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Also matches:"));
+        lines.extend(matched.into_iter().map(|field| highlighted(field, query)));
+    }
+    lines
+}
+
+/// `text` with the parts matched by `query` in bold yellow.
+fn highlighted(text: &str, query: &str) -> Line<'static> {
+    let matched = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    let mut end = 0;
+    for range in highlight(text, query) {
+        spans.push(Span::raw(text[end..range.start].to_string()));
+        spans.push(Span::styled(text[range.clone()].to_string(), matched));
+        end = range.end;
+    }
+    spans.push(Span::raw(text[end..].to_string()));
+    Line::from(spans)
+}
+
+fn render_list_switcher(frame: &mut Frame, lists: &[String], list_state: &mut ListState) {
+    // Centered popup, tall enough for every list plus borders.
+    let area = frame.area();
+    let height = (lists.len() as u16 + 2).min(area.height);
+    let width = 40.min(area.width);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let items: Vec<ListItem> = lists
+        .iter()
+        .map(|name| ListItem::new(name.as_str()))
+        .collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Lists"))
+        .highlight_style(Style::default().fg(Color::Black).bg(Color::Cyan))
+        .highlight_symbol("> ");
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, list_state);
+}
+
+fn selected_archive_text(
+    entries: &[&ArchivedTodo],
+    list_state: &ListState,
+    time_format: &TimeFormat,
+) -> String {
+    if entries.is_empty() {
+        return String::from("No task selected");
+    }
+
+    let index = list_state.selected().unwrap_or(0).min(entries.len() - 1);
+    let entry = entries[index];
+    let now = unix_now();
+    let mut text = format!(
+        "ID: {}\nOutcome: {}\nCreated: {}\nArchived: {}\nPriority: {}\n\n{}",
+        entry.todo.id,
+        entry.outcome,
+        time_format.with_age(entry.todo.created_at, now),
+        time_format.with_age(entry.completed_at, now),
+        entry.todo.priority,
+        entry.todo.description
+    );
+    push_attachments_and_notes(&mut text, &entry.todo);
+    text
+}
+
+/// Statistics tab: added and completed tasks per day as sparklines, the
+/// backlog of recent days as bars, and a summary beside them.
+fn render_stats(
+    frame: &mut Frame,
+    area: Rect,
+    stats: &Stats,
+    app: &TodoApp,
+    time_format: &TimeFormat,
+) {
+    let now = unix_now();
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Min(6),
+        ])
+        .split(area);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(rows[2]);
+
+    let added: u64 = stats.added.iter().sum();
+    let completed: u64 = stats.completed.iter().sum();
+    // Both sparklines share a scale so their heights compare.
+    let max = stats
+        .added
+        .iter()
+        .chain(&stats.completed)
+        .copied()
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let sparkline = |title: String, data: &[u64], color: Color| {
+        Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .data(data.iter().copied())
+            .max(max)
+            .style(Style::default().fg(color))
+    };
+    frame.render_widget(
+        sparkline(
+            format!("Added per day, last {STATS_DAYS} days: {added}"),
+            &stats.added,
+            Color::Cyan,
+        ),
+        rows[0],
+    );
+    frame.render_widget(
+        sparkline(
+            format!("Completed per day, last {STATS_DAYS} days: {completed}"),
+            &stats.completed,
+            Color::Green,
+        ),
+        rows[1],
+    );
+
+    // Bars are labelled with the day of the month.
+    let recent = stats.days.len().saturating_sub(BACKLOG_DAYS);
+    let bars: Vec<Bar> = stats.days[recent..]
+        .iter()
+        .zip(&stats.backlog[recent..])
+        .map(|(day, pending)| {
+            let date = format_date(*day);
+            let label = date.get(8..).unwrap_or(&date).to_string();
+            Bar::with_label(label, *pending)
+        })
+        .collect();
+    let backlog = BarChart::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Pending at end of day, last {BACKLOG_DAYS} days")),
+        )
+        .data(BarGroup::new(bars))
+        .bar_width(3)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(Color::Yellow))
+        .value_style(Style::default().fg(Color::Black).bg(Color::Yellow));
+    frame.render_widget(backlog, bottom[0]);
+
+    let change = stats.backlog_change();
+    let (trend, trend_color) = match change {
+        0 => (String::from("steady"), Color::Reset),
+        1.. => (format!("up {change}"), Color::Red),
+        _ => (format!("down {}", change.unsigned_abs()), Color::Green),
+    };
+    let average = match stats.average_completion {
+        Some(seconds) => format!(
+            "{} over {} task(s)",
+            format_duration(seconds),
+            stats.completed_total
+        ),
+        None => String::from("-"),
+    };
+    let oldest = match stats.oldest_pending.and_then(|id| app.get(id)) {
+        Some(todo) => format!(
+            "#{} {}, created {}",
+            todo.id,
+            todo.description,
+            time_format.with_age(todo.created_at, now)
+        ),
+        None => String::from("-"),
+    };
+    let summary = Paragraph::new(vec![
+        Line::from(format!("Pending now: {}", app.len())),
+        Line::from(vec![
+            Span::raw(format!("Backlog over {STATS_DAYS} days: ")),
+            Span::styled(trend, Style::default().fg(trend_color)),
+        ]),
+        Line::from(format!("Average time to complete: {average}")),
+        Line::from(format!("Oldest pending: {oldest}")),
+    ])
+    .wrap(Wrap { trim: false })
+    .block(Block::default().borders(Borders::ALL).title("Summary"));
+    frame.render_widget(summary, bottom[1]);
+}
+
+fn tab_line(active: Tab) -> Line<'static> {
+    let tab = |label: &'static str, tab: Tab| {
+        if tab == active {
+            Span::styled(
+                format!(" {label} "),
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::raw(format!(" {label} "))
+        }
+    };
+    Line::from(vec![
+        tab("Pending", Tab::Pending),
+        Span::raw("|"),
+        tab("Archive", Tab::Archive),
+        Span::raw("|"),
+        tab("Search", Tab::Search),
+        Span::raw("|"),
+        tab("Stats", Tab::Stats),
+    ])
 }
@@ -0,0 +1,171 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::journal::JournalOp;
+use crate::store::{self, FORMAT_VERSION};
+
+/// First bytes of an undo history file; it shares `store::FORMAT_VERSION`.
+pub const UNDO_MAGIC: [u8; 4] = *b"TUND";
+
+/// One step of the undo or redo history: the operations that take it back.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct UndoEntry {
+    /// What the step did, such as `delete #3: Buy milk`.
+    pub label: String,
+    /// Operations that revert the step, in the order they are applied.
+    pub ops: Vec<JournalOp>,
+}
+
+/// Undo and redo stacks, oldest step first, kept next to the queue file.
+#[derive(Debug, Clone, Default, BorshSerialize, BorshDeserialize)]
+pub struct UndoLog {
+    pub undo: Vec<UndoEntry>,
+    pub redo: Vec<UndoEntry>,
+}
+
+/// Which stack a recorded step goes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum Step {
+    /// A new change: undoable, and anything undone before can no longer be redone.
+    Do,
+    /// The newest undo step was taken back; the entry makes it again.
+    Undo,
+    /// The newest undone step was made again; the entry takes it back.
+    Redo,
+}
+
+/// One step appended to the history file since it was last written whole.
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Record {
+    step: Step,
+    entry: UndoEntry,
+    depth: u64,
+}
+
+impl UndoLog {
+    /// Drops the oldest steps of both stacks beyond `depth`.
+    pub fn truncate(&mut self, depth: usize) {
+        for steps in [&mut self.undo, &mut self.redo] {
+            let excess = steps.len().saturating_sub(depth);
+            steps.drain(..excess);
+        }
+    }
+
+    /// Puts `entry` on the stack `step` calls for, then keeps at most `depth` steps.
+    pub fn record(&mut self, step: Step, entry: UndoEntry, depth: usize) {
+        match step {
+            Step::Do => {
+                self.undo.push(entry);
+                self.redo.clear();
+            }
+            Step::Undo => {
+                self.undo.pop();
+                self.redo.push(entry);
+            }
+            Step::Redo => {
+                self.redo.pop();
+                self.undo.push(entry);
+            }
+        }
+        self.truncate(depth);
+    }
+}
+
+/// History read back from its file.
+#[derive(Debug, Default)]
+pub struct Loaded {
+    pub log: UndoLog,
+    /// Steps appended since the file was last written whole.
+    pub appended: usize,
+    /// The last step was cut short, e.g. by a crash mid-append. Everything
+    /// before it is intact; the file must be rewritten before appending again.
+    pub torn: bool,
+}
+
+/// Undo history that sits next to the queue file (`todos.bin` -> `todos.bin.undo`).
+pub fn undo_path(queue_path: &Path) -> PathBuf {
+    store::with_suffix(queue_path, ".undo")
+}
+
+/// Reads the undo history; a missing file has none.
+///
+/// History written with another format version is dropped rather than
+/// upgraded: it only replays against the tasks it was recorded with.
+pub fn load(path: &Path) -> io::Result<Loaded> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Loaded::default()),
+        Err(err) => return Err(err),
+    };
+
+    let Some(body) = bytes.strip_prefix(&UNDO_MAGIC) else {
+        return Err(invalid(format!(
+            "{} is not a todo undo history",
+            path.display()
+        )));
+    };
+    let mut rest = match body.split_first_chunk::<2>() {
+        Some((version, body)) if u16::from_le_bytes(*version) == FORMAT_VERSION => body,
+        _ => return Ok(Loaded::default()),
+    };
+    let mut loaded = Loaded {
+        log: UndoLog::deserialize(&mut rest)
+            .map_err(|err| invalid(format!("corrupt undo history: {err}")))?,
+        ..Loaded::default()
+    };
+
+    while !rest.is_empty() {
+        let Some((len, body)) = rest.split_first_chunk::<4>() else {
+            loaded.torn = true;
+            break;
+        };
+        let len = u32::from_le_bytes(*len) as usize;
+        if body.len() < len {
+            loaded.torn = true;
+            break;
+        }
+
+        let record = Record::try_from_slice(&body[..len])
+            .map_err(|err| invalid(format!("corrupt undo step: {err}")))?;
+        let depth = usize::try_from(record.depth).unwrap_or(usize::MAX);
+        loaded.log.record(record.step, record.entry, depth);
+        loaded.appended += 1;
+        rest = &body[len..];
+    }
+    Ok(loaded)
+}
+
+/// Writes the history as `UNDO_MAGIC`, a little-endian `u16` version and a Borsh
+/// `UndoLog`, replacing the file atomically.
+pub fn save(path: &Path, log: &UndoLog) -> io::Result<()> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&UNDO_MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    borsh::to_writer(&mut bytes, log)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+    store::write_atomic(path, &bytes)
+}
+
+/// Appends one step as a `u32` length-prefixed Borsh record, then syncs.
+///
+/// Loading replays it with `UndoLog::record`, so the cost of a step does not
+/// grow with the history before it. The file must already exist.
+pub fn append(path: &Path, step: Step, entry: &UndoEntry, depth: usize) -> io::Result<()> {
+    // Laid out like `Record`, without cloning the entry.
+    let depth = u64::try_from(depth).unwrap_or(u64::MAX);
+    let body = borsh::to_vec(&(step, entry, depth))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let len = u32::try_from(body.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "undo step too large"))?;
+    let mut bytes = Vec::with_capacity(4 + body.len());
+    bytes.extend_from_slice(&len.to_le_bytes());
+    bytes.extend_from_slice(&body);
+
+    let mut file = OpenOptions::new().append(true).open(path)?;
+    file.write_all(&bytes)?;
+    file.sync_data()
+}
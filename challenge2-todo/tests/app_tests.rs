@@ -1,6 +1,10 @@
-use challenge2_todo::TodoApp;
+mod common;
+
+use challenge2_todo::dates::{parse_due, start_of_local_day};
+use challenge2_todo::{DueStatus, Filter, NewTask, Outcome, Priority, Recurrence, TodoApp};
+use common::{remove_store, temp_store};
 use std::{
-    env, fs,
+    io,
     time::{SystemTime, UNIX_EPOCH},
 };
 
@@ -12,7 +16,7 @@ fn unique_timestamp() -> u64 {
 
 #[test]
 fn todo_persists_between_restarts() {
-    let file_path = env::temp_dir().join(format!("challenge2_todos_{}.bin", unique_timestamp()));
+    let file_path = temp_store("app");
 
     let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
     app.add_task("Buy groceries".to_string())
@@ -28,15 +32,12 @@ fn todo_persists_between_restarts() {
 
     assert_eq!(descriptions, vec!["Buy groceries", "Pay bills"]);
 
-    let _ = fs::remove_file(file_path);
+    remove_store(&file_path);
 }
 
 #[test]
 fn delete_at_removes_selected_and_persists() {
-    let file_path = env::temp_dir().join(format!(
-        "challenge2_todos_delete_{}.bin",
-        unique_timestamp()
-    ));
+    let file_path = temp_store("app_delete");
 
     let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
     app.add_task("First".to_string())
@@ -60,5 +61,424 @@ fn delete_at_removes_selected_and_persists() {
 
     assert_eq!(descriptions, vec!["First", "Third"]);
 
-    let _ = fs::remove_file(file_path);
+    remove_store(&file_path);
+}
+
+#[test]
+fn complete_next_takes_highest_priority_and_priority_persists() {
+    let file_path = temp_store("app_priority");
+
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    app.add_task("Routine".to_string())
+        .expect("normal add should succeed");
+    app.add_task_with_priority("Outage".to_string(), Priority::Urgent)
+        .expect("urgent add should succeed");
+    app.add_task_with_priority("Someday".to_string(), Priority::Low)
+        .expect("low add should succeed");
+
+    let raised = app
+        .set_priority_at(2, Priority::High)
+        .expect("reprioritize should succeed")
+        .expect("task should exist");
+    assert_eq!(raised.description, "Someday");
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    let order: Vec<(&str, Priority)> = reloaded
+        .list_tasks()
+        .map(|todo| (todo.description.as_str(), todo.priority))
+        .collect();
+    assert_eq!(
+        order,
+        vec![
+            ("Outage", Priority::Urgent),
+            ("Someday", Priority::High),
+            ("Routine", Priority::Normal),
+        ]
+    );
+
+    let completed = app
+        .complete_next()
+        .expect("complete should succeed")
+        .expect("task should exist");
+    assert_eq!(completed.description, "Outage");
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn completed_and_deleted_tasks_are_archived_and_restorable() {
+    let file_path = temp_store("app_archive");
+
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    app.add_task("Ship release".to_string())
+        .expect("first add should succeed");
+    app.add_task("Old idea".to_string())
+        .expect("second add should succeed");
+    app.complete_next().expect("complete should succeed");
+    app.delete_at(0).expect("delete should succeed");
+    assert!(app.is_empty());
+
+    let mut reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    let history: Vec<(&str, Outcome)> = reloaded
+        .history()
+        .map(|entry| (entry.todo.description.as_str(), entry.outcome))
+        .collect();
+    assert_eq!(
+        history,
+        vec![
+            ("Old idea", Outcome::Deleted),
+            ("Ship release", Outcome::Done)
+        ]
+    );
+
+    let restored = reloaded
+        .restore(1)
+        .expect("restore should succeed")
+        .expect("archived task should exist");
+    assert_eq!(restored.description, "Ship release");
+    assert_eq!(reloaded.history().count(), 1);
+
+    // Archived ids stay reserved, so new tasks never reuse them.
+    let added = reloaded
+        .add_task("Next".to_string())
+        .expect("add after restore should succeed");
+    assert_eq!(added.id, 3);
+
+    let ids: Vec<u64> = reloaded.list_tasks().map(|todo| todo.id).collect();
+    assert_eq!(ids, vec![1, 3]);
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn id_based_operations_target_any_pending_task() {
+    let file_path = temp_store("app_by_id");
+
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    for description in ["First", "Second", "Third", "Fourth"] {
+        app.add_task(description.to_string())
+            .expect("add should succeed");
+    }
+    app.add_task_with_priority("Hot".to_string(), Priority::High)
+        .expect("high add should succeed");
+
+    let completed = app
+        .complete(3)
+        .expect("complete should succeed")
+        .expect("task #3 should exist");
+    assert_eq!(completed.description, "Third");
+    assert!(app.get(3).is_none());
+    assert!(app.complete(3).expect("second complete").is_none());
+
+    let edited = app
+        .edit_description(2, "Second (edited)".to_string())
+        .expect("edit should succeed")
+        .expect("task #2 should exist");
+    assert_eq!(edited.description, "Second (edited)");
+
+    // Position 0 is the High task's slot, so #4 is clamped to the top of Normal.
+    let moved = app.move_to(4, 0).expect("move should succeed");
+    assert_eq!(moved, Some(1));
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    let descriptions: Vec<&str> = reloaded
+        .list_tasks()
+        .map(|todo| todo.description.as_str())
+        .collect();
+    assert_eq!(
+        descriptions,
+        vec!["Hot", "Fourth", "First", "Second (edited)"]
+    );
+    assert_eq!(reloaded.position(2), Some(3));
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn due_dates_drive_overdue_and_sorting() {
+    let file_path = temp_store("app_due");
+    let now = unique_timestamp();
+
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    app.add_task("No deadline".to_string())
+        .expect("add should succeed");
+    let later = app
+        .add_task_with(
+            "Later".to_string(),
+            NewTask {
+                due_at: Some(now + 7 * 86_400),
+                ..NewTask::default()
+            },
+        )
+        .expect("add should succeed");
+    let late = app
+        .add_task_with(
+            "Late".to_string(),
+            NewTask {
+                due_at: Some(now - 60),
+                ..NewTask::default()
+            },
+        )
+        .expect("add should succeed");
+
+    assert_eq!(late.due_status(now), Some(DueStatus::Overdue));
+    assert_eq!(later.due_status(now), Some(DueStatus::Upcoming));
+
+    let overdue: Vec<u64> = app.overdue_tasks(now).map(|todo| todo.id).collect();
+    assert_eq!(overdue, vec![late.id]);
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    let by_due: Vec<&str> = reloaded
+        .tasks_by_due()
+        .into_iter()
+        .map(|todo| todo.description.as_str())
+        .collect();
+    assert_eq!(by_due, vec!["Late", "Later", "No deadline"]);
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn parse_due_accepts_relative_inputs() {
+    let now = unique_timestamp();
+    let tomorrow = start_of_local_day(now, 1).expect("date should be in range");
+
+    assert_eq!(parse_due("today", now), Ok(tomorrow - 1));
+    assert_eq!(parse_due("+1d", now), parse_due("tomorrow", now));
+    assert_eq!(parse_due("+1w", now), parse_due("+7d", now));
+    assert_eq!(parse_due("+4h", now), Ok(now + 4 * 3600));
+    assert!(parse_due("+3x", now).is_err());
+    assert!(parse_due("someday", now).is_err());
+
+    // Offsets too large to represent are rejected instead of overflowing.
+    let out_of_range = Err(String::from("date is out of range"));
+    for input in [
+        "+9999999999999999h",
+        "+18446744073709551615d",
+        "+9999999999999999999w",
+    ] {
+        assert_eq!(parse_due(input, now), out_of_range, "{input}");
+    }
+}
+
+#[test]
+fn project_and_tags_persist_and_filter() {
+    let file_path = temp_store("app_tags");
+
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    app.add_task_with(
+        "Fix bug".to_string(),
+        NewTask {
+            project: Some("backend".to_string()),
+            tags: vec!["urgent".to_string()],
+            ..NewTask::default()
+        },
+    )
+    .expect("add should succeed");
+    app.add_task("Water plants".to_string())
+        .expect("add should succeed");
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    let filter: Filter = "project:backend and tag:urgent"
+        .parse()
+        .expect("filter should parse");
+    let matched: Vec<String> = reloaded
+        .filter_tasks(&filter, unique_timestamp())
+        .map(|todo| format!("{} {}", todo.description, todo.labels()))
+        .collect();
+    assert_eq!(matched, vec!["Fix bug +backend @urgent"]);
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn completing_recurring_task_enqueues_next_instance() {
+    let file_path = temp_store("app_recurring");
+    let now = unique_timestamp();
+
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let first = app
+        .add_task_with(
+            "Standup prep".to_string(),
+            NewTask {
+                due_at: Some(now - 3600),
+                recurrence: Some(Recurrence::Daily),
+                ..NewTask::default()
+            },
+        )
+        .expect("add should succeed");
+
+    let completed = app
+        .complete(first.id)
+        .expect("complete should succeed")
+        .expect("task should exist");
+    let next = app
+        .next_in_series(&completed)
+        .expect("next instance should be enqueued")
+        .clone();
+
+    assert_ne!(next.id, first.id);
+    assert_eq!(next.description, "Standup prep");
+    assert_eq!(next.recurrence, Some(Recurrence::Daily));
+    assert!(next.due_at.expect("next instance should be due") > now);
+    assert_eq!(app.len(), 1);
+
+    // A plain task completes without leaving anything behind.
+    let one_off = app
+        .add_task("One-off".to_string())
+        .expect("add should succeed");
+    let one_off = app
+        .complete(one_off.id)
+        .expect("complete should succeed")
+        .expect("task should exist");
+    assert_eq!(one_off.description, "One-off");
+    assert!(app.next_in_series(&one_off).is_none());
+    assert_eq!(app.len(), 1);
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn blocked_tasks_are_skipped_and_refused() {
+    let file_path = temp_store("app_blocked");
+
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let deploy = app
+        .add_task("Deploy".to_string())
+        .expect("add should succeed");
+    let review = app
+        .add_task("Review".to_string())
+        .expect("add should succeed");
+    app.add_blocker(deploy.id, review.id)
+        .expect("link should succeed")
+        .expect("task should exist");
+
+    let error = app
+        .complete(deploy.id)
+        .expect_err("blocked task should be refused");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(app.pending_blockers(deploy.id), vec![review.id]);
+
+    // `Deploy` heads the queue but is blocked, so `Review` is completed first.
+    let completed = app
+        .complete_next()
+        .expect("complete should succeed")
+        .expect("unblocked task should exist");
+    assert_eq!(completed.id, review.id);
+    assert!(app.pending_blockers(deploy.id).is_empty());
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    assert_eq!(
+        reloaded.get(deploy.id).map(|todo| todo.blocked_by.clone()),
+        Some(vec![review.id])
+    );
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn blocker_cycles_are_rejected() {
+    let file_path = temp_store("app_cycle");
+
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let ids: Vec<u64> = ["A", "B", "C"]
+        .iter()
+        .map(|name| {
+            app.add_task(name.to_string())
+                .expect("add should succeed")
+                .id
+        })
+        .collect();
+
+    app.add_blocker(ids[0], ids[1]).expect("A waits on B");
+    app.add_blocker(ids[1], ids[2]).expect("B waits on C");
+    assert!(app.add_blocker(ids[2], ids[0]).is_err());
+    assert!(app.add_blocker(ids[0], ids[0]).is_err());
+    assert!(app.get(ids[2]).expect("C is pending").blocked_by.is_empty());
+
+    // All tasks blocked except C, which is the only one `complete_next` may take.
+    let completed = app
+        .complete_next()
+        .expect("complete should succeed")
+        .expect("C should be completable");
+    assert_eq!(completed.id, ids[2]);
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn subtasks_toggle_and_persist() {
+    let file_path = temp_store("app_subtasks");
+
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let todo = app
+        .add_task("Release".to_string())
+        .expect("add should succeed");
+    app.add_subtask(todo.id, "Tag".to_string())
+        .expect("add subtask should succeed");
+    app.add_subtask(todo.id, "Publish".to_string())
+        .expect("add subtask should succeed");
+    app.toggle_subtask(todo.id, 1)
+        .expect("toggle should succeed")
+        .expect("task should exist");
+    assert!(app.toggle_subtask(todo.id, 2).is_err());
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    let subtasks: Vec<(String, bool)> = reloaded
+        .get(todo.id)
+        .expect("task should persist")
+        .subtasks
+        .iter()
+        .map(|subtask| (subtask.title.clone(), subtask.done))
+        .collect();
+    assert_eq!(
+        subtasks,
+        vec![("Tag".to_string(), false), ("Publish".to_string(), true)]
+    );
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn notes_and_attachments_persist_and_undo() {
+    let file_path = temp_store("app_notes");
+
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let todo = app
+        .add_task("Write spec".to_string())
+        .expect("add should succeed");
+    app.set_notes(todo.id, Some("Scope:\n- login\n- logout\n\n".to_string()))
+        .expect("notes should save");
+    app.add_attachment(todo.id, "/home/me/spec.pdf".to_string())
+        .expect("attach should succeed");
+    app.add_attachment(todo.id, "https://example.com/issue/7".to_string())
+        .expect("attach should succeed");
+    app.add_attachment(todo.id, "/home/me/spec.pdf".to_string())
+        .expect("attaching twice changes nothing");
+    let error = app
+        .remove_attachment(todo.id, 2)
+        .expect_err("there are two attachments");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    app.remove_attachment(todo.id, 0)
+        .expect("detach should succeed");
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    let saved = reloaded.get(todo.id).expect("task should persist");
+    assert_eq!(saved.notes.as_deref(), Some("Scope:\n- login\n- logout"));
+    assert_eq!(saved.attachments, vec!["https://example.com/issue/7"]);
+    assert_eq!(
+        reloaded.search("logout").first().map(|hit| hit.todo.id),
+        Some(todo.id)
+    );
+
+    app.set_notes(todo.id, Some(" \n".to_string()))
+        .expect("blank notes clear them");
+    assert!(app.get(todo.id).is_some_and(|todo| todo.notes.is_none()));
+    app.undo().expect("undo should succeed");
+    app.undo().expect("undo should succeed");
+    let restored = app.get(todo.id).expect("task should exist");
+    assert!(restored.notes.is_some());
+    assert_eq!(restored.attachments.len(), 2);
+
+    remove_store(&file_path);
 }
@@ -0,0 +1,431 @@
+use challenge2_todo::dates::{format_age, unix_now};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn temp_home(name: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos());
+    let home = env::temp_dir().join(format!("challenge2_cli_{name}_{nanos}"));
+    fs::create_dir_all(&home).expect("temp home should be creatable");
+    home
+}
+
+/// Runs the `todo` binary with `home` as its only source of XDG locations.
+///
+/// Local time is India's, which is off UTC by a half hour, so `--utc` shows.
+fn todo(home: &Path, args: &[&str]) -> Output {
+    command(home)
+        .args(args)
+        .output()
+        .expect("todo binary should run")
+}
+
+fn command(home: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_todo"));
+    command
+        .env("HOME", home)
+        .env("TZ", "Asia/Kolkata")
+        .env_remove("XDG_DATA_HOME")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("TODO_FILE");
+    command
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn flags_may_follow_the_description_and_global_options_go_anywhere() {
+    let home = temp_home("flags");
+
+    let added = todo(
+        &home,
+        &[
+            "add",
+            "Fix bug",
+            "+backend",
+            "--priority",
+            "high",
+            "--list",
+            "work",
+        ],
+    );
+    assert!(added.status.success(), "{}", stderr(&added));
+    assert_eq!(stdout(&added), "Added task #1 (high): Fix bug\n");
+
+    let listed = todo(&home, &["--list", "work", "list", "project:backend"]);
+    assert!(stdout(&listed).contains("high   Fix bug +backend"));
+    assert!(home.join(".local/share/todo/work.bin").exists());
+
+    // `--` lets a description start with a dash.
+    let dashed = todo(&home, &["add", "--", "-v flag is broken"]);
+    assert_eq!(
+        stdout(&dashed),
+        "Added task #1 (normal): -v flag is broken\n"
+    );
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn usage_errors_go_to_stderr() {
+    let home = temp_home("errors");
+
+    let unknown = todo(&home, &["frobnicate"]);
+    assert_eq!(unknown.status.code(), Some(2));
+    assert!(stdout(&unknown).is_empty());
+    assert!(stderr(&unknown).contains("unrecognized subcommand 'frobnicate'"));
+    assert!(stderr(&unknown).contains("Usage: todo"));
+
+    let bad_priority = todo(&home, &["add", "-p", "asap", "Task"]);
+    assert_eq!(bad_priority.status.code(), Some(2));
+    assert!(stderr(&bad_priority).contains("unknown priority 'asap'"));
+
+    let both = todo(&home, &["--file", "a.bin", "--list", "work", "list"]);
+    assert_eq!(both.status.code(), Some(2));
+
+    // Nothing was stored by the failed commands.
+    assert!(!home.join(".local/share/todo/default.bin").exists());
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn help_version_completions_and_man_page_print_to_stdout() {
+    let home = temp_home("generated");
+
+    let help = todo(&home, &["--help"]);
+    assert!(help.status.success());
+    assert!(stdout(&help).contains("Commands:"));
+    assert!(stdout(&help).contains("completions"));
+
+    let version = todo(&home, &["--version"]);
+    assert_eq!(
+        stdout(&version),
+        format!("todo {}\n", env!("CARGO_PKG_VERSION"))
+    );
+
+    for shell in ["bash", "zsh", "fish"] {
+        let script = todo(&home, &["completions", shell]);
+        assert!(script.status.success());
+        assert!(stdout(&script).contains("todo"), "{shell} script is empty");
+    }
+
+    let man = todo(&home, &["man"]);
+    assert!(stdout(&man).contains(".TH todo 1"));
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn format_flags_and_export_import_round_trip() {
+    let home = temp_home("export");
+
+    let added = todo(&home, &["add", "Fix bug", "-p", "high", "--format", "json"]);
+    let added = stdout(&added);
+    assert!(added.starts_with('{'), "{added}");
+    assert!(added.contains("\"description\": \"Fix bug\""));
+
+    let csv = todo(&home, &["list", "--format", "csv"]);
+    let csv = stdout(&csv);
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 2);
+    assert!(rows[0].starts_with("id,priority,description,"));
+    assert!(rows[1].starts_with("1,high,Fix bug,"));
+
+    let exported = todo(&home, &["export"]);
+    let backup = home.join("backup.json");
+    fs::write(&backup, &exported.stdout).expect("export should be writable");
+    let backup = backup.to_str().expect("temp path is utf-8");
+
+    let imported = todo(&home, &["--list", "copy", "import", backup]);
+    assert!(imported.status.success(), "{}", stderr(&imported));
+    assert_eq!(
+        stdout(&imported),
+        "Imported tasks: 1 added, 0 updated, 0 restored, 0 archived, 0 removed.\n"
+    );
+    assert_eq!(
+        stdout(&todo(&home, &["--list", "copy", "export"])),
+        stdout(&exported)
+    );
+
+    let done = todo(&home, &["done", "--format", "json"]);
+    assert!(stdout(&done).contains("\"id\": 1"));
+    let none_left = todo(&home, &["done", "--format", "json"]);
+    assert_eq!(stdout(&none_left), "null\n");
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn times_follow_the_time_zone_utc_flag_and_configured_format() {
+    let home = temp_home("times");
+    let tasks = home.join("tasks.json");
+    fs::write(
+        &tasks,
+        r#"[
+            {"description": "Old task", "created_at": 1760000000},
+            {"description": "Older task", "created_at": 1750000000}
+        ]"#,
+    )
+    .expect("tasks should be writable");
+    let imported = todo(
+        &home,
+        &["import", tasks.to_str().expect("temp path is utf-8")],
+    );
+    assert!(imported.status.success(), "{}", stderr(&imported));
+
+    let now = unix_now();
+    let (old, older) = (format_age(1760000000, now), format_age(1750000000, now));
+    let local = todo(&home, &["list", "--sort", "created"]);
+    assert_eq!(
+        stdout(&local),
+        format!(
+            "#1 [2025-10-09 14:23 ({old})] normal Old task\n#2 [2025-06-15 20:36 ({older})] normal Older task\n"
+        )
+    );
+    let utc = todo(&home, &["list", "--utc", "created<2025-10-01 12:00"]);
+    assert_eq!(
+        stdout(&utc),
+        format!("#2 [2025-06-15 15:06 ({older})] normal Older task\n")
+    );
+
+    let config = home.join(".config/todo/config.toml");
+    fs::create_dir_all(config.parent().expect("config has a parent"))
+        .expect("config dir should be creatable");
+    fs::write(&config, "time-format = \"%d %b %Y\"\n").expect("config should be writable");
+    let shown = todo(&home, &["show", "1"]);
+    assert!(
+        stdout(&shown).starts_with(&format!(
+            "#1 [09 Oct 2025 ({old})] normal Old task\n  created: 09 Oct 2025 ("
+        )),
+        "{}",
+        stdout(&shown)
+    );
+
+    let bad = todo(&home, &["list", "created>soon"]);
+    assert!(!bad.status.success());
+    assert!(
+        stderr(&bad).contains("invalid time 'soon'"),
+        "{}",
+        stderr(&bad)
+    );
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn list_rows_show_how_long_ago_tasks_were_created() {
+    let home = temp_home("age");
+    todo(&home, &["add", "Fresh task"]);
+
+    let listed = stdout(&todo(&home, &["list"]));
+    assert!(
+        listed.starts_with("#1 [") && listed.contains(" (just now)] normal Fresh task"),
+        "{listed}"
+    );
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn undo_and_redo_carry_over_between_runs() {
+    let home = temp_home("undo");
+    todo(&home, &["add", "Fix bug"]);
+    todo(&home, &["done"]);
+
+    assert_eq!(
+        stdout(&todo(&home, &["undo", "--show"])),
+        "1. complete #1: Fix bug\n2. add #1: Fix bug\n"
+    );
+    assert_eq!(
+        stdout(&todo(&home, &["undo"])),
+        "Undid complete #1: Fix bug\n"
+    );
+    assert!(stdout(&todo(&home, &["list"])).contains("normal Fix bug"));
+    assert_eq!(
+        stdout(&todo(&home, &["redo"])),
+        "Redid complete #1: Fix bug\n"
+    );
+    assert_eq!(stdout(&todo(&home, &["redo"])), "Nothing to redo.\n");
+
+    let config = home.join(".config/todo/config.toml");
+    fs::create_dir_all(config.parent().expect("config has a parent"))
+        .expect("config dir should be creatable");
+    fs::write(&config, "undo-depth = 0\n").expect("config should be writable");
+    todo(&home, &["add", "Untracked"]);
+    assert_eq!(stdout(&todo(&home, &["undo"])), "Nothing to undo.\n");
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn search_ranks_pending_and_archived_tasks() {
+    let home = temp_home("search");
+    todo(&home, &["add", "Pay invoices"]);
+    todo(&home, &["add", "Send invoice +billing"]);
+    todo(&home, &["done", "2"]);
+
+    let found = stdout(&todo(&home, &["search", "Invoice"]));
+    let lines: Vec<&str> = found.lines().collect();
+    assert_eq!(lines.len(), 2, "{found}");
+    assert!(lines[0].starts_with("#2 [") && lines[0].ends_with("done    Send invoice"));
+    assert!(lines[1].starts_with("#1 [") && lines[1].ends_with("normal Pay invoices"));
+    assert!(stdout(&todo(&home, &["search", "billng"])).starts_with("#2 "));
+    assert_eq!(
+        stdout(&todo(&home, &["search", "rent"])),
+        "No matching tasks.\n"
+    );
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn notes_open_in_the_editor_and_attachments_show() {
+    let home = temp_home("notes");
+    todo(&home, &["add", "Write spec"]);
+
+    // The "editor" appends a line to whatever file it is given.
+    let editor = home.join("editor.sh");
+    fs::write(&editor, "printf 'Ask design\\n' >> \"$1\"\n").expect("editor should be writable");
+    let edit = || {
+        Command::new(env!("CARGO_BIN_EXE_todo"))
+            .args(["note", "1"])
+            .env("HOME", &home)
+            .env("VISUAL", format!("sh {}", editor.display()))
+            .env_remove("XDG_DATA_HOME")
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("TODO_FILE")
+            .output()
+            .expect("todo binary should run")
+    };
+    let noted = edit();
+    assert!(noted.status.success(), "{}", stderr(&noted));
+    assert_eq!(stdout(&noted), "Saved notes of task #1.\n");
+    edit();
+
+    let spec = home.join("spec.txt");
+    fs::write(&spec, "").expect("spec should be writable");
+    let spec = spec.canonicalize().expect("spec should exist");
+    let attached = todo(
+        &home,
+        &["attach", "1", &home.join("spec.txt").to_string_lossy()],
+    );
+    assert_eq!(
+        stdout(&attached),
+        format!("Attached {} to task #1 (1 attachment(s))\n", spec.display())
+    );
+    todo(&home, &["attach", "1", "https://example.com/7"]);
+    let missing = todo(&home, &["attach", "1", "no-such-file"]);
+    assert!(!missing.status.success());
+
+    let shown = stdout(&todo(&home, &["show", "1"]));
+    assert!(shown.contains(&format!(
+        "  attachments:\n    1. {}\n    2. https://example.com/7\n",
+        spec.display()
+    )));
+    assert!(shown.ends_with("  notes:\n    Ask design\n    Ask design\n"));
+
+    let detached = todo(&home, &["detach", "1", "1"]);
+    assert_eq!(
+        stdout(&detached),
+        format!("Removed {} from task #1\n", spec.display())
+    );
+    let cleared = todo(&home, &["note", "1", "--clear"]);
+    assert_eq!(stdout(&cleared), "Removed notes of task #1.\n");
+    let shown = stdout(&todo(&home, &["show", "1"]));
+    assert!(shown.ends_with("  attachments:\n    1. https://example.com/7\n"));
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn timers_start_stop_and_show_up_in_reports() {
+    let home = temp_home("timers");
+    todo(&home, &["add", "Write spec +docs"]);
+    todo(&home, &["add", "Fix bug @urgent"]);
+
+    let started = todo(&home, &["start", "1"]);
+    assert_eq!(stdout(&started), "Started timer on task #1: Write spec\n");
+    let again = todo(&home, &["start", "#1"]);
+    assert!(stdout(&again).starts_with("Timer on task #1 is already running ("));
+    let switched = stdout(&todo(&home, &["start", "2"]));
+    assert!(switched.starts_with("Stopped timer on task #1 after "));
+    assert!(switched.ends_with("s: Write spec\nStarted timer on task #2: Fix bug\n"));
+    assert!(stdout(&todo(&home, &["list"])).contains("Fix bug @urgent (timer running "));
+
+    let stopped = stdout(&todo(&home, &["stop"]));
+    assert!(stopped.starts_with("Stopped timer on task #2 after "));
+    assert_eq!(stdout(&todo(&home, &["stop"])), "No timer is running.\n");
+
+    // Timers above ran for under a second, which reports leave out; imported time counts.
+    let tracked = home.join("tracked.json");
+    fs::write(
+        &tracked,
+        r#"[{"description": "Old work", "tags": ["urgent"], "time_entries": [
+            {"started_at": 1700000000, "stopped_at": 1700005400}]}]"#,
+    )
+    .expect("import file should be writable");
+    todo(&home, &["import", &tracked.to_string_lossy()]);
+    assert_eq!(
+        stdout(&todo(&home, &["report", "--by", "tag"])),
+        "  1h 30m  @urgent\n  1h 30m  total\n"
+    );
+    assert_eq!(
+        stdout(&todo(&home, &["report", "--since", "today"])),
+        "No time tracked in this period.\n"
+    );
+    assert!(stdout(&todo(&home, &["show", "2"])).contains("\n  time: "));
+
+    let conflict = todo(&home, &["report", "--week", "--since", "today"]);
+    assert_eq!(conflict.status.code(), Some(2));
+
+    let _ = fs::remove_dir_all(&home);
+}
+
+#[test]
+fn store_in_the_working_directory_is_pointed_at_until_migrated() {
+    let home = temp_home("migrate");
+    let project = home.join("project");
+    fs::create_dir_all(&project).expect("project dir should be creatable");
+    let legacy = project.join("todos.bin");
+    let legacy_arg = legacy.to_string_lossy().into_owned();
+    todo(&home, &["--file", &legacy_arg, "add", "Old task"]);
+    let in_project = |args: &[&str]| {
+        command(&home)
+            .current_dir(&project)
+            .args(args)
+            .output()
+            .expect("todo binary should run")
+    };
+
+    // Reading the default list only prints a hint; the file stays put.
+    let listed = in_project(&["list"]);
+    assert!(listed.status.success(), "{}", stderr(&listed));
+    assert_eq!(stdout(&listed), "No pending tasks.\n");
+    assert!(stderr(&listed).contains("run `todo migrate`"));
+    assert!(legacy.exists());
+
+    let migrated = in_project(&["migrate"]);
+    assert!(migrated.status.success(), "{}", stderr(&migrated));
+    assert!(!legacy.exists());
+    let listed = in_project(&["list"]);
+    assert!(stdout(&listed).contains("Old task"));
+    assert!(stderr(&listed).is_empty());
+
+    let again = in_project(&["migrate"]);
+    assert_eq!(again.status.code(), Some(1));
+    assert!(stderr(&again).contains("no todos.bin"));
+
+    let _ = fs::remove_dir_all(&home);
+}
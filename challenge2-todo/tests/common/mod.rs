@@ -0,0 +1,30 @@
+//! Fixtures shared by the integration tests.
+
+use challenge2_todo::archive::archive_path;
+use challenge2_todo::journal::journal_path;
+use challenge2_todo::store;
+use challenge2_todo::undo::undo_path;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Path of a store in the temp directory that no other test run uses.
+pub fn temp_store(name: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos());
+    env::temp_dir().join(format!("challenge2_{name}_{}_{nanos}.bin", process::id()))
+}
+
+/// Removes the store at `path` with every file kept next to it.
+pub fn remove_store(path: &Path) {
+    let _ = fs::remove_file(store::backup_path(path));
+    let _ = fs::remove_file(store::lock_path(path));
+    let _ = fs::remove_file(archive_path(path));
+    let _ = fs::remove_file(journal_path(path));
+    let _ = fs::remove_file(undo_path(path));
+    let _ = fs::remove_file(path);
+}
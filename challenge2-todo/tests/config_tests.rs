@@ -0,0 +1,231 @@
+use challenge2_todo::config::{self, Config, DEFAULT_LIST, Dirs, Location};
+use challenge2_todo::dates::TimeFormat;
+use challenge2_todo::{Priority, TodoApp};
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+fn dirs_from(vars: &[(&str, &str)]) -> std::io::Result<Dirs> {
+    Dirs::from_vars(|key| {
+        vars.iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| OsString::from(value))
+    })
+}
+
+fn sample_dirs() -> Dirs {
+    Dirs {
+        data_dir: PathBuf::from("/data/todo"),
+        config_file: PathBuf::from("/config/todo/config.toml"),
+    }
+}
+
+#[test]
+fn dirs_follow_xdg_variables_and_fall_back_to_home() {
+    let dirs = dirs_from(&[
+        ("XDG_DATA_HOME", "/xdg/data"),
+        ("XDG_CONFIG_HOME", "/xdg/config"),
+        ("HOME", "/home/me"),
+    ])
+    .expect("dirs should resolve");
+    assert_eq!(dirs.data_dir, Path::new("/xdg/data/todo"));
+    assert_eq!(dirs.config_file, Path::new("/xdg/config/todo/config.toml"));
+
+    // Relative XDG values are invalid per the spec and ignored.
+    let dirs = dirs_from(&[("XDG_DATA_HOME", "relative"), ("HOME", "/home/me")])
+        .expect("dirs should resolve");
+    assert_eq!(dirs.data_dir, Path::new("/home/me/.local/share/todo"));
+    assert_eq!(
+        dirs.config_file,
+        Path::new("/home/me/.config/todo/config.toml")
+    );
+
+    assert!(dirs_from(&[]).is_err());
+}
+
+#[test]
+fn config_parses_defaults_and_rejects_bad_values() {
+    let config: Config = "data-dir = \"/sync/todo\"\nlist = \"work\"\npriority = \"High\"\n\
+                          time-format = \"%d %b %H:%M\"\nutc = true\nundo-depth = 20\n"
+        .parse()
+        .expect("config should parse");
+    assert_eq!(
+        config,
+        Config {
+            data_dir: Some(PathBuf::from("/sync/todo")),
+            list: Some("work".to_string()),
+            priority: Some(Priority::High),
+            time_format: Some("%d %b %H:%M".parse().expect("pattern should parse")),
+            utc: true,
+            undo_depth: Some(20),
+        }
+    );
+    assert_eq!("".parse::<Config>(), Ok(Config::default()));
+    assert_eq!(
+        Config::default().time_format(true),
+        TimeFormat::default().in_utc(true)
+    );
+
+    let error = "priority = \"asap\"".parse::<Config>().unwrap_err();
+    assert!(error.contains("unknown priority 'asap'"), "{error}");
+    assert!("colour = \"red\"".parse::<Config>().is_err());
+    let error = "time-format = \"%Q\"".parse::<Config>().unwrap_err();
+    assert!(error.contains("invalid time format '%Q'"), "{error}");
+    assert!("list = \"../escape\"".parse::<Config>().is_err());
+
+    let missing = env::temp_dir().join("challenge2_config_missing/config.toml");
+    assert_eq!(
+        Config::load(&missing).expect("missing config is fine"),
+        Config::default()
+    );
+}
+
+#[test]
+fn location_prefers_flags_then_env_then_config() {
+    let dirs = sample_dirs();
+    let config = Config {
+        list: Some("home".to_string()),
+        ..Config::default()
+    };
+    let env_file = Some(PathBuf::from("/env/todos.bin"));
+
+    let resolve = |file: Option<&str>, list: Option<&str>, env_file: Option<PathBuf>| {
+        Location::resolve(
+            file.map(PathBuf::from),
+            list.map(str::to_string),
+            env_file,
+            &config,
+            &dirs,
+        )
+    };
+
+    assert_eq!(
+        resolve(Some("/tmp/a.bin"), None, env_file.clone()),
+        Ok(Location::File(PathBuf::from("/tmp/a.bin")))
+    );
+    assert!(resolve(Some("/tmp/a.bin"), Some("work"), None).is_err());
+
+    let work = resolve(None, Some("work"), env_file.clone()).expect("list wins over env");
+    assert_eq!(work.path(), Path::new("/data/todo/work.bin"));
+    assert_eq!(work.list_name(), Some("work"));
+
+    assert_eq!(
+        resolve(None, None, env_file).expect("env file"),
+        Location::File(PathBuf::from("/env/todos.bin"))
+    );
+    assert_eq!(
+        resolve(None, None, None).expect("config list").list_name(),
+        Some("home")
+    );
+    assert!(resolve(None, Some("a/b"), None).is_err());
+
+    let fallback =
+        Location::resolve(None, None, None, &Config::default(), &dirs).expect("default list");
+    assert_eq!(fallback.list_name(), Some(DEFAULT_LIST));
+
+    let moved = Config {
+        data_dir: Some(PathBuf::from("/sync")),
+        ..Config::default()
+    };
+    let moved = Location::resolve(None, Some("work".to_string()), None, &moved, &dirs)
+        .expect("custom data dir");
+    assert_eq!(moved.path(), Path::new("/sync/work.bin"));
+}
+
+#[test]
+fn list_names_skip_files_that_belong_to_a_list() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos());
+    let dir = env::temp_dir().join(format!("challenge2_lists_{nanos}"));
+    fs::create_dir_all(&dir).expect("data dir should be creatable");
+    for name in [
+        "work.bin",
+        "work.archive.bin",
+        "work.journal",
+        "work.bin.archive",
+        "work.bin.journal",
+        "work.bin.lock",
+        "work.bin.bak",
+        "home.bin",
+        "notes.txt",
+    ] {
+        fs::write(dir.join(name), b"").expect("file should be writable");
+    }
+
+    assert_eq!(
+        config::list_names(&dir).expect("lists should read"),
+        vec!["home".to_string(), "work".to_string()]
+    );
+    assert!(
+        config::list_names(&dir.join("missing"))
+            .expect("missing dir has no lists")
+            .is_empty()
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn store_in_the_working_directory_is_only_moved_when_asked() {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos());
+    let root = env::temp_dir().join(format!("challenge2_legacy_{nanos}"));
+    let working_dir = root.join("project");
+    let data_dir = root.join("data");
+    fs::create_dir_all(&working_dir).expect("working dir should be creatable");
+    fs::create_dir_all(&data_dir).expect("data dir should be creatable");
+
+    let legacy = working_dir.join(config::LEGACY_FILE);
+    let mut app = TodoApp::load_or_new(&legacy).expect("load should succeed");
+    app.add_task("Old task".to_string())
+        .expect("add should succeed");
+    app.add_task("Done task".to_string())
+        .expect("add should succeed");
+    app.complete(2).expect("complete should succeed");
+    drop(app);
+
+    let list = |name: &str| Location::List {
+        data_dir: data_dir.clone(),
+        name: name.to_string(),
+    };
+    // Only the default list points at it, and checking moves nothing.
+    assert_eq!(config::legacy_store(&list("work"), &working_dir), None);
+    let default = list(DEFAULT_LIST);
+    assert_eq!(
+        config::legacy_store(&default, &working_dir),
+        Some(legacy.clone())
+    );
+    assert!(legacy.exists());
+
+    assert_eq!(
+        config::migrate_legacy_store(&default, &working_dir).expect("move should succeed"),
+        legacy
+    );
+    assert!(!legacy.exists());
+    assert_eq!(config::legacy_store(&default, &working_dir), None);
+
+    let app = TodoApp::load_or_new(default.path()).expect("moved store should load");
+    let pending: Vec<&str> = app
+        .list_tasks()
+        .map(|todo| todo.description.as_str())
+        .collect();
+    assert_eq!(pending, vec!["Old task"]);
+    assert_eq!(app.history().count(), 1);
+    assert_eq!(app.undo_labels().count(), 3);
+
+    // Once the default list exists, a new file in the working directory is left alone.
+    fs::write(&legacy, b"").expect("file should be writable");
+    assert_eq!(config::legacy_store(&default, &working_dir), None);
+    let error = config::migrate_legacy_store(&default, &working_dir)
+        .expect_err("an existing list should not be replaced");
+    assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+    assert!(legacy.exists());
+
+    let _ = fs::remove_dir_all(&root);
+}
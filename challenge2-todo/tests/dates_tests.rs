@@ -0,0 +1,68 @@
+use challenge2_todo::dates::{Moment, TimeFormat, format_age, parse_date};
+
+const NOW: u64 = 1_800_000_000;
+
+#[test]
+fn time_format_renders_patterns_in_utc_or_local_time() {
+    let utc = TimeFormat::default().in_utc(true);
+    assert_eq!(utc.format(NOW), "2027-01-15 08:00");
+    assert_eq!(
+        utc.with_age(NOW - 3 * 3_600, NOW),
+        "2027-01-15 05:00 (3h ago)"
+    );
+
+    let custom: TimeFormat = "%d %b %Y, %H:%M:%S".parse().expect("pattern should parse");
+    assert_eq!(
+        custom.clone().in_utc(true).format(NOW),
+        "15 Jan 2027, 08:00:00"
+    );
+
+    // Local rendering agrees with how dates are read back in.
+    let local = TimeFormat::default();
+    assert_eq!(parse_date(&local.format(NOW)), Ok(NOW));
+
+    assert!("".parse::<TimeFormat>().is_err());
+    assert!("%Y-%Q".parse::<TimeFormat>().is_err());
+}
+
+#[test]
+fn ages_use_the_largest_whole_unit() {
+    let cases = [
+        (NOW, "just now"),
+        (NOW - 59, "just now"),
+        (NOW - 90, "1m ago"),
+        (NOW - 3 * 3_600 - 59, "3h ago"),
+        (NOW - 2 * 86_400, "2d ago"),
+        (NOW - 20 * 86_400, "2w ago"),
+        (NOW - 100 * 86_400, "3mo ago"),
+        (NOW - 800 * 86_400, "2y ago"),
+        (NOW + 4 * 3_600, "in 4h"),
+    ];
+    for (timestamp, expected) in cases {
+        assert_eq!(format_age(timestamp, NOW), expected, "{timestamp}");
+    }
+}
+
+#[test]
+fn moments_read_dates_and_ages() {
+    let parse = |input: &str| input.parse::<Moment>().map(|moment| moment.resolve(NOW));
+
+    assert_eq!(parse("3h ago"), Ok(NOW - 3 * 3_600));
+    assert_eq!(parse("90m"), Ok(NOW - 90 * 60));
+    assert_eq!(parse("1W AGO"), Ok(NOW - 7 * 86_400));
+    assert_eq!(parse("2026-10-18"), parse_date("2026-10-18"));
+    assert_eq!(parse("2026-10-18 14:02"), parse_date("2026-10-18 14:02"));
+    assert_eq!(parse("2026-10-18T14:02"), parse_date("2026-10-18 14:02"));
+
+    let today = parse("today").expect("today should parse");
+    let yesterday = parse("yesterday").expect("yesterday should parse");
+    assert!(today <= NOW && NOW - today < 86_400, "{today}");
+    assert!(yesterday < today && today - yesterday <= 25 * 3_600);
+
+    for input in ["", "soon", "3y", "3h later", "2026-13-01"] {
+        assert!(
+            input.parse::<Moment>().is_err(),
+            "'{input}' should not parse"
+        );
+    }
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use challenge2_todo::export::{self, Format, TaskRecord};
+use challenge2_todo::{ImportMode, ImportSummary, NewTask, Priority, TodoApp};
+use common::{remove_store, temp_store};
+
+fn descriptions(app: &TodoApp) -> Vec<(u64, &str)> {
+    app.list_tasks()
+        .map(|todo| (todo.id, todo.description.as_str()))
+        .collect()
+}
+
+#[test]
+fn csv_and_tsv_escape_fields_and_share_the_header() {
+    let file_path = temp_store("escape");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let options = NewTask {
+        project: Some("backend".to_string()),
+        tags: vec!["a".to_string(), "b".to_string()],
+        ..NewTask::default()
+    };
+    let todo = app
+        .add_task_with("Say \"hi\", then\ttab".to_string(), options)
+        .expect("add should succeed");
+    app.add_subtask(todo.id, "step".to_string())
+        .expect("subtask should be added");
+
+    let mut csv = Vec::new();
+    export::write_tasks(Format::Csv, app.list_tasks(), &mut csv).expect("csv should write");
+    let csv = String::from_utf8(csv).expect("csv is utf-8");
+    let created = todo.created_at;
+    assert_eq!(
+        csv,
+        format!(
+            "{}\n1,normal,\"Say \"\"hi\"\", then\ttab\",{created},,backend,a b,,0,1,\n",
+            export::COLUMNS.join(",")
+        )
+    );
+
+    let mut tsv = Vec::new();
+    export::write_task(Format::Tsv, &todo, &mut tsv).expect("tsv should write");
+    let tsv = String::from_utf8(tsv).expect("tsv is utf-8");
+    let rows: Vec<&str> = tsv.lines().collect();
+    assert_eq!(rows[0], export::COLUMNS.join("\t"));
+    assert_eq!(
+        rows[1].split('\t').nth(2),
+        Some("Say \"hi\", then\\ttab"),
+        "tabs inside fields must not split columns"
+    );
+    assert_eq!(rows[1].split('\t').count(), export::COLUMNS.len());
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn json_export_imports_into_an_identical_list() {
+    let source_path = temp_store("source");
+    let target_path = temp_store("target");
+    let mut source = TodoApp::load_or_new(&source_path).expect("load should succeed");
+    source
+        .add_task_with(
+            "Ship release".to_string(),
+            NewTask {
+                priority: Priority::High,
+                due_at: Some(1_900_000_000),
+                recurrence: Some("weekly:mon".parse().expect("rule should parse")),
+                ..NewTask::default()
+            },
+        )
+        .expect("add should succeed");
+    source
+        .add_task("Write notes".to_string())
+        .expect("add should succeed");
+    source.add_blocker(2, 1).expect("block should succeed");
+
+    let mut json = Vec::new();
+    export::write_tasks(Format::Json, source.list_tasks(), &mut json).expect("json should write");
+    let records = export::parse_json(&String::from_utf8(json).expect("json is utf-8"))
+        .expect("export should parse back");
+
+    let mut target = TodoApp::load_or_new(&target_path).expect("load should succeed");
+    let summary = target
+        .import(records.clone(), ImportMode::Merge)
+        .expect("import should succeed");
+    assert_eq!(summary.added, 2);
+
+    let reloaded = TodoApp::load_or_new(&target_path).expect("reload should succeed");
+    let round_trip: Vec<TaskRecord> = reloaded.list_tasks().map(TaskRecord::from).collect();
+    assert_eq!(round_trip, records);
+    assert_eq!(reloaded.pending_blockers(2), vec![1]);
+
+    remove_store(&source_path);
+    remove_store(&target_path);
+}
+
+#[test]
+fn import_merges_by_id_and_replace_drops_the_rest() {
+    let file_path = temp_store("merge");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    for description in ["One", "Two", "Three"] {
+        app.add_task(description.to_string())
+            .expect("add should succeed");
+    }
+    app.complete(3).expect("complete should succeed");
+
+    // Id 1 is updated, 3 comes back from the archive, the id-less record gets a
+    // fresh id above every id in the file, and the duplicate 7 keeps its last copy.
+    let records = export::parse_json(
+        r#"[
+            {"id": 1, "description": "One, renamed"},
+            {"description": "No id"},
+            {"id": 3, "description": "Three again", "priority": "low"},
+            {"id": 7, "description": "Seven"},
+            {"id": 7, "description": "Seven, last copy"}
+        ]"#,
+    )
+    .expect("records should parse");
+    let summary = app
+        .import(records, ImportMode::Merge)
+        .expect("import should succeed");
+    assert_eq!(
+        summary,
+        ImportSummary {
+            added: 2,
+            updated: 1,
+            restored: 1,
+            archived: 0,
+            removed: 0,
+        }
+    );
+    assert_eq!(
+        descriptions(&app),
+        vec![
+            (1, "One, renamed"),
+            (2, "Two"),
+            (8, "No id"),
+            (7, "Seven, last copy"),
+            (3, "Three again"),
+        ]
+    );
+    assert!(app.history().all(|entry| entry.todo.id != 3));
+
+    let records = export::parse_json(r#"[{"id": 2, "description": "Only two"}]"#)
+        .expect("records should parse");
+    let summary = app
+        .import(records, ImportMode::Replace)
+        .expect("replace should succeed");
+    assert_eq!(summary.removed, 4);
+    assert_eq!(summary.updated, 1);
+
+    let mut reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    assert_eq!(descriptions(&reloaded), vec![(2, "Only two")]);
+    let next = reloaded
+        .add_task("After import".to_string())
+        .expect("add should succeed");
+    assert_eq!(next.id, 9, "imported ids are never reused");
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn invalid_records_are_rejected_without_changes() {
+    let file_path = temp_store("invalid");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    app.add_task("Keep me".to_string())
+        .expect("add should succeed");
+
+    assert!(export::parse_json(r#"{"description": "not an array"}"#).is_err());
+    assert!(export::parse_json(r#"[{"id": 1}]"#).is_err());
+
+    let records = export::parse_json(
+        r#"[{"description": "Fine"}, {"description": "Bad", "priority": "asap"}]"#,
+    )
+    .expect("records should parse");
+    let err = app
+        .import(records, ImportMode::Replace)
+        .expect_err("bad priority should fail");
+    assert!(err.to_string().contains("task 2"), "{err}");
+    assert_eq!(descriptions(&app), vec![(1, "Keep me")]);
+
+    remove_store(&file_path);
+}
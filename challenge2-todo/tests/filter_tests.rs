@@ -0,0 +1,90 @@
+use challenge2_todo::{Filter, InlineTags, Priority, Todo};
+
+const NOW: u64 = 1_800_000_000;
+
+fn todo(description: &str, project: Option<&str>, tags: &[&str], due_at: Option<u64>) -> Todo {
+    Todo {
+        id: 1,
+        description: description.to_string(),
+        created_at: NOW,
+        priority: Priority::Normal,
+        due_at,
+        project: project.map(str::to_string),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        recurrence: None,
+        subtasks: Vec::new(),
+        blocked_by: Vec::new(),
+        metadata: Vec::new(),
+        notes: None,
+        attachments: Vec::new(),
+        time_entries: Vec::new(),
+    }
+}
+
+fn matches(expression: &str, todo: &Todo) -> bool {
+    let filter: Filter = expression.parse().expect("filter should parse");
+    filter.matches(todo, NOW)
+}
+
+#[test]
+fn inline_tags_are_split_from_description() {
+    let inline = InlineTags::parse("Fix bug +backend @urgent @Urgent reply +1");
+
+    assert_eq!(inline.description, "Fix bug reply +1");
+    assert_eq!(inline.project.as_deref(), Some("backend"));
+    assert_eq!(inline.tags, vec!["urgent".to_string()]);
+}
+
+#[test]
+fn filter_combines_terms_with_and_or_not() {
+    let overdue = todo("Fix login", Some("backend"), &["urgent"], Some(NOW - 1));
+    let upcoming = todo("Ship docs", Some("backend"), &["urgent"], None);
+
+    let expression = "project:backend and tag:urgent and not overdue";
+    assert!(!matches(expression, &overdue));
+    assert!(matches(expression, &upcoming));
+
+    // Adjacent terms are an implicit `and`; shorthand sigils match like the inline syntax.
+    assert!(matches("+BACKEND @urgent docs", &upcoming));
+    assert!(!matches("+backend login", &upcoming));
+
+    assert!(matches("due:none or (overdue and login)", &overdue));
+    assert!(matches("due:none or (overdue and login)", &upcoming));
+    assert!(!matches("not (tag:urgent or priority:high)", &upcoming));
+}
+
+#[test]
+fn filter_compares_creation_times() {
+    let task = todo("Ship docs", None, &[], None);
+
+    assert!(matches("created>3h ago", &task));
+    assert!(!matches("created<3h", &task));
+    assert!(matches("created>today and docs", &task));
+    assert!(!matches("created<yesterday", &task));
+
+    // A date may carry the time as a second word, the way list rows show it.
+    assert!(matches("created>2020-01-01 10:00 and not overdue", &task));
+    assert!(matches("CREATED<2099-01-01T10:00", &task));
+    assert!(!matches("created<2020-01-01", &task));
+}
+
+#[test]
+fn filter_rejects_malformed_expressions() {
+    for expression in [
+        "",
+        "tag:",
+        "(overdue",
+        "overdue)",
+        "and overdue",
+        "priority:soon",
+        "when:now",
+        "created>",
+        "created>soon",
+        "created:today",
+    ] {
+        assert!(
+            expression.parse::<Filter>().is_err(),
+            "'{expression}' should not parse"
+        );
+    }
+}
@@ -0,0 +1,550 @@
+mod common;
+
+use challenge2_todo::archive::{self, ARCHIVE_MAGIC, ArchivedTodo, archive_path};
+use challenge2_todo::dates::parse_due;
+use challenge2_todo::journal::{JOURNAL_MAGIC, journal_path};
+use challenge2_todo::store::{self, FORMAT_VERSION, LEGACY_VERSION, MAGIC};
+use challenge2_todo::{Outcome, Priority, Recurrence, Todo, TodoApp};
+use common::{remove_store, temp_store};
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+// Fixtures were written byte-by-byte from the layout of each version:
+// - `todos_legacy.bin`: header-less Borsh `Vec` of `{ id, description, created_at }`
+//   holding "Buy groceries" (#1) and "Pay bills" (#2).
+// - `todos_v1.bin`: `TODO` + version 1 + Borsh `Vec<Todo>` holding a fully populated
+//   "Ship release" (#1) and a bare "Review PR" (#2).
+// - `todos_v2.bin`: the same tasks as `todos_v1.bin` behind `TODO` + version 2 and a
+//   `u64` journal generation of 3.
+// - `todos_v3.bin`: `todos_v2.bin` behind version 3, where each task ends with a
+//   `metadata` list; "Review PR" (#2) has `t` = `2026-10-15`.
+// - `todos_v4.bin`: `todos_v3.bin` behind version 4, where each task ends with
+//   `notes` and `attachments`; "Review PR" (#2) has two lines of notes and a URL.
+// - `todos_v5.bin`: `todos_v4.bin` behind version 5, where each task ends with
+//   `time_entries`; "Ship release" (#1) has a finished entry and a running one.
+// - `todos_v6.bin`: `todos_v5.bin` behind version 6, where `Monthly` rules carry
+//   their day; neither task is monthly, so only the version differs.
+fn fixture(name: &str) -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    fs::read(path).expect("fixture should exist")
+}
+
+fn write_temp_store(name: &str, bytes: &[u8]) -> PathBuf {
+    let path = temp_store(name);
+    fs::write(&path, bytes).expect("temp store should be writable");
+    path
+}
+
+/// Borsh bytes of `todo` in the version 4 layout: the current one without the
+/// trailing `time_entries` length, which is all an empty list encodes to.
+fn v4_todo_bytes(todo: &Todo) -> Vec<u8> {
+    assert!(todo.time_entries.is_empty());
+    let mut bytes = borsh::to_vec(todo).expect("todo should encode");
+    bytes.truncate(bytes.len() - 4);
+    bytes
+}
+
+/// Borsh bytes of `todo` in the version 3 layout: the version 4 one without the
+/// trailing `notes` tag and `attachments` length.
+fn v3_todo_bytes(todo: &Todo) -> Vec<u8> {
+    assert!(todo.notes.is_none() && todo.attachments.is_empty());
+    let mut bytes = v4_todo_bytes(todo);
+    bytes.truncate(bytes.len() - 5);
+    bytes
+}
+
+/// Borsh bytes of `todo` in the version 2 layout: the version 3 one without the
+/// trailing `metadata` length.
+fn v2_todo_bytes(todo: &Todo) -> Vec<u8> {
+    assert!(todo.metadata.is_empty());
+    let mut bytes = v3_todo_bytes(todo);
+    bytes.truncate(bytes.len() - 4);
+    bytes
+}
+
+/// Borsh bytes of `todo` in the version 5 layout with a day-less `Monthly` rule:
+/// the current one with `recurrence` set to `Some` and the old variant index.
+fn v5_monthly_todo_bytes(todo: &Todo) -> Vec<u8> {
+    assert!(todo.recurrence.is_none());
+    let before_recurrence = borsh::to_vec(&(
+        todo.id,
+        &todo.description,
+        todo.created_at,
+        todo.priority,
+        todo.due_at,
+        &todo.project,
+        &todo.tags,
+    ))
+    .expect("fields should encode");
+    let at = before_recurrence.len();
+    let mut bytes = borsh::to_vec(todo).expect("todo should encode");
+    bytes.splice(at..=at, [1, 3]); // `Some(Monthly)`
+    bytes
+}
+
+fn sample_todo(id: u64, description: &str) -> Todo {
+    Todo {
+        id,
+        description: description.to_string(),
+        created_at: 1_700_000_000 + id,
+        priority: Priority::Normal,
+        due_at: None,
+        project: None,
+        tags: Vec::new(),
+        recurrence: None,
+        subtasks: Vec::new(),
+        blocked_by: Vec::new(),
+        metadata: Vec::new(),
+        notes: None,
+        attachments: Vec::new(),
+        time_entries: Vec::new(),
+    }
+}
+
+#[test]
+fn legacy_file_is_migrated_with_backup() {
+    let legacy = fixture("todos_legacy.bin");
+    let path = write_temp_store("legacy", &legacy);
+
+    let app = TodoApp::load_or_new(&path).expect("legacy file should load");
+    let tasks: Vec<(u64, &str, u64, Priority)> = app
+        .list_tasks()
+        .map(|todo| {
+            (
+                todo.id,
+                todo.description.as_str(),
+                todo.created_at,
+                todo.priority,
+            )
+        })
+        .collect();
+    assert_eq!(
+        tasks,
+        vec![
+            (1, "Buy groceries", 1_700_000_000, Priority::Normal),
+            (2, "Pay bills", 1_700_000_100, Priority::Normal),
+        ]
+    );
+
+    // The original bytes are kept and the file itself is rewritten in the current format.
+    let backup = fs::read(store::backup_path(&path)).expect("backup should exist");
+    assert_eq!(backup, legacy);
+    let migrated = fs::read(&path).expect("migrated file should exist");
+    assert!(migrated.starts_with(&MAGIC));
+    let snapshot = store::decode(&migrated).expect("migrated file should decode");
+    assert_eq!(snapshot.version, FORMAT_VERSION);
+
+    let reloaded = TodoApp::load_or_new(&path).expect("migrated file should load");
+    assert_eq!(reloaded.len(), 2);
+
+    remove_store(&path);
+}
+
+#[test]
+fn v1_file_loads_every_field_and_is_migrated() {
+    let v1 = fixture("todos_v1.bin");
+    let path = write_temp_store("v1", &v1);
+
+    let app = TodoApp::load_or_new(&path).expect("v1 file should load");
+    let ship = app.get(1).expect("task #1 should load");
+    assert_eq!(ship.description, "Ship release");
+    assert_eq!(ship.priority, Priority::High);
+    assert_eq!(ship.due_at, Some(1_760_086_399));
+    assert_eq!(ship.project.as_deref(), Some("backend"));
+    assert_eq!(ship.tags, vec!["urgent".to_string()]);
+    assert_eq!(ship.recurrence, Some(Recurrence::Weekly(0b0000_1001)));
+    assert_eq!(ship.subtasks.len(), 1);
+    assert!(ship.subtasks[0].done);
+    assert_eq!(ship.blocked_by, vec![2]);
+
+    let review = app.get(2).expect("task #2 should load");
+    assert_eq!(review.description, "Review PR");
+    assert!(review.due_at.is_none() && review.tags.is_empty());
+
+    // Version 1 predates the journal, so it is upgraded like a legacy file.
+    let backup = fs::read(store::backup_path(&path)).expect("backup should exist");
+    assert_eq!(backup, v1);
+    let migrated = store::decode(&fs::read(&path).expect("migrated file should exist"))
+        .expect("migrated file should decode");
+    assert_eq!(migrated.version, FORMAT_VERSION);
+    assert_eq!(migrated.todos.len(), 2);
+
+    remove_store(&path);
+}
+
+#[test]
+fn header_less_files_of_every_pre_version_layout_load() {
+    // Before the header, releases appended priority, the due date, project and
+    // tags, then recurrence to each task; the queue and archive both kept them.
+    let rent = |priority| (1_u64, "Pay rent".to_string(), 1_700_000_000_u64, priority);
+    let with_priority = borsh::to_vec(&vec![rent(Priority::High)]).expect("encode");
+    let with_due =
+        borsh::to_vec(&vec![(rent(Priority::Low), Some(1_700_086_400_u64))]).expect("encode");
+    let with_tags = borsh::to_vec(&vec![(
+        rent(Priority::Urgent),
+        None::<u64>,
+        Some("home".to_string()),
+        vec!["bills".to_string()],
+    )])
+    .expect("encode");
+    let with_recurrence = borsh::to_vec(&vec![(
+        rent(Priority::Normal),
+        Some(1_700_086_400_u64),
+        None::<String>,
+        Vec::<String>::new(),
+        Some(Recurrence::EveryDays(14)),
+    )])
+    .expect("encode");
+
+    let loaded = |bytes: &[u8]| {
+        let snapshot = store::decode(bytes).expect("header-less file should decode");
+        assert_eq!(snapshot.version, LEGACY_VERSION);
+        snapshot.todos.into_iter().next().expect("one task")
+    };
+    assert_eq!(loaded(&with_priority).priority, Priority::High);
+    let due = loaded(&with_due);
+    assert_eq!(
+        (due.priority, due.due_at),
+        (Priority::Low, Some(1_700_086_400))
+    );
+    let tagged = loaded(&with_tags);
+    assert_eq!(tagged.project.as_deref(), Some("home"));
+    assert_eq!(tagged.tags, vec!["bills".to_string()]);
+    let recurring = loaded(&with_recurrence);
+    assert_eq!(recurring.description, "Pay rent");
+    assert_eq!(recurring.recurrence, Some(Recurrence::EveryDays(14)));
+
+    let path = write_temp_store("header_less", &with_tags);
+    let archive = borsh::to_vec(&vec![(
+        (2_u64, "Renew lease".to_string(), 1_700_000_000_u64),
+        Priority::High,
+        Some(1_700_000_500_u64),
+        1_700_000_600_u64,
+        Outcome::Done,
+    )])
+    .expect("encode");
+    fs::write(archive_path(&path), archive).expect("archive should be writable");
+    let app = TodoApp::load_or_new(&path).expect("header-less store should load");
+    assert_eq!(app.get(1).map(|todo| todo.priority), Some(Priority::Urgent));
+    let archived = app.history().next().expect("archive should load");
+    assert_eq!(archived.todo.description, "Renew lease");
+    assert_eq!(archived.todo.due_at, Some(1_700_000_500));
+    assert_eq!(
+        (archived.completed_at, archived.outcome),
+        (1_700_000_600, Outcome::Done)
+    );
+
+    remove_store(&path);
+}
+
+#[test]
+fn current_file_loads_without_migration() {
+    let v6 = fixture("todos_v6.bin");
+    let path = write_temp_store("v6", &v6);
+
+    let app = TodoApp::load_or_new(&path).expect("v6 file should load");
+    let ids: Vec<u64> = app.list_tasks().map(|todo| todo.id).collect();
+    assert_eq!(ids, vec![1, 2]);
+    assert_eq!(
+        app.get(1).and_then(|todo| todo.recurrence),
+        Some(Recurrence::Weekly(0b0000_1001))
+    );
+    assert_eq!(
+        app.get(2).map(|todo| todo.metadata.clone()),
+        Some(vec![("t".to_string(), "2026-10-15".to_string())])
+    );
+    let review = app.get(2).expect("task #2 should load");
+    assert_eq!(
+        review.notes.as_deref(),
+        Some("Check the migration.\nThen approve.")
+    );
+    assert_eq!(review.attachments, vec!["https://example.com/pr/42"]);
+    let ship = app.get(1).expect("task #1 should load");
+    assert_eq!(ship.running_since(), Some(1_760_003_600));
+    assert_eq!(ship.time_spent(0, 1_760_004_200), 1_800 + 600);
+
+    assert!(!store::backup_path(&path).exists());
+    assert_eq!(fs::read(&path).expect("file is untouched"), v6);
+
+    remove_store(&path);
+}
+
+#[test]
+fn v2_store_with_journal_and_archive_is_upgraded() {
+    let v2 = fixture("todos_v2.bin");
+    let path = write_temp_store("v2", &v2);
+
+    // A version 2 journal for the fixture's generation 3 that adds task #3.
+    let added = v2_todo_bytes(&sample_todo(3, "From the journal"));
+    let mut journal = JOURNAL_MAGIC.to_vec();
+    journal.extend_from_slice(&2_u16.to_le_bytes());
+    journal.extend_from_slice(&3_u64.to_le_bytes());
+    journal.extend_from_slice(&(added.len() as u32 + 1).to_le_bytes());
+    journal.push(0); // `JournalOp::Add`
+    journal.extend_from_slice(&added);
+    fs::write(journal_path(&path), journal).expect("journal should be writable");
+
+    // A header-less archive holding task #4, completed.
+    let mut archived = 1_u32.to_le_bytes().to_vec();
+    archived.extend_from_slice(&v2_todo_bytes(&sample_todo(4, "Archived")));
+    archived.extend_from_slice(&1_700_000_500_u64.to_le_bytes());
+    archived.push(0); // `Outcome::Done`
+    fs::write(archive_path(&path), archived).expect("archive should be writable");
+
+    let app = TodoApp::load_or_new(&path).expect("v2 store should load");
+    let ids: Vec<u64> = app.list_tasks().map(|todo| todo.id).collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+    let history: Vec<(u64, &str, u64, Outcome)> = app
+        .history()
+        .map(|entry| {
+            (
+                entry.todo.id,
+                entry.todo.description.as_str(),
+                entry.completed_at,
+                entry.outcome,
+            )
+        })
+        .collect();
+    assert_eq!(history, vec![(4, "Archived", 1_700_000_500, Outcome::Done)]);
+
+    // Every file is rewritten in the current format, the store keeping a backup.
+    assert_eq!(
+        fs::read(store::backup_path(&path)).expect("backup should exist"),
+        v2
+    );
+    let snapshot =
+        store::decode(&fs::read(&path).expect("store should exist")).expect("store should decode");
+    assert_eq!(
+        (snapshot.version, snapshot.todos.len()),
+        (FORMAT_VERSION, 3)
+    );
+    let archive_bytes = fs::read(archive_path(&path)).expect("archive should exist");
+    assert!(archive_bytes.starts_with(&ARCHIVE_MAGIC));
+    let journal = fs::read(journal_path(&path)).expect("journal should exist");
+    assert_eq!(journal[4..6], FORMAT_VERSION.to_le_bytes());
+
+    let reloaded = TodoApp::load_or_new(&path).expect("upgraded store should load");
+    assert_eq!(reloaded.len(), 3);
+    assert_eq!(reloaded.history().count(), 1);
+
+    remove_store(&path);
+}
+
+#[test]
+fn v3_store_with_journal_and_archive_is_upgraded() {
+    let v3 = fixture("todos_v3.bin");
+    let path = write_temp_store("v3", &v3);
+
+    // A version 3 journal for generation 3 that adds task #3 and then removes task #1.
+    let added = v3_todo_bytes(&sample_todo(3, "From the journal"));
+    let mut journal = JOURNAL_MAGIC.to_vec();
+    journal.extend_from_slice(&3_u16.to_le_bytes());
+    journal.extend_from_slice(&3_u64.to_le_bytes());
+    journal.extend_from_slice(&(added.len() as u32 + 1).to_le_bytes());
+    journal.push(0); // `JournalOp::Add`
+    journal.extend_from_slice(&added);
+    journal.extend_from_slice(&9_u32.to_le_bytes());
+    journal.push(6); // `JournalOp::Remove`
+    journal.extend_from_slice(&1_u64.to_le_bytes());
+    fs::write(journal_path(&path), journal).expect("journal should be writable");
+
+    // A version 3 archive holding task #4, deleted.
+    let mut archived = ARCHIVE_MAGIC.to_vec();
+    archived.extend_from_slice(&3_u16.to_le_bytes());
+    archived.extend_from_slice(&1_u32.to_le_bytes());
+    archived.extend_from_slice(&v3_todo_bytes(&sample_todo(4, "Archived")));
+    archived.extend_from_slice(&1_700_000_500_u64.to_le_bytes());
+    archived.push(1); // `Outcome::Deleted`
+    fs::write(archive_path(&path), archived).expect("archive should be writable");
+
+    let app = TodoApp::load_or_new(&path).expect("v3 store should load");
+    let ids: Vec<u64> = app.list_tasks().map(|todo| todo.id).collect();
+    assert_eq!(ids, vec![2, 3]);
+    assert!(app.list_tasks().all(|todo| todo.notes.is_none()));
+    let history: Vec<(u64, Outcome)> = app
+        .history()
+        .map(|entry| (entry.todo.id, entry.outcome))
+        .collect();
+    assert_eq!(history, vec![(4, Outcome::Deleted)]);
+
+    assert_eq!(
+        fs::read(store::backup_path(&path)).expect("backup should exist"),
+        v3
+    );
+    let archive = fs::read(archive_path(&path)).expect("archive should exist");
+    assert_eq!(archive[4..6], FORMAT_VERSION.to_le_bytes());
+    let journal = fs::read(journal_path(&path)).expect("journal should exist");
+    assert_eq!(journal[4..6], FORMAT_VERSION.to_le_bytes());
+
+    remove_store(&path);
+}
+
+#[test]
+fn v4_store_with_journal_and_archive_is_upgraded() {
+    let v4 = fixture("todos_v4.bin");
+    let path = write_temp_store("v4", &v4);
+
+    // A version 4 journal for generation 3 that edits task #2's notes.
+    let edited = v4_todo_bytes(&Todo {
+        notes: Some("Approved.".to_string()),
+        ..sample_todo(2, "Review PR")
+    });
+    let mut journal = JOURNAL_MAGIC.to_vec();
+    journal.extend_from_slice(&4_u16.to_le_bytes());
+    journal.extend_from_slice(&3_u64.to_le_bytes());
+    journal.extend_from_slice(&(edited.len() as u32 + 1).to_le_bytes());
+    journal.push(3); // `JournalOp::Edit`
+    journal.extend_from_slice(&edited);
+    fs::write(journal_path(&path), journal).expect("journal should be writable");
+
+    // A version 4 archive holding task #4, done.
+    let mut archived = ARCHIVE_MAGIC.to_vec();
+    archived.extend_from_slice(&4_u16.to_le_bytes());
+    archived.extend_from_slice(&1_u32.to_le_bytes());
+    archived.extend_from_slice(&v4_todo_bytes(&sample_todo(4, "Archived")));
+    archived.extend_from_slice(&1_700_000_500_u64.to_le_bytes());
+    archived.push(0); // `Outcome::Done`
+    fs::write(archive_path(&path), archived).expect("archive should be writable");
+
+    let app = TodoApp::load_or_new(&path).expect("v4 store should load");
+    let review = app.get(2).expect("task #2 should load");
+    assert_eq!(review.notes.as_deref(), Some("Approved."));
+    assert!(app.list_tasks().all(|todo| todo.time_entries.is_empty()));
+    let history: Vec<(u64, Outcome)> = app
+        .history()
+        .map(|entry| (entry.todo.id, entry.outcome))
+        .collect();
+    assert_eq!(history, vec![(4, Outcome::Done)]);
+
+    assert_eq!(
+        fs::read(store::backup_path(&path)).expect("backup should exist"),
+        v4
+    );
+    let archive = fs::read(archive_path(&path)).expect("archive should exist");
+    assert_eq!(archive[4..6], FORMAT_VERSION.to_le_bytes());
+
+    remove_store(&path);
+}
+
+#[test]
+fn v5_store_pins_monthly_rules_to_their_day() {
+    // A version 5 snapshot for generation 0 with a monthly task due on the 31st.
+    let rent = Todo {
+        due_at: Some(parse_due("2026-01-31", 0).expect("date should parse")),
+        ..sample_todo(1, "Pay rent")
+    };
+    let mut v5 = MAGIC.to_vec();
+    v5.extend_from_slice(&5_u16.to_le_bytes());
+    v5.extend_from_slice(&0_u64.to_le_bytes());
+    v5.extend_from_slice(&1_u32.to_le_bytes());
+    v5.extend_from_slice(&v5_monthly_todo_bytes(&rent));
+    let path = write_temp_store("v5", &v5);
+
+    // A version 5 journal adding a monthly task without a due date.
+    let added = v5_monthly_todo_bytes(&sample_todo(2, "Water plants"));
+    let mut journal = JOURNAL_MAGIC.to_vec();
+    journal.extend_from_slice(&5_u16.to_le_bytes());
+    journal.extend_from_slice(&0_u64.to_le_bytes());
+    journal.extend_from_slice(&(added.len() as u32 + 1).to_le_bytes());
+    journal.push(0); // `JournalOp::Add`
+    journal.extend_from_slice(&added);
+    fs::write(journal_path(&path), journal).expect("journal should be writable");
+
+    let app = TodoApp::load_or_new(&path).expect("v5 store should load");
+    assert_eq!(
+        app.get(1).and_then(|todo| todo.recurrence),
+        Some(Recurrence::Monthly(31))
+    );
+    // Without a due date the rule is pinned to the day the task was created.
+    let plants = app.get(2).expect("task #2 should load");
+    assert_eq!(
+        plants.recurrence,
+        Some(Recurrence::Monthly(0).anchored(plants.created_at))
+    );
+
+    assert_eq!(
+        fs::read(store::backup_path(&path)).expect("backup should exist"),
+        v5
+    );
+    let migrated = store::decode(&fs::read(&path).expect("migrated file should exist"))
+        .expect("migrated file should decode");
+    assert_eq!(migrated.version, FORMAT_VERSION);
+
+    remove_store(&path);
+}
+
+#[test]
+fn archive_round_trips_and_rejects_unknown_versions() {
+    let path = write_temp_store("archive", b"");
+    let entries = vec![ArchivedTodo {
+        todo: Todo {
+            metadata: vec![("t".to_string(), "2026-10-15".to_string())],
+            ..sample_todo(9, "Kept")
+        },
+        completed_at: 1_700_000_900,
+        outcome: Outcome::Deleted,
+    }];
+    archive::save(&archive_path(&path), &entries).expect("archive should save");
+    let loaded = archive::load(&archive_path(&path)).expect("archive should load");
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].todo.metadata, entries[0].todo.metadata);
+    assert_eq!(loaded[0].outcome, Outcome::Deleted);
+
+    let mut future = ARCHIVE_MAGIC.to_vec();
+    future.extend_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    future.extend_from_slice(&0_u32.to_le_bytes());
+    fs::write(archive_path(&path), future).expect("archive should be writable");
+    let error = archive::load(&archive_path(&path)).expect_err("future versions are rejected");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    remove_store(&path);
+}
+
+#[test]
+fn decode_reports_versions_and_rejects_unknown_ones() {
+    let legacy = store::decode(&fixture("todos_legacy.bin")).expect("legacy decodes");
+    assert_eq!(
+        (legacy.todos.len(), legacy.version, legacy.generation),
+        (2, LEGACY_VERSION, 0)
+    );
+
+    let v1 = store::decode(&fixture("todos_v1.bin")).expect("v1 decodes");
+    assert_eq!((v1.todos.len(), v1.version, v1.generation), (2, 1, 0));
+
+    let v2 = store::decode(&fixture("todos_v2.bin")).expect("v2 decodes");
+    assert_eq!((v2.todos.len(), v2.version, v2.generation), (2, 2, 3));
+    assert!(v2.todos.iter().all(|todo| todo.metadata.is_empty()));
+
+    let v3 = store::decode(&fixture("todos_v3.bin")).expect("v3 decodes");
+    assert_eq!((v3.todos.len(), v3.version, v3.generation), (2, 3, 3));
+    assert!(v3.todos.iter().all(|todo| todo.notes.is_none()));
+
+    let v4 = store::decode(&fixture("todos_v4.bin")).expect("v4 decodes");
+    assert_eq!((v4.todos.len(), v4.version, v4.generation), (2, 4, 3));
+    assert!(v4.todos.iter().all(|todo| todo.time_entries.is_empty()));
+
+    let v5 = store::decode(&fixture("todos_v5.bin")).expect("v5 decodes");
+    assert_eq!((v5.todos.len(), v5.version, v5.generation), (2, 5, 3));
+
+    let v6 = store::decode(&fixture("todos_v6.bin")).expect("v6 decodes");
+    assert_eq!((v6.todos.len(), v6.version, v6.generation), (2, 6, 3));
+
+    let encoded = store::encode(&v1.todos, 7).expect("encode should succeed");
+    let round_trip = store::decode(&encoded).expect("round trip");
+    assert_eq!((round_trip.todos.len(), round_trip.generation), (2, 7));
+
+    let mut future = MAGIC.to_vec();
+    future.extend_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    future.extend_from_slice(&0_u32.to_le_bytes());
+    let error = store::decode(&future).expect_err("future versions are rejected");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    let path = write_temp_store("future", &future);
+    assert!(TodoApp::load_or_new(&path).is_err());
+    assert_eq!(fs::read(&path).expect("file is untouched"), future);
+    remove_store(&path);
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use challenge2_todo::journal::{self, JournalOp, journal_path};
+use challenge2_todo::store::{self, FORMAT_VERSION};
+use challenge2_todo::{Outcome, Priority, TodoApp};
+use common::{remove_store, temp_store};
+use std::{fs, fs::OpenOptions, io::Write};
+
+fn descriptions(app: &TodoApp) -> Vec<&str> {
+    app.list_tasks()
+        .map(|todo| todo.description.as_str())
+        .collect()
+}
+
+#[test]
+fn mutations_are_appended_and_replayed_after_restart() {
+    let path = temp_store("replay");
+
+    let mut app = TodoApp::load_or_new(&path).expect("load should succeed");
+    app.add_task("Write spec".to_string())
+        .expect("add should succeed");
+    let snapshot = fs::read(&path).expect("first write takes a snapshot");
+
+    app.add_task_with_priority("Fix outage".to_string(), Priority::High)
+        .expect("add should succeed");
+    app.add_task("Old idea".to_string())
+        .expect("add should succeed");
+    app.edit_description(1, "Write the spec".to_string())
+        .expect("edit should succeed");
+    app.complete_next().expect("complete should succeed");
+    app.delete_at(1).expect("delete should succeed");
+
+    // Later mutations only touch the journal.
+    assert_eq!(fs::read(&path).expect("snapshot should exist"), snapshot);
+    let generation = store::decode(&snapshot)
+        .expect("snapshot should decode")
+        .generation;
+    let replay = journal::read(&journal_path(&path), generation).expect("journal should read");
+    assert_eq!(replay.ops.len(), 5);
+    assert!(!replay.torn);
+
+    let mut reloaded = TodoApp::load_or_new(&path).expect("reload should succeed");
+    assert_eq!(descriptions(&reloaded), vec!["Write the spec"]);
+    let history: Vec<(&str, Outcome)> = reloaded
+        .history()
+        .map(|entry| (entry.todo.description.as_str(), entry.outcome))
+        .collect();
+    assert_eq!(
+        history,
+        vec![
+            ("Old idea", Outcome::Deleted),
+            ("Fix outage", Outcome::Done)
+        ]
+    );
+
+    // Ids handed out through the journal stay reserved.
+    let added = reloaded
+        .add_task("Next".to_string())
+        .expect("add should succeed");
+    assert_eq!(added.id, 4);
+
+    remove_store(&path);
+}
+
+#[test]
+fn compaction_folds_the_journal_into_a_new_snapshot() {
+    let path = temp_store("compact");
+
+    let mut app = TodoApp::load_or_new(&path).expect("load should succeed");
+    app.add_task("Draft".to_string())
+        .expect("add should succeed");
+    for revision in 0..300 {
+        app.edit_description(1, format!("Draft {revision}"))
+            .expect("edit should succeed");
+    }
+
+    // A small store is snapshotted again before its journal gets long.
+    let generation = store::decode(&fs::read(&path).expect("snapshot should exist"))
+        .expect("snapshot should decode")
+        .generation;
+    assert!(generation > 1);
+    let replay = journal::read(&journal_path(&path), generation).expect("journal should read");
+    assert!(replay.ops.len() < 300);
+
+    app.compact().expect("compact should succeed");
+    let snapshot = store::decode(&fs::read(&path).expect("snapshot should exist"))
+        .expect("snapshot should decode");
+    assert_eq!(snapshot.todos[0].description, "Draft 299");
+    assert_eq!(snapshot.generation, generation + 1);
+    assert!(
+        journal::read(&journal_path(&path), snapshot.generation)
+            .expect("journal should read")
+            .ops
+            .is_empty()
+    );
+
+    let reloaded = TodoApp::load_or_new(&path).expect("reload should succeed");
+    assert_eq!(descriptions(&reloaded), vec!["Draft 299"]);
+
+    remove_store(&path);
+}
+
+#[test]
+fn torn_tail_is_ignored_and_rewritten_on_next_write() {
+    let path = temp_store("torn");
+
+    let mut app = TodoApp::load_or_new(&path).expect("load should succeed");
+    app.add_task("Kept".to_string())
+        .expect("add should succeed");
+    app.add_task("Also kept".to_string())
+        .expect("add should succeed");
+
+    // A crash mid-append leaves a length prefix promising more bytes than were written.
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(journal_path(&path))
+        .expect("journal should exist");
+    file.write_all(&[200, 0, 0, 0, 1, 2])
+        .expect("journal should be writable");
+    drop(file);
+
+    let mut reloaded = TodoApp::load_or_new(&path).expect("torn journal should load");
+    assert_eq!(descriptions(&reloaded), vec!["Kept", "Also kept"]);
+
+    reloaded
+        .add_task("After crash".to_string())
+        .expect("add should succeed");
+    let reloaded = TodoApp::load_or_new(&path).expect("reload should succeed");
+    assert_eq!(
+        descriptions(&reloaded),
+        vec!["Kept", "Also kept", "After crash"]
+    );
+
+    remove_store(&path);
+}
+
+#[test]
+fn journal_from_an_older_generation_is_not_replayed() {
+    let path = temp_store("stale");
+
+    let mut app = TodoApp::load_or_new(&path).expect("load should succeed");
+    app.add_task("Only once".to_string())
+        .expect("add should succeed");
+    let snapshot = store::decode(&fs::read(&path).expect("snapshot should exist"))
+        .expect("snapshot should decode");
+
+    // Simulate a crash after the snapshot was replaced but before the journal was reset.
+    journal::reset(&journal_path(&path), snapshot.generation - 1).expect("reset should succeed");
+    journal::append(
+        &journal_path(&path),
+        &[JournalOp::Add(snapshot.todos[0].clone())],
+    )
+    .expect("append should succeed");
+
+    let mut reloaded = TodoApp::load_or_new(&path).expect("reload should succeed");
+    assert_eq!(descriptions(&reloaded), vec!["Only once"]);
+
+    // The stale journal is rewritten before the next change goes in, so that change is kept.
+    reloaded
+        .add_task("After the crash".to_string())
+        .expect("add should succeed");
+    let reloaded = TodoApp::load_or_new(&path).expect("reload should succeed");
+    assert_eq!(
+        descriptions(&reloaded),
+        vec!["Only once", "After the crash"]
+    );
+
+    // Journals written by a different format version are refused rather than misread.
+    let mut bytes = fs::read(journal_path(&path)).expect("journal should exist");
+    bytes[4..6].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    fs::write(journal_path(&path), bytes).expect("journal should be writable");
+    assert!(TodoApp::load_or_new(&path).is_err());
+
+    remove_store(&path);
+}
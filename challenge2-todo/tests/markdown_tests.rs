@@ -0,0 +1,94 @@
+mod common;
+
+use challenge2_todo::dates::{parse_date, parse_due};
+use challenge2_todo::export::SubtaskRecord;
+use challenge2_todo::{ArchivedTodo, ImportMode, TodoApp, markdown};
+use common::{remove_store, temp_store};
+
+const NOW: u64 = 1_800_000_000;
+
+fn subtask(title: &str, done: bool) -> SubtaskRecord {
+    SubtaskRecord {
+        title: title.to_string(),
+        done,
+    }
+}
+
+#[test]
+fn checklists_become_tasks_with_subtasks_and_sections() {
+    let records = markdown::parse(
+        "Notes that are not tasks.\n\
+         - [ ] Loose item @quick\n\
+         - plain bullet\n\
+         \n\
+         ## Work\n\
+         \n\
+         - [ ] (B) Ship release +backend due:2026-11-01\n\
+         \x20 - [x] Write notes\n\
+         \x20 - [ ] Tag\n\
+         * [X] Old thing done:2026-10-02\n\
+         - [x] Undated\n",
+        NOW,
+    );
+    let descriptions: Vec<&str> = records
+        .iter()
+        .map(|record| record.description.as_str())
+        .collect();
+    assert_eq!(
+        descriptions,
+        vec!["Loose item", "Ship release", "Old thing", "Undated"]
+    );
+
+    assert_eq!(records[0].tags, vec!["quick".to_string()]);
+    assert!(records[0].metadata.is_empty());
+
+    let ship = &records[1];
+    assert_eq!(ship.priority, "high");
+    assert_eq!(ship.project.as_deref(), Some("backend"));
+    assert_eq!(ship.due_at, parse_due("2026-11-01", NOW).ok());
+    assert_eq!(
+        ship.subtasks,
+        vec![subtask("Write notes", true), subtask("Tag", false)]
+    );
+    assert_eq!(
+        ship.metadata,
+        vec![("section".to_string(), "## Work".to_string())]
+    );
+    assert_eq!(ship.completed_at, None);
+
+    assert_eq!(records[2].completed_at, parse_date("2026-10-02").ok());
+    assert_eq!(records[3].completed_at, Some(NOW));
+}
+
+#[test]
+fn import_then_export_writes_the_same_checklist() {
+    let file = "\
+- [ ] Loose item @quick
+
+## Work
+
+- [ ] (B) Ship release +backend due:2026-11-01
+  - [x] Write notes
+  - [ ] Tag
+- [x] Old thing done:2026-10-02
+
+### Home
+
+- [ ] (C) Fix sink
+";
+    let file_path = temp_store("round_trip");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let summary = app
+        .import(markdown::parse(file, NOW), ImportMode::Merge)
+        .expect("import should succeed");
+    assert_eq!((summary.added, summary.archived), (3, 1));
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    let mut finished: Vec<&ArchivedTodo> = reloaded.history().collect();
+    finished.reverse();
+    let mut written = Vec::new();
+    markdown::write(reloaded.list_tasks(), finished, &mut written).expect("write should succeed");
+    assert_eq!(String::from_utf8(written).expect("output is utf-8"), file);
+
+    remove_store(&file_path);
+}
@@ -1,4 +1,4 @@
-use challenge2_todo::Queue;
+use challenge2_todo::{PriorityQueue, Queue};
 
 #[test]
 fn queue_is_fifo() {
@@ -29,3 +29,51 @@ fn queue_can_remove_at_logical_index() {
     assert_eq!(queue.dequeue(), Some(40_u8));
     assert!(queue.is_empty());
 }
+
+#[test]
+fn priority_queue_serves_highest_level_first_and_fifo_within_level() {
+    let mut queue = PriorityQueue::new(3);
+    queue.enqueue(0, "low");
+    queue.enqueue(1, "normal-a");
+    queue.enqueue(2, "high");
+    queue.enqueue(1, "normal-b");
+
+    assert_eq!(queue.len(), 4);
+    assert_eq!(queue.peek(), Some(&"high"));
+    assert_eq!(
+        queue.iter().copied().collect::<Vec<_>>(),
+        vec!["high", "normal-a", "normal-b", "low"]
+    );
+    assert_eq!(queue.dequeue(), Some("high"));
+    assert_eq!(queue.dequeue(), Some("normal-a"));
+    assert_eq!(queue.dequeue(), Some("normal-b"));
+    assert_eq!(queue.dequeue(), Some("low"));
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn priority_queue_remove_at_uses_iteration_order() {
+    let mut queue = PriorityQueue::new(2);
+    queue.enqueue(0, 10_u8);
+    queue.enqueue(1, 20_u8);
+    queue.enqueue(0, 30_u8);
+
+    assert_eq!(queue.remove_at(1), Some(10_u8));
+    assert_eq!(queue.remove_at(5), None);
+    assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![20, 30]);
+}
+
+#[test]
+fn queue_insert_at_places_item_at_logical_index() {
+    let mut queue = Queue::new();
+    queue.enqueue(1_u8);
+    queue.enqueue(2_u8);
+    queue.enqueue(3_u8);
+    assert_eq!(queue.dequeue(), Some(1_u8));
+
+    queue.insert_at(1, 9_u8);
+    queue.insert_at(99, 7_u8);
+
+    assert_eq!(queue.iter().copied().collect::<Vec<_>>(), vec![2, 9, 3, 7]);
+    assert_eq!(queue.len(), 4);
+}
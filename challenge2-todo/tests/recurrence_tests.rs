@@ -0,0 +1,114 @@
+use challenge2_todo::Recurrence;
+use challenge2_todo::dates::{parse_date, parse_due};
+
+fn at(input: &str) -> u64 {
+    parse_date(input).expect("fixture date should parse")
+}
+
+fn end_of(day: &str) -> u64 {
+    parse_due(day, 0).expect("fixture day should parse")
+}
+
+#[test]
+fn recurrence_parses_and_round_trips() {
+    let cases = [
+        ("daily", Recurrence::Daily),
+        ("Weekday", Recurrence::Weekdays),
+        ("weekly", Recurrence::EveryDays(7)),
+        ("weekly:mon,thu", Recurrence::Weekly(0b0000_1001)),
+        ("monthly", Recurrence::Monthly(0)),
+        ("Monthly:31", Recurrence::Monthly(31)),
+        ("3d", Recurrence::EveryDays(3)),
+    ];
+    for (input, expected) in cases {
+        let parsed: Recurrence = input.parse().expect("recurrence should parse");
+        assert_eq!(parsed, expected);
+        assert_eq!(parsed.to_string().parse::<Recurrence>(), Ok(parsed));
+    }
+
+    for input in ["0d", "yearly", "weekly:", "mo", "monthly:0", "monthly:32"] {
+        assert!(
+            input.parse::<Recurrence>().is_err(),
+            "'{input}' should not parse"
+        );
+    }
+}
+
+#[test]
+fn next_due_follows_each_rule() {
+    // Friday morning, with the previous instance due at the end of that Friday.
+    let now = at("2026-10-16 09:00");
+    let friday = Some(end_of("2026-10-16"));
+
+    assert_eq!(
+        Recurrence::Daily.next_due(friday, now),
+        Some(end_of("2026-10-17"))
+    );
+    assert_eq!(
+        Recurrence::Weekdays.next_due(friday, now),
+        Some(end_of("2026-10-19"))
+    );
+    assert_eq!(
+        Recurrence::Weekly(0b0000_1001).next_due(friday, now),
+        Some(end_of("2026-10-19"))
+    );
+    assert_eq!(
+        Recurrence::EveryDays(3).next_due(friday, now),
+        Some(end_of("2026-10-19"))
+    );
+    assert_eq!(
+        Recurrence::Monthly(31).next_due(Some(end_of("2026-01-31")), at("2026-01-31 09:00")),
+        Some(end_of("2026-02-28"))
+    );
+}
+
+#[test]
+fn monthly_keeps_its_day_across_short_months() {
+    let rule = Recurrence::Monthly(0).anchored(end_of("2026-01-31"));
+    assert_eq!(rule, Recurrence::Monthly(31));
+    assert_eq!(
+        Recurrence::Daily.anchored(end_of("2026-01-31")),
+        Recurrence::Daily
+    );
+
+    // Jan 31 -> Feb 28 -> Mar 31 -> Apr 30 -> May 31, not drifting to the 28th.
+    let mut due = end_of("2026-01-31");
+    let mut dues = Vec::new();
+    for _ in 0..4 {
+        due = rule
+            .next_due(Some(due), due)
+            .expect("next due should exist");
+        dues.push(due);
+    }
+    assert_eq!(
+        dues,
+        ["2026-02-28", "2026-03-31", "2026-04-30", "2026-05-31"].map(end_of)
+    );
+
+    // A leap year clamps to the 29th.
+    assert_eq!(
+        rule.next_due(Some(end_of("2028-01-31")), at("2028-01-31 09:00")),
+        Some(end_of("2028-02-29"))
+    );
+}
+
+#[test]
+fn next_due_skips_past_missed_instances() {
+    let now = at("2026-10-16 09:00");
+
+    // Overdue by a week: the next instance is due later today, not in the past.
+    assert_eq!(
+        Recurrence::Daily.next_due(Some(end_of("2026-10-09")), now),
+        Some(end_of("2026-10-16"))
+    );
+    assert_eq!(
+        Recurrence::EveryDays(3).next_due(Some(end_of("2026-10-01")), now),
+        Some(end_of("2026-10-16"))
+    );
+
+    // Without a previous due date the series counts from today.
+    assert_eq!(
+        Recurrence::Daily.next_due(None, now),
+        Some(end_of("2026-10-17"))
+    );
+}
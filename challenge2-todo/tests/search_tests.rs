@@ -0,0 +1,98 @@
+mod common;
+
+use challenge2_todo::search::{MatchKind, highlight};
+use challenge2_todo::{NewTask, TodoApp};
+use common::{remove_store, temp_store};
+
+fn found(app: &TodoApp, query: &str) -> Vec<u64> {
+    app.search(query).iter().map(|hit| hit.todo.id).collect()
+}
+
+#[test]
+fn words_match_whole_by_prefix_inside_or_with_typos() {
+    assert_eq!(MatchKind::of("milk", "milk"), Some(MatchKind::Exact));
+    assert_eq!(MatchKind::of("inv", "invoice"), Some(MatchKind::Prefix));
+    assert_eq!(
+        MatchKind::of("voice", "invoice"),
+        Some(MatchKind::Substring)
+    );
+    assert_eq!(MatchKind::of("milkk", "milk"), Some(MatchKind::Fuzzy));
+    assert_eq!(MatchKind::of("meetng", "meetings"), Some(MatchKind::Fuzzy));
+    assert_eq!(
+        MatchKind::of("recieve", "receive"),
+        None,
+        "two typos need 8 letters"
+    );
+    assert_eq!(MatchKind::of("dcotor", "doctor"), None);
+    assert_eq!(
+        MatchKind::of("accomodation", "accommodation"),
+        Some(MatchKind::Fuzzy)
+    );
+    assert_eq!(
+        MatchKind::of("bug", "bag"),
+        None,
+        "short words must match exactly"
+    );
+
+    let text = "Pay Invoice for Café, and call the ÉCOLE";
+    let marked = |query: &str| -> Vec<&str> {
+        highlight(text, query)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    };
+    assert_eq!(marked("voice pay"), vec!["Pay", "voice"]);
+    assert_eq!(marked("café école"), vec!["Café", "ÉCOLE"]);
+    assert_eq!(marked("invoise"), vec!["Invoice"]);
+    assert_eq!(marked("in invoice"), vec!["Invoice"], "overlaps merge");
+    assert!(marked("--").is_empty());
+}
+
+#[test]
+fn search_ranks_pending_and_archived_tasks_and_follows_changes() {
+    let file_path = temp_store("ranks");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    app.add_task("Pay the invoices".to_string())
+        .expect("add should succeed");
+    app.add_task("Send invoice to ACME".to_string())
+        .expect("add should succeed");
+    app.add_task_with(
+        "Call the bank".to_string(),
+        NewTask {
+            project: Some("invoicing".to_string()),
+            ..NewTask::default()
+        },
+    )
+    .expect("add should succeed");
+    app.add_task("Archive old invoice".to_string())
+        .expect("add should succeed");
+    app.complete(4).expect("complete should succeed");
+
+    // Whole words rank above prefixes, and pending tasks above archived ones on a tie.
+    assert_eq!(found(&app, "INVOICE"), vec![2, 4, 1, 3]);
+    assert_eq!(found(&app, "invoice acme"), vec![2]);
+    assert_eq!(found(&app, "invoise"), vec![1, 2, 4], "typos still match");
+    assert!(found(&app, "invoice payroll").is_empty());
+    assert!(found(&app, "  ").is_empty());
+    let hits = app.search("old");
+    assert!(hits[0].archived.is_some());
+
+    app.add_subtask(3, "Ask about the overdraft".to_string())
+        .expect("subtask should be added");
+    assert_eq!(found(&app, "overdraft"), vec![3]);
+    app.edit_description(2, "Send receipt to ACME".to_string())
+        .expect("edit should succeed");
+    assert_eq!(found(&app, "receipt"), vec![2]);
+    assert_eq!(found(&app, "invoice"), vec![4, 1, 3]);
+    app.delete_at(0).expect("delete should succeed");
+    app.undo().expect("undo should succeed");
+    app.undo().expect("undo should succeed");
+    assert_eq!(found(&app, "invoice"), vec![2, 4, 1, 3]);
+
+    // The index is rebuilt from the snapshot and journal on load.
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    assert_eq!(found(&reloaded, "invoice"), vec![2, 4, 1, 3]);
+    assert_eq!(found(&reloaded, "overdraft"), vec![3]);
+
+    remove_store(&file_path);
+}
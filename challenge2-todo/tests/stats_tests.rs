@@ -0,0 +1,69 @@
+mod common;
+
+use challenge2_todo::dates::{Moment, unix_now};
+use challenge2_todo::export::TaskRecord;
+use challenge2_todo::{ImportMode, TodoApp};
+use common::{remove_store, temp_store};
+
+fn created(description: &str, created_at: u64) -> TaskRecord {
+    TaskRecord {
+        created_at: Some(created_at),
+        ..TaskRecord::new(description.to_string())
+    }
+}
+
+#[test]
+fn stats_count_days_backlog_and_completion_time() {
+    let file_path = temp_store("days");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let empty = app.stats(7, unix_now());
+    assert_eq!(empty.days.len(), 7);
+    assert!(
+        empty
+            .added
+            .iter()
+            .chain(&empty.backlog)
+            .all(|count| *count == 0)
+    );
+    assert_eq!(
+        (empty.average_completion, empty.oldest_pending),
+        (None, None)
+    );
+
+    // An hour into each local day, `ago` days before today.
+    let now = unix_now();
+    let day = |ago: u64| Moment::DaysAgo(ago).resolve(now) + 3_600;
+    let records = vec![
+        TaskRecord {
+            completed_at: Some(day(1)),
+            ..created("Done two days in", day(3))
+        },
+        created("Still pending", day(2)),
+        created("Deleted today", day(2)),
+        created("Older than the window", day(40)),
+    ];
+    app.import(records, ImportMode::Merge)
+        .expect("import should succeed");
+    let deleted = app.position(3).expect("task #3 should be pending");
+    app.delete_at(deleted).expect("delete should succeed");
+
+    let stats = app.stats(7, now);
+    assert_eq!(stats.days[6], Moment::DaysAgo(0).resolve(now));
+    assert_eq!(stats.added, vec![0, 0, 0, 1, 2, 0, 0]);
+    // Deleted tasks leave the backlog without counting as completed.
+    assert_eq!(stats.completed, vec![0, 0, 0, 0, 0, 1, 0]);
+    assert_eq!(stats.backlog, vec![1, 1, 1, 2, 4, 3, 2]);
+    assert_eq!(stats.backlog_change(), 1);
+    assert_eq!(stats.average_completion, Some(day(1) - day(3)));
+    assert_eq!(stats.completed_total, 1);
+    assert_eq!(stats.oldest_pending, Some(4));
+
+    // Restoring a task takes its completion back out of the figures.
+    app.restore(1).expect("restore should succeed");
+    let restored = app.stats(7, now);
+    assert!(restored.completed.iter().all(|count| *count == 0));
+    assert_eq!(restored.average_completion, None);
+    assert_eq!(restored.backlog[6], 3);
+
+    remove_store(&file_path);
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use challenge2_todo::TodoApp;
+use challenge2_todo::archive::archive_path;
+use challenge2_todo::journal::journal_path;
+use challenge2_todo::store::{self, StoreLock};
+use challenge2_todo::undo::undo_path;
+use common::{remove_store, temp_store};
+use std::{
+    fs, io,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+
+#[test]
+fn lock_reports_owner_and_is_released_on_drop() {
+    let path = temp_store("lock");
+
+    let lock = StoreLock::try_acquire(&path).expect("first lock should succeed");
+    let error = StoreLock::try_acquire(&path).expect_err("second lock should fail");
+    assert_eq!(error.kind(), io::ErrorKind::WouldBlock);
+    assert!(
+        error
+            .to_string()
+            .contains(&format!("store is locked by pid {}", std::process::id())),
+        "unexpected message: {error}"
+    );
+
+    drop(lock);
+    assert!(!store::lock_path(&path).exists());
+    StoreLock::try_acquire(&path).expect("lock should be free again");
+
+    remove_store(&path);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn stale_lock_from_dead_process_is_taken_over() {
+    let path = temp_store("stale");
+    // Above the kernel's pid_max, so no live process can own it.
+    fs::write(store::lock_path(&path), "4294967295").expect("lock file should be writable");
+
+    let _lock = StoreLock::try_acquire(&path).expect("stale lock should be taken over");
+    let owner = fs::read_to_string(store::lock_path(&path)).expect("lock file should exist");
+    assert_eq!(owner, std::process::id().to_string());
+}
+
+#[test]
+fn lock_is_held_by_one_owner_at_a_time() {
+    let path = temp_store("contended");
+    let holders = AtomicUsize::new(0);
+
+    // Owners remove the lock file on release while others are opening it.
+    thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..25 {
+                    let _lock = StoreLock::acquire(&path).expect("lock should be acquired");
+                    assert_eq!(holders.fetch_add(1, Ordering::SeqCst), 0);
+                    thread::sleep(Duration::from_micros(200));
+                    holders.fetch_sub(1, Ordering::SeqCst);
+                }
+            });
+        }
+    });
+    assert!(!store::lock_path(&path).exists());
+
+    remove_store(&path);
+}
+
+#[test]
+fn writes_from_another_process_are_not_overwritten() {
+    let path = temp_store("conflict");
+
+    let mut terminal_a = TodoApp::load_or_new(&path).expect("load should succeed");
+    let mut terminal_b = TodoApp::load_or_new(&path).expect("load should succeed");
+
+    terminal_a
+        .add_task("From A".to_string())
+        .expect("first writer should succeed");
+    assert!(
+        terminal_b.add_task("From B".to_string()).is_err(),
+        "stale writer must not clobber the store"
+    );
+
+    assert!(
+        terminal_b
+            .reload_if_changed()
+            .expect("reload should succeed")
+    );
+    assert!(
+        !terminal_b
+            .reload_if_changed()
+            .expect("check should succeed")
+    );
+    terminal_b
+        .add_task("From B".to_string())
+        .expect("write after reload should succeed");
+
+    let reloaded = TodoApp::load_or_new(&path).expect("reload should succeed");
+    let descriptions: Vec<&str> = reloaded
+        .list_tasks()
+        .map(|todo| todo.description.as_str())
+        .collect();
+    assert_eq!(descriptions, vec!["From A", "From B"]);
+
+    // Atomic writes leave neither the temp file nor the lock behind.
+    assert!(!store::lock_path(&path).exists());
+    let mut temp = path.clone().into_os_string();
+    temp.push(".tmp");
+    assert!(!Path::new(&temp).exists());
+
+    remove_store(&path);
+}
+
+#[test]
+fn stores_differing_only_by_extension_keep_separate_files() {
+    let base = temp_store("siblings");
+    let one = base.with_extension("one");
+    let two = base.with_extension("two");
+
+    let mut first = TodoApp::load_or_new(&one).expect("load should succeed");
+    for description in ["first", "second", "third"] {
+        first
+            .add_task(description.to_string())
+            .expect("add should succeed");
+    }
+    first.complete(1).expect("complete should succeed");
+    let mut other = TodoApp::load_or_new(&two).expect("load should succeed");
+    other
+        .add_task("other list".to_string())
+        .expect("add should succeed");
+
+    let first = TodoApp::load_or_new(&one).expect("reload should succeed");
+    let pending: Vec<&str> = first
+        .list_tasks()
+        .map(|todo| todo.description.as_str())
+        .collect();
+    assert_eq!(pending, vec!["second", "third"]);
+    assert_eq!(first.history().count(), 1);
+    let other = TodoApp::load_or_new(&two).expect("reload should succeed");
+    assert_eq!(other.undo_labels().count(), 1);
+
+    remove_store(&one);
+    remove_store(&two);
+}
+
+#[test]
+fn siblings_under_their_old_names_are_moved() {
+    let path = temp_store("old_names");
+    let mut app = TodoApp::load_or_new(&path).expect("load should succeed");
+    app.add_task("kept".to_string())
+        .expect("add should succeed");
+    app.complete(1).expect("complete should succeed");
+    app.add_task("journaled".to_string())
+        .expect("add should succeed");
+    drop(app);
+
+    // Older builds replaced the store's extension instead of appending to it.
+    for (current, old) in [
+        (journal_path(&path), path.with_extension("journal")),
+        (archive_path(&path), path.with_extension("archive.bin")),
+        (undo_path(&path), path.with_extension("undo")),
+    ] {
+        fs::rename(current, old).expect("sibling should be renamed");
+    }
+
+    let app = TodoApp::load_or_new(&path).expect("reload should succeed");
+    let pending: Vec<&str> = app
+        .list_tasks()
+        .map(|todo| todo.description.as_str())
+        .collect();
+    assert_eq!(pending, vec!["journaled"]);
+    assert_eq!(app.history().count(), 1);
+    assert!(!path.with_extension("journal").exists());
+    assert!(journal_path(&path).exists());
+
+    remove_store(&path);
+}
+
+#[test]
+fn stores_not_named_bin_leave_look_alike_files_alone() {
+    let path = temp_store("other_extension").with_extension("txt");
+    // Not this store's: older builds only ever wrote `.bin` stores.
+    let unrelated = path.with_extension("journal");
+    fs::write(&unrelated, b"someone else's notes").expect("file should be writable");
+
+    let mut app = TodoApp::load_or_new(&path).expect("load should succeed");
+    app.add_task("kept".to_string())
+        .expect("add should succeed");
+    assert_eq!(
+        fs::read(&unrelated).expect("file should remain"),
+        b"someone else's notes"
+    );
+    assert!(journal_path(&path).exists());
+
+    let _ = fs::remove_file(&unrelated);
+    remove_store(&path);
+}
+
+#[test]
+fn stamps_change_with_every_commit_and_reload() {
+    let path = temp_store("stamps");
+    let mut app = TodoApp::load_or_new(&path).expect("load should succeed");
+    let mut seen = vec![app.stamps()];
+    app.add_task("first".to_string())
+        .expect("add should succeed");
+    seen.push(app.stamps());
+    app.complete(1).expect("complete should succeed");
+    seen.push(app.stamps());
+
+    let mut other = TodoApp::load_or_new(&path).expect("load should succeed");
+    assert_eq!(other.stamps(), app.stamps());
+    other
+        .add_task("from elsewhere".to_string())
+        .expect("add should succeed");
+    assert!(app.reload_if_changed().expect("reload should succeed"));
+    seen.push(app.stamps());
+
+    // Caches keyed on the stamps are refreshed after each of these changes.
+    for (index, stamps) in seen.iter().enumerate() {
+        assert!(!seen[..index].contains(stamps), "stamps {index} repeat");
+    }
+
+    remove_store(&path);
+}
+
+#[test]
+fn failed_write_leaves_the_tasks_as_they_were() {
+    let path = temp_store("failed_write");
+    let mut app = TodoApp::load_or_new(&path).expect("load should succeed");
+    // A directory where the archive's temp file goes makes the first snapshot fail.
+    let mut blocker = archive_path(&path).into_os_string();
+    blocker.push(".tmp");
+    fs::create_dir(&blocker).expect("blocker should be creatable");
+
+    assert!(app.add_task("lost".to_string()).is_err());
+    assert!(app.is_empty());
+    assert_eq!(app.undo_labels().count(), 0);
+
+    fs::remove_dir(&blocker).expect("blocker should be removable");
+    let todo = app
+        .add_task("kept".to_string())
+        .expect("add should succeed");
+    assert_eq!(todo.id, 1);
+    let reloaded = TodoApp::load_or_new(&path).expect("reload should succeed");
+    let pending: Vec<&str> = reloaded
+        .list_tasks()
+        .map(|todo| todo.description.as_str())
+        .collect();
+    assert_eq!(pending, vec!["kept"]);
+    assert_eq!(reloaded.undo_labels().count(), 1);
+
+    remove_store(&path);
+}
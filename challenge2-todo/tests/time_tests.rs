@@ -0,0 +1,179 @@
+mod common;
+
+use challenge2_todo::dates::{format_duration, start_of_local_week};
+use challenge2_todo::export::{TaskRecord, TimeEntryRecord};
+use challenge2_todo::{ImportMode, NewTask, Recurrence, ReportBy, TodoApp};
+use chrono::{Datelike, Local, TimeZone, Weekday};
+use common::{remove_store, temp_store};
+
+fn running(app: &TodoApp) -> Option<u64> {
+    app.running_timer().map(|todo| todo.id)
+}
+
+/// A pending record with stopped time entries given as `(started_at, seconds)`.
+fn tracked(description: &str, entries: &[(u64, u64)]) -> TaskRecord {
+    TaskRecord {
+        time_entries: entries
+            .iter()
+            .map(|&(started_at, seconds)| TimeEntryRecord {
+                started_at,
+                stopped_at: Some(started_at + seconds),
+            })
+            .collect(),
+        ..TaskRecord::new(description.to_string())
+    }
+}
+
+#[test]
+fn one_timer_runs_at_a_time_and_stops_when_its_task_leaves() {
+    let file_path = temp_store("timers");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    app.add_task("Write spec".to_string())
+        .expect("add should succeed");
+    app.add_task_with(
+        "Standup".to_string(),
+        NewTask {
+            recurrence: Some(Recurrence::EveryDays(1)),
+            ..NewTask::default()
+        },
+    )
+    .expect("add should succeed");
+
+    assert!(app.stop_timer().expect("stop should succeed").is_none());
+    app.start_timer(1).expect("start should succeed");
+    app.start_timer(1).expect("starting again changes nothing");
+    assert_eq!(app.get(1).map(|todo| todo.time_entries.len()), Some(1));
+
+    // Starting another timer stops the first, and one undo takes both back.
+    app.start_timer(2).expect("start should succeed");
+    assert_eq!(running(&app), Some(2));
+    assert!(
+        app.get(1)
+            .is_some_and(|todo| todo.running_since().is_none())
+    );
+    assert_eq!(
+        app.undo().expect("undo should succeed").as_deref(),
+        Some("start timer #2: Standup")
+    );
+    assert_eq!(running(&app), Some(1));
+    assert!(app.start_timer(9).expect("start should succeed").is_none());
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    assert_eq!(running(&reloaded), Some(1));
+
+    // Completing a timed task stops its timer; the next instance starts untimed.
+    app.redo().expect("redo should succeed");
+    app.complete(2).expect("complete should succeed");
+    assert_eq!(running(&app), None);
+    let archived = app.history().next().expect("task #2 should be archived");
+    assert!(archived.todo.running_since().is_none());
+    assert_eq!(
+        archived.todo.time_entries[0].stopped_at,
+        Some(archived.completed_at)
+    );
+    let next = app
+        .next_in_series(&archived.todo)
+        .expect("standup should recur");
+    assert!(next.time_entries.is_empty());
+
+    // Undoing the completion brings the task back with its timer running.
+    app.undo().expect("undo should succeed");
+    assert_eq!(running(&app), Some(2));
+    let stopped = app
+        .stop_timer()
+        .expect("stop should succeed")
+        .expect("a timer was running");
+    assert_eq!(stopped.id, 2);
+    assert_eq!(running(&app), None);
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn report_adds_up_time_in_the_period_by_task_tag_or_project() {
+    let file_path = temp_store("report");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let records = vec![
+        TaskRecord {
+            project: Some("backend".to_string()),
+            tags: vec!["urgent".to_string(), "bug".to_string()],
+            ..tracked("Fix login", &[(1_000, 600), (5_000, 1_200)])
+        },
+        TaskRecord {
+            tags: vec!["urgent".to_string()],
+            ..tracked("Write spec", &[(2_000, 3_600)])
+        },
+        TaskRecord {
+            project: Some("backend".to_string()),
+            completed_at: Some(9_000),
+            ..tracked("Deploy", &[(4_000, 300)])
+        },
+        TaskRecord::new("Untimed".to_string()),
+    ];
+    app.import(records, ImportMode::Merge)
+        .expect("import should succeed");
+
+    let by_task = app.time_report(0, 10_000, ReportBy::Task);
+    assert_eq!(
+        by_task,
+        vec![
+            ("#2 Write spec".to_string(), 3_600),
+            ("#1 Fix login".to_string(), 1_800),
+            ("#3 Deploy".to_string(), 300),
+        ]
+    );
+    assert_eq!(
+        app.time_report(0, 10_000, ReportBy::Tag),
+        vec![
+            ("@urgent".to_string(), 5_400),
+            ("@bug".to_string(), 1_800),
+            ("(no tag)".to_string(), 300),
+        ]
+    );
+    assert_eq!(
+        app.time_report(0, 10_000, ReportBy::Project),
+        vec![
+            ("(no project)".to_string(), 3_600),
+            ("+backend".to_string(), 2_100),
+        ]
+    );
+
+    // Entries count only the part inside the period.
+    assert_eq!(
+        app.time_report(5_600, 6_000, ReportBy::Task),
+        vec![("#1 Fix login".to_string(), 400)]
+    );
+    assert!(app.time_report(10_000, 20_000, ReportBy::Tag).is_empty());
+
+    let bad = TaskRecord {
+        time_entries: vec![TimeEntryRecord {
+            started_at: 10,
+            stopped_at: Some(5),
+        }],
+        ..TaskRecord::new("Backwards".to_string())
+    };
+    assert!(app.import(vec![bad], ImportMode::Merge).is_err());
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn durations_and_weeks() {
+    assert_eq!(format_duration(45), "45s");
+    assert_eq!(format_duration(25 * 60 + 59), "25m");
+    assert_eq!(format_duration(3 * 3_600 + 5 * 60), "3h 05m");
+    assert_eq!(format_duration(2 * 86_400 + 4 * 3_600 + 59), "2d 04h");
+
+    let thursday = Local
+        .with_ymd_and_hms(2026, 10, 15, 18, 30, 0)
+        .single()
+        .expect("local time should exist");
+    let start = start_of_local_week(thursday.timestamp() as u64).expect("week should start");
+    let monday = Local
+        .timestamp_opt(start as i64, 0)
+        .single()
+        .expect("start should be a local time");
+    assert_eq!(monday.weekday(), Weekday::Mon);
+    assert_eq!(monday.day(), 12);
+    assert_eq!(monday.format("%H:%M").to_string(), "00:00");
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use challenge2_todo::dates::{parse_date, parse_due};
+use challenge2_todo::{ArchivedTodo, ImportMode, TodoApp, todotxt};
+use common::{remove_store, temp_store};
+
+const NOW: u64 = 1_800_000_000;
+
+fn date(text: &str) -> u64 {
+    parse_date(text).expect("test date should parse")
+}
+
+fn pair(key: &str, value: &str) -> (String, String) {
+    (key.to_string(), value.to_string())
+}
+
+#[test]
+fn lines_map_priority_projects_contexts_and_keys() {
+    let records = todotxt::parse(
+        "(A) 2026-10-01 Call Mom +family @phone @Home due:2026-10-20 t:2026-10-15\n\
+         \n\
+         (C) Read https://example.com/post at 10:30 rec:weekly:mon,thu due:someday\n\
+         x 2026-10-18 2026-10-10 Paid rent +home pri:B\n\
+         (F) Sort +old +photos\n",
+        NOW,
+    );
+    assert_eq!(records.len(), 4);
+
+    let call = &records[0];
+    assert_eq!(call.description, "Call Mom");
+    assert_eq!(call.priority, "urgent");
+    assert_eq!(call.created_at, Some(date("2026-10-01")));
+    assert_eq!(call.project.as_deref(), Some("family"));
+    assert_eq!(call.tags, vec!["phone".to_string(), "Home".to_string()]);
+    assert_eq!(call.due_at, parse_due("2026-10-20", NOW).ok());
+    assert_eq!(call.metadata, vec![pair("t", "2026-10-15")]);
+    assert_eq!(call.completed_at, None);
+
+    // URLs and times are text; an unparsable `due:` is kept rather than dropped.
+    let read = &records[1];
+    assert_eq!(read.description, "Read https://example.com/post at 10:30");
+    assert_eq!(read.priority, "normal");
+    assert_eq!(read.created_at, None);
+    assert_eq!(read.recurrence.as_deref(), Some("weekly:mon,thu"));
+    assert_eq!(read.due_at, None);
+    assert_eq!(
+        read.metadata,
+        vec![pair("due", "someday"), pair("pri", "C")]
+    );
+
+    let rent = &records[2];
+    assert_eq!(rent.description, "Paid rent");
+    assert_eq!(rent.priority, "high");
+    assert_eq!(rent.completed_at, Some(date("2026-10-18")));
+    assert_eq!(rent.created_at, Some(date("2026-10-10")));
+    assert!(rent.metadata.is_empty());
+
+    // The last project wins, as in `todo add`; earlier ones stay in the text.
+    let sort = &records[3];
+    assert_eq!(sort.description, "Sort +old");
+    assert_eq!(sort.project.as_deref(), Some("photos"));
+    assert_eq!(sort.priority, "low");
+    assert_eq!(sort.metadata, vec![pair("pri", "F")]);
+
+    // A finished task without a completion date is finished now.
+    let bare = todotxt::parse("x Old chore", NOW);
+    assert_eq!(bare[0].description, "Old chore");
+    assert_eq!(bare[0].completed_at, Some(NOW));
+}
+
+#[test]
+fn import_then_export_writes_the_same_file() {
+    let file = "\
+(A) 2026-10-01 Call Mom +family @phone due:2026-10-20 t:2026-10-15
+(C) 2026-10-02 Read article rec:weekly:mon,thu due:someday
+2026-10-03 Plain task
+(E) 2026-09-01 Someday maybe +old +photos
+x 2026-10-18 2026-10-10 Paid rent +home pri:B
+";
+    let file_path = temp_store("round_trip");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let summary = app
+        .import(todotxt::parse(file, NOW), ImportMode::Merge)
+        .expect("import should succeed");
+    assert_eq!((summary.added, summary.archived), (4, 1));
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    let mut finished: Vec<&ArchivedTodo> = reloaded.history().collect();
+    finished.reverse();
+    let mut written = Vec::new();
+    todotxt::write(reloaded.list_tasks(), finished, &mut written).expect("write should succeed");
+
+    // Tasks come out in queue order, so the urgent task stays first and the low one last.
+    let expected: Vec<&str> = vec![
+        "(A) 2026-10-01 Call Mom +family @phone due:2026-10-20 t:2026-10-15",
+        "(C) 2026-10-02 Read article rec:weekly:mon,thu due:someday",
+        "2026-10-03 Plain task",
+        "(E) 2026-09-01 Someday maybe +old +photos",
+        "x 2026-10-18 2026-10-10 Paid rent +home pri:B",
+    ];
+    let written = String::from_utf8(written).expect("output is utf-8");
+    assert_eq!(written.lines().collect::<Vec<&str>>(), expected);
+
+    remove_store(&file_path);
+}
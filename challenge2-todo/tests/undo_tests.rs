@@ -0,0 +1,268 @@
+mod common;
+
+use challenge2_todo::app::DEFAULT_UNDO_DEPTH;
+use challenge2_todo::export;
+use challenge2_todo::undo::{self, undo_path};
+use challenge2_todo::{ImportMode, NewTask, Outcome, Priority, TodoApp};
+use common::{remove_store, temp_store};
+use std::fs;
+
+/// Pending tasks in list order and archived tasks by id, enough to tell any two states apart.
+type State = (
+    Vec<(u64, String, Priority)>,
+    Vec<(u64, String, Outcome, u64)>,
+);
+
+fn state(app: &TodoApp) -> State {
+    let pending = app
+        .list_tasks()
+        .map(|todo| (todo.id, todo.description.clone(), todo.priority))
+        .collect();
+    let mut archived: Vec<(u64, String, Outcome, u64)> = app
+        .history()
+        .map(|entry| {
+            (
+                entry.todo.id,
+                entry.todo.description.clone(),
+                entry.outcome,
+                entry.completed_at,
+            )
+        })
+        .collect();
+    archived.sort_by_key(|entry| entry.0);
+    (pending, archived)
+}
+
+#[test]
+fn every_change_can_be_undone_and_redone_in_place() {
+    let file_path = temp_store("steps");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let mut states = vec![state(&app)];
+    let mut labels = Vec::new();
+
+    let mut step = |app: &mut TodoApp, change: &dyn Fn(&mut TodoApp)| {
+        change(app);
+        states.push(state(app));
+        labels.push(
+            app.undo_labels()
+                .next()
+                .expect("change is undoable")
+                .to_string(),
+        );
+    };
+    for description in ["First", "Second", "Third", "Fourth"] {
+        step(&mut app, &|app| {
+            app.add_task(description.to_string())
+                .expect("add should succeed");
+        });
+    }
+    step(&mut app, &|app| {
+        app.set_priority_at(2, Priority::High)
+            .expect("priority should change");
+    });
+    step(&mut app, &|app| {
+        app.edit_description(2, "Second, edited".to_string())
+            .expect("edit should succeed");
+    });
+    step(&mut app, &|app| {
+        app.move_to(4, 1).expect("move should succeed");
+    });
+    step(&mut app, &|app| {
+        app.delete_at(3).expect("delete should succeed");
+    });
+    step(&mut app, &|app| {
+        app.complete_next().expect("complete should succeed");
+    });
+    step(&mut app, &|app| {
+        app.restore(3).expect("restore should succeed");
+    });
+    step(&mut app, &|app| {
+        app.add_task_with(
+            "Water plants".to_string(),
+            NewTask {
+                recurrence: Some("daily".parse().expect("rule should parse")),
+                ..NewTask::default()
+            },
+        )
+        .expect("add should succeed");
+    });
+    step(&mut app, &|app| {
+        app.complete(5).expect("complete should succeed");
+    });
+    step(&mut app, &|app| {
+        let records = export::parse_json(r#"[{"id": 1, "description": "Only one"}]"#)
+            .expect("records should parse");
+        app.import(records, ImportMode::Replace)
+            .expect("import should succeed");
+    });
+    assert_eq!(labels[0], "add #1: First");
+    assert_eq!(labels[7], "delete #2: Second, edited");
+    assert_eq!(labels[12], "import of 4 task(s)");
+
+    // Undo walks back through every state, in a new session halfway through.
+    let last = states.len() - 1;
+    for expected in states[..last].iter().rev().take(6) {
+        app.undo().expect("undo should succeed");
+        assert_eq!(&state(&app), expected);
+    }
+    let mut app = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    for expected in states[..last - 6].iter().rev() {
+        app.undo().expect("undo should succeed");
+        assert_eq!(&state(&app), expected);
+    }
+    assert_eq!(app.undo().expect("undo should succeed"), None);
+
+    for (expected, label) in states[1..].iter().zip(&labels) {
+        assert_eq!(
+            app.redo().expect("redo should succeed").as_ref(),
+            Some(label)
+        );
+        assert_eq!(&state(&app), expected);
+    }
+    assert_eq!(app.redo().expect("redo should succeed"), None);
+
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    assert_eq!(state(&reloaded), states[last]);
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn history_is_bounded_and_new_changes_drop_redo() {
+    let file_path = temp_store("depth");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    app.set_undo_depth(2);
+    for description in ["One", "Two", "Three"] {
+        app.add_task(description.to_string())
+            .expect("add should succeed");
+    }
+    assert_eq!(
+        app.undo_labels().collect::<Vec<&str>>(),
+        vec!["add #3: Three", "add #2: Two"]
+    );
+
+    assert_eq!(
+        app.undo().expect("undo should succeed").as_deref(),
+        Some("add #3: Three")
+    );
+    app.undo().expect("undo should succeed");
+    assert_eq!(app.undo().expect("undo should succeed"), None);
+    assert_eq!(app.len(), 1);
+
+    // Redo survives a restart, until a new change replaces it.
+    let mut app = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    assert_eq!(
+        app.redo().expect("redo should succeed").as_deref(),
+        Some("add #2: Two")
+    );
+    let todo = app
+        .add_task("Four".to_string())
+        .expect("add should succeed");
+    assert_eq!(todo.id, 4, "ids of undone tasks are not reused");
+    assert_eq!(app.redo().expect("redo should succeed"), None);
+
+    app.set_undo_depth(0);
+    app.add_task("Five".to_string())
+        .expect("add should succeed");
+    assert_eq!(app.undo().expect("undo should succeed"), None);
+    assert_eq!(app.len(), 4);
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn depth_above_the_default_survives_a_restart() {
+    let file_path = temp_store("deep");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    app.set_undo_depth(300);
+    for index in 0..150 {
+        app.add_task(format!("Task {index}"))
+            .expect("add should succeed");
+    }
+
+    // The depth is applied after loading, so nothing past the default is lost first.
+    let mut app = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    app.set_undo_depth(300);
+    assert_eq!(app.undo_labels().count(), 150);
+    app.reload().expect("reload should succeed");
+    assert_eq!(app.undo_depth(), 300);
+    assert_eq!(app.undo_labels().count(), 150);
+
+    // Without a configured depth the next change trims to the default.
+    let mut app = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    app.add_task("One more".to_string())
+        .expect("add should succeed");
+    assert_eq!(app.undo_labels().count(), DEFAULT_UNDO_DEPTH);
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn undo_refuses_a_step_whose_task_has_moved_on() {
+    let file_path = temp_store("stale");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    app.add_task("Ship it".to_string())
+        .expect("add should succeed");
+    app.complete(1).expect("complete should succeed");
+
+    // Leave only the add in the history, as if the completion had been trimmed from it.
+    let mut log = undo::load(&undo_path(&file_path))
+        .expect("history should load")
+        .log;
+    log.undo.pop();
+    undo::save(&undo_path(&file_path), &log).expect("history should save");
+    let mut app = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    let before = state(&app);
+
+    let error = app.undo().expect_err("the task is no longer pending");
+    assert!(
+        error.to_string().contains("cannot undo add #1: Ship it"),
+        "{error}"
+    );
+    assert_eq!(state(&app), before);
+    assert_eq!(
+        app.undo_labels().collect::<Vec<_>>(),
+        vec!["add #1: Ship it"]
+    );
+    let reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    assert_eq!(state(&reloaded), before);
+
+    remove_store(&file_path);
+}
+
+#[test]
+fn later_steps_are_appended_without_rewriting_a_large_one() {
+    let file_path = temp_store("append");
+    let mut app = TodoApp::load_or_new(&file_path).expect("load should succeed");
+    let json = format!(
+        "[{}]",
+        (0..500)
+            .map(|index| format!(r#"{{"description": "Imported task number {index}"}}"#))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+    let records = export::parse_json(&json).expect("records should parse");
+    app.import(records, ImportMode::Merge)
+        .expect("import should succeed");
+    let before = fs::read(undo_path(&file_path)).expect("history should exist");
+
+    app.edit_description(1, "Renamed".to_string())
+        .expect("edit should succeed");
+    // The import's step stays where it is; only the edit is added after it.
+    let after = fs::read(undo_path(&file_path)).expect("history should exist");
+    assert!(after.starts_with(&before));
+    assert!(after.len() - before.len() < 1_000);
+
+    let mut reloaded = TodoApp::load_or_new(&file_path).expect("reload should succeed");
+    assert_eq!(
+        reloaded.undo_labels().collect::<Vec<&str>>(),
+        app.undo_labels().collect::<Vec<&str>>()
+    );
+    reloaded.undo().expect("undo should succeed");
+    assert_eq!(
+        reloaded.get(1).map(|todo| todo.description.as_str()),
+        Some("Imported task number 0")
+    );
+
+    remove_store(&file_path);
+}